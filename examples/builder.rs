@@ -1,7 +1,5 @@
-use frcrs::input::{Gamepad, Joystick};
-use frcrs::{observe_user_program_starting, refresh_data};
-use std::sync::{Arc, Mutex};
-use std::thread::sleep;
+use frcrs::input::{Bindings, Buttons, Gamepad};
+use frcrs::{observe_user_program_starting, refresh_data, TaskManager};
 use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::task::LocalSet;
@@ -12,28 +10,37 @@ fn main() {
     let local = LocalSet::new();
 
     let controller = local.run_until(async {
-        let mut joystick = Gamepad::new(1);
+        let joystick = Gamepad::new(1);
+        let mut bindings = Bindings::new();
+        let mut tasks = TaskManager::new();
 
-        observe_user_program_starting();
-
-        loop {
-            refresh_data();
-
-            // if joystick.a() {
-            //     println!("A pressed");
-            // }
+        // if joystick.a() {
+        //     println!("A pressed");
+        // }
 
-            joystick.clone().while_held(1, &local, || async {
+        joystick
+            .trigger(Buttons::A)
+            .while_true(&mut bindings, || async {
                 println!("Button 1 pressed");
                 tokio::time::sleep(Duration::from_secs(1)).await;
                 println!("Button 1 finished");
             });
 
-            joystick.clone().while_held(2, &local, || async {
+        joystick
+            .trigger(Buttons::B)
+            .while_true(&mut bindings, || async {
                 println!("Button 2 pressed");
                 tokio::time::sleep(Duration::from_secs(1)).await;
                 println!("Button 2 finished");
             });
+
+        observe_user_program_starting();
+
+        loop {
+            refresh_data();
+            bindings.poll(&mut tasks);
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
         }
     });
 
@@ -25,19 +25,19 @@ impl Robot for MyRobot {
         println!("Robot init");
     }
 
-    fn disabled_init(&mut self) {
+    async fn disabled_init(&mut self) {
         println!("Disabled init");
     }
 
-    fn autonomous_init(&mut self) {
+    async fn autonomous_init(&mut self) {
         println!("Autonomous init");
     }
 
-    fn teleop_init(&mut self) {
+    async fn teleop_init(&mut self) {
         println!("Teleop init");
     }
 
-    fn test_init(&mut self) {
+    async fn test_init(&mut self) {
         println!("Test init");
     }
 
@@ -57,9 +57,9 @@ impl Robot for MyRobot {
         };
 
         if self.gamepad.left_bumper() {
-            self.task_manager.run_task(task);
+            self.task_manager.run_task("print", task);
         } else {
-            self.task_manager.abort_task(task);
+            self.task_manager.abort_task("print");
         }
     }
 
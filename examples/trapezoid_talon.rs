@@ -44,7 +44,8 @@ fn main() {
             let state = RobotState::get();
 
             if state.enabled() && state.teleop() {
-                let (desired_position, desired_velocity) = profile.update(dt.as_secs_f64());
+                let desired = profile.update(dt.as_secs_f64());
+                let (desired_position, desired_velocity) = (desired.position, desired.velocity);
 
                 // The PID controller computes the output (percent output, e.g., -1.0 to 1.0)
                 let pid_output =
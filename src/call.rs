@@ -37,6 +37,9 @@ macro_rules! call {
 
 // this one only offers a performance benefit if you construct in a loop,
 // the intent is just to homogenize the api
+// every constructor in the crate now goes through `try_create!`, this stays for code that
+// would rather panic
+#[allow(unused_macros)]
 macro_rules! create {
     ($path:tt, $sig:tt, $args:expr) => {{
         use crate::java;
@@ -53,6 +56,82 @@ macro_rules! create {
     }};
 }
 
+/// Like [`call_static!`], but returns a `Result` instead of panicking
+macro_rules! try_call_static {
+    ($path:tt, $method:tt, $sig:tt, $args:expr, $ret:expr) => {
+        (|| -> Result<jni::objects::JValueOwned<'static>, crate::error::FrcError> {
+            use crate::error::{check, check_class, check_method};
+            use crate::try_java;
+            use jni::objects::{JClass, JStaticMethodID};
+            use once_cell::sync::OnceCell;
+            static FNPTR: OnceCell<JStaticMethodID> = OnceCell::new();
+            static CLASS: OnceCell<JClass> = OnceCell::new();
+            let mut java = try_java()?;
+            let class = CLASS.get_or_try_init(|| {
+                let class = java.find_class($path);
+                check_class(&mut java, $path, class)
+            })?;
+            let fnptr = FNPTR.get_or_try_init(|| {
+                let fnptr = java.get_static_method_id(class, $method, $sig);
+                check_method(&mut java, $path, $method, $sig, fnptr)
+            })?;
+
+            let result = unsafe { java.call_static_method_unchecked(class, fnptr, $ret, $args) };
+            check(&mut java, result)
+        })()
+    };
+}
+
+/// Like [`call!`], but returns a `Result` instead of panicking
+macro_rules! try_call {
+    ($obj:expr, $path:tt, $method:tt, $sig:tt, $args:expr, $ret:expr) => {
+        (|| -> Result<jni::objects::JValueOwned<'static>, crate::error::FrcError> {
+            use crate::error::{check, check_class, check_method};
+            use crate::try_java;
+            use jni::objects::JMethodID;
+            use once_cell::sync::OnceCell;
+            static FNPTR: OnceCell<JMethodID> = OnceCell::new();
+            let mut java = try_java()?;
+            let fnptr = FNPTR.get_or_try_init(|| {
+                let class = java.find_class($path);
+                let class = check_class(&mut java, $path, class)?;
+                let fnptr = java.get_method_id(class, $method, $sig);
+                check_method(&mut java, $path, $method, $sig, fnptr)
+            })?;
+
+            let result = unsafe { java.call_method_unchecked($obj, fnptr, $ret, $args) };
+            check(&mut java, result)
+        })()
+    };
+}
+
+/// Like [`create!`], but returns a `Result` instead of panicking
+macro_rules! try_create {
+    ($path:tt, $sig:tt, $args:expr) => {
+        (|| -> Result<jni::objects::GlobalRef, crate::error::FrcError> {
+            use crate::error::{check, check_class, check_method};
+            use crate::try_java;
+            use jni::objects::{JClass, JMethodID};
+            use once_cell::sync::OnceCell;
+            static FNPTR: OnceCell<JMethodID> = OnceCell::new();
+            static CLASS: OnceCell<JClass> = OnceCell::new();
+            let mut java = try_java()?;
+            let class = CLASS.get_or_try_init(|| {
+                let class = java.find_class($path);
+                check_class(&mut java, $path, class)
+            })?;
+            let fnptr = FNPTR.get_or_try_init(|| {
+                let fnptr = java.get_method_id(class, "<init>", $sig);
+                check_method(&mut java, $path, "<init>", $sig, fnptr)
+            })?;
+
+            let obj = unsafe { java.new_object_unchecked(class, *fnptr, $args) };
+            let obj = check(&mut java, obj)?;
+            Ok(java.new_global_ref(obj)?)
+        })()
+    };
+}
+
 macro_rules! once {
     ($code:expr) => {{
         static ONCE: OnceCell<JObject> = OnceCell::new();
@@ -61,4 +140,4 @@ macro_rules! once {
     }};
 }
 
-pub(crate) use {call, call_static, create, once};
+pub(crate) use {call, call_static, create, once, try_call, try_call_static, try_create};
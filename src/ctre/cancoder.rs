@@ -1,7 +1,11 @@
-use crate::call::{call, call_static, create};
-use crate::java;
+use crate::call::{call, call_static, try_create};
+use crate::error::FrcError;
+use crate::hardware::AbsoluteEncoder;
+use crate::try_java;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
+use uom::si::angle::revolution;
+use uom::si::f64::Angle;
 
 pub struct CanCoder {
     instance: GlobalRef,
@@ -9,22 +13,24 @@ pub struct CanCoder {
 
 impl CanCoder {
     pub fn new(id: i32, can_loop: Option<String>) -> Self {
-        let string = java()
-            .new_string(can_loop.unwrap_or("rio".to_string()))
-            .unwrap();
+        Self::try_new(id, can_loop).unwrap()
+    }
+
+    /// Like [`CanCoder::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32, can_loop: Option<String>) -> Result<Self, FrcError> {
+        let string = try_java()?.new_string(can_loop.unwrap_or("rio".to_string()))?;
 
-        let instance = create!(
+        let instance = try_create!(
             "com/ctre/phoenix6/hardware/core/CoreCANcoder",
             "(ILjava/lang/String;)V",
             &[
                 JValue::Int(id).as_jni(),
-                JValue::Object(&JObject::from_raw(string.into_raw())).as_jni()
+                JValue::Object(&JObject::from(string)).as_jni()
             ]
-        );
-
-        let instance = java().new_global_ref(instance).unwrap();
+        )
+        .map_err(FrcError::device("CANcoder", id))?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
 
     /*pub fn get(&self) -> f64 {
@@ -61,3 +67,9 @@ impl CanCoder {
         .unwrap()
     }
 }
+
+impl AbsoluteEncoder for CanCoder {
+    fn absolute_position(&mut self) -> Angle {
+        Angle::new::<revolution>(self.get_absolute())
+    }
+}
@@ -1,5 +1,6 @@
-use crate::call::{call, call_static, create};
-use crate::java;
+use crate::call::{call, call_static, try_create};
+use crate::error::FrcError;
+use crate::try_java;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
 use jni::sys::jboolean;
@@ -12,22 +13,24 @@ pub struct CanRange {
 
 impl CanRange {
     pub fn new(id: i32, can_loop: Option<String>) -> Self {
-        let string = java()
-            .new_string(can_loop.unwrap_or("rio".to_string()))
-            .unwrap();
+        Self::try_new(id, can_loop).unwrap()
+    }
+
+    /// Like [`CanRange::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32, can_loop: Option<String>) -> Result<Self, FrcError> {
+        let string = try_java()?.new_string(can_loop.unwrap_or("rio".to_string()))?;
 
-        let instance = create!(
+        let instance = try_create!(
             "com/ctre/phoenix6/hardware/core/CoreCANrange",
             "(ILjava/lang/String;)V",
             &[
                 JValue::Int(id).as_jni(),
-                JValue::Object(&JObject::from_raw(string.into_raw())).as_jni()
+                JValue::Object(&JObject::from(string)).as_jni()
             ]
-        );
-
-        let instance = java().new_global_ref(instance).unwrap();
+        )
+        .map_err(FrcError::device("CANrange", id))?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
     pub fn get_distance(&self) -> Length {
         let status_signal = call!(
@@ -1,8 +1,12 @@
-use crate::call::{call, call_static, create};
-use crate::java;
+use crate::call::{call, call_static, try_create};
+use crate::error::FrcError;
+use crate::hardware::Gyro;
+use crate::try_java;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
 use nalgebra::{Rotation3, Vector3};
+use uom::si::angle::degree;
+use uom::si::f64::Angle;
 
 pub struct Pigeon {
     instance: GlobalRef,
@@ -10,20 +14,24 @@ pub struct Pigeon {
 
 impl Pigeon {
     pub fn new(id: i32, can: Option<String>) -> Self {
-        let string = java().new_string(can.unwrap_or("rio".to_string())).unwrap();
+        Self::try_new(id, can).unwrap()
+    }
+
+    /// Like [`Pigeon::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32, can: Option<String>) -> Result<Self, FrcError> {
+        let string = try_java()?.new_string(can.unwrap_or("rio".to_string()))?;
 
-        let instance = create!(
+        let instance = try_create!(
             "com/ctre/phoenix6/hardware/Pigeon2",
             "(ILjava/lang/String;)V",
             &[
                 JValue::Int(id).as_jni(),
                 JValue::Object(&JObject::from(string)).as_jni()
             ]
-        );
+        )
+        .map_err(FrcError::device("Pigeon2", id))?;
 
-        Self {
-            instance: java().new_global_ref(instance).unwrap(),
-        }
+        Ok(Self { instance })
     }
 
     pub fn get_angle(&self) -> f64 {
@@ -121,3 +129,14 @@ impl Pigeon {
         );
     }
 }
+
+impl Gyro for Pigeon {
+    fn heading(&mut self) -> Angle {
+        // getAngle is clockwise positive
+        Angle::new::<degree>(-self.get_angle())
+    }
+
+    fn reset(&mut self) {
+        Pigeon::reset(self);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::call::{call, call_static, create};
+use crate::call::{call, call_static, try_create};
+use crate::error::FrcError;
+use crate::hardware::MotorController;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::Primitive::Void;
 use jni::signature::ReturnType;
@@ -43,13 +45,19 @@ impl SRX {
     /// let motor = SRX::new(1);
     /// ```
     pub fn new(id: i32) -> Self {
-        let instance = create!(
+        Self::try_new(id).unwrap()
+    }
+
+    /// Like [`SRX::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32) -> Result<Self, FrcError> {
+        let instance = try_create!(
             "com/ctre/phoenix/motorcontrol/can/TalonSRX",
             "(I)V",
             &[JValue::Int(id).as_jni()]
-        );
+        )
+        .map_err(FrcError::device("TalonSRX", id))?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
 
     /// Sets the output of the `TalonSRX` motor controller.
@@ -91,3 +99,13 @@ impl SRX {
         );
     }
 }
+
+impl MotorController for SRX {
+    fn set_percent(&mut self, percent: f64) {
+        self.set(percent);
+    }
+
+    fn stop(&mut self) {
+        self.set(0.);
+    }
+}
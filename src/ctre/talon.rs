@@ -1,8 +1,13 @@
-use crate::call::{call, call_static, create};
-use crate::java;
+use crate::call::{call, call_static, try_call, try_call_static, try_create};
+use crate::error::FrcError;
+use crate::hardware::{Encoder, MotorController};
+use crate::try_java;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
 use jni::sys::jboolean;
+use uom::si::angle::revolution;
+use uom::si::angular_velocity::revolution_per_second;
+use uom::si::f64::{Angle, AngularVelocity};
 
 pub enum ControlMode {
     Percent,
@@ -45,22 +50,24 @@ impl Talon {
     /// let talon = Talon::new(1, Some("can0".to_string()));
     /// ```
     pub fn new(id: i32, can_bus: Option<String>) -> Self {
-        let string = java()
-            .new_string(can_bus.unwrap_or("rio".to_string()))
-            .unwrap();
+        Self::try_new(id, can_bus).unwrap()
+    }
+
+    /// Like [`Talon::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32, can_bus: Option<String>) -> Result<Self, FrcError> {
+        let string = try_java()?.new_string(can_bus.unwrap_or("rio".to_string()))?;
 
-        let instance = create!(
+        let instance = try_create!(
             "com/ctre/phoenix6/hardware/TalonFX",
             "(ILjava/lang/String;)V",
             &[
                 JValue::Int(id).as_jni(),
                 JValue::Object(&JObject::from(string)).as_jni()
             ]
-        );
-
-        let instance = java().new_global_ref(instance).unwrap();
+        )
+        .map_err(FrcError::device("TalonFX", id))?;
 
-        Self { instance, id }
+        Ok(Self { instance, id })
     }
 
     /// Sets the output for the motor controller.
@@ -74,90 +81,97 @@ impl Talon {
     /// talon.set(ControlMode::Percent, 0.5);
     /// ```
     pub fn set(&self, control_mode: ControlMode, amount: f64) {
+        self.try_set(control_mode, amount).unwrap()
+    }
+
+    /// Like [`Talon::set`], but returns an error instead of panicking
+    pub fn try_set(&self, control_mode: ControlMode, amount: f64) -> Result<(), FrcError> {
         match control_mode {
             ControlMode::Percent => {
-                let control = create!(
+                let control = try_create!(
                     "com/ctre/phoenix6/controls/DutyCycleOut",
                     "(D)V",
                     &[JValue::Double(amount).as_jni()]
-                );
+                )?;
 
-                call!(
+                try_call!(
                     self.instance.as_obj(),
                     "com/ctre/phoenix6/hardware/core/CoreTalonFX",
                     "setControl",
                     "(Lcom/ctre/phoenix6/controls/DutyCycleOut;)Lcom/ctre/phoenix6/StatusCode;",
                     &[JValue::Object(&control.as_obj()).as_jni()],
                     ReturnType::Object
-                )
-                .l()
-                .unwrap();
+                )?;
             }
             ControlMode::Position => {
-                let control = create!(
+                let control = try_create!(
                     "com/ctre/phoenix6/controls/PositionDutyCycle",
                     "(D)V",
                     &[JValue::Double(amount).as_jni()]
-                );
+                )?;
 
-                call!(
+                try_call!(
                     self.instance.as_obj(),
                     "com/ctre/phoenix6/hardware/core/CoreTalonFX",
                     "setControl",
                     "(Lcom/ctre/phoenix6/controls/PositionDutyCycle;)Lcom/ctre/phoenix6/StatusCode;",
                     &[JValue::Object(&control.as_obj()).as_jni()],
                     ReturnType::Object
-                ).l().unwrap();
+                )?;
             }
             ControlMode::MotionMagic => {
-                let control = create!(
+                let control = try_create!(
                     "com/ctre/phoenix6/controls/MotionMagicDutyCycle",
                     "(D)V",
                     &[JValue::Double(amount).as_jni()]
-                );
+                )?;
 
-                call!(
+                try_call!(
                     self.instance.as_obj(),
                     "com/ctre/phoenix6/hardware/core/CoreTalonFX",
                     "setControl",
                     "(Lcom/ctre/phoenix6/controls/MotionMagicDutyCycle;)Lcom/ctre/phoenix6/StatusCode;",
                     &[JValue::Object(&control.as_obj()).as_jni()],
                     ReturnType::Object
-                ).l().unwrap();
+                )?;
             }
             ControlMode::Velocity => {
-                let control = create!(
+                let control = try_create!(
                     "com/ctre/phoenix6/controls/VelocityDutyCycle",
                     "(D)V",
                     &[JValue::Double(amount).as_jni()]
-                );
+                )?;
 
-                call!(
+                try_call!(
                     self.instance.as_obj(),
                     "com/ctre/phoenix6/hardware/core/CoreTalonFX",
                     "setControl",
                     "(Lcom/ctre/phoenix6/controls/VelocityDutyCycle;)Lcom/ctre/phoenix6/StatusCode;",
                     &[JValue::Object(&control.as_obj()).as_jni()],
                     ReturnType::Object
-                )
-                .l()
-                .unwrap();
+                )?;
             }
         };
+
+        Ok(())
     }
 
     /// Stop the motor
     pub fn stop(&self) {
-        call!(
+        self.try_stop().unwrap()
+    }
+
+    /// Like [`Talon::stop`], but returns an error instead of panicking
+    pub fn try_stop(&self) -> Result<(), FrcError> {
+        Ok(try_call!(
             self.instance.as_obj(),
             "com/ctre/phoenix6/hardware/TalonFX",
             "stopMotor",
             "()V",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Void)
-        )
-        .v()
-        .unwrap()
+        )?
+        .v()?)
     }
 
     /// Retrieves the current velocity of the motor.
@@ -170,26 +184,29 @@ impl Talon {
     /// let velocity = talon.get_velocity();
     /// ```
     pub fn get_velocity(&self) -> f64 {
-        let status_signal = call!(
+        self.try_get_velocity().unwrap()
+    }
+
+    /// Like [`Talon::get_velocity`], but returns an error instead of panicking
+    pub fn try_get_velocity(&self) -> Result<f64, FrcError> {
+        let status_signal = try_call!(
             self.instance.as_obj(),
             "com/ctre/phoenix6/hardware/core/CoreTalonFX",
             "getVelocity",
             "()Lcom/ctre/phoenix6/StatusSignal;",
             &Vec::new(),
             ReturnType::Object
-        )
-        .l()
-        .unwrap();
+        )?
+        .l()?;
 
-        call_static!(
+        Ok(try_call_static!(
             "frc/robot/Wrapper",
             "getValue",
             "(Lcom/ctre/phoenix6/StatusSignal;)D",
             &[JValue::Object(&status_signal).as_jni()],
             ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        )?
+        .d()?)
     }
 
     /// Retrieves the current position of the motor.
@@ -202,26 +219,29 @@ impl Talon {
     /// let position = talon.get_position();
     /// ```
     pub fn get_position(&self) -> f64 {
-        let status_signal = call!(
+        self.try_get_position().unwrap()
+    }
+
+    /// Like [`Talon::get_position`], but returns an error instead of panicking
+    pub fn try_get_position(&self) -> Result<f64, FrcError> {
+        let status_signal = try_call!(
             self.instance.as_obj(),
             "com/ctre/phoenix6/hardware/core/CoreTalonFX",
             "getPosition",
             "()Lcom/ctre/phoenix6/StatusSignal;",
             &Vec::new(),
             ReturnType::Object
-        )
-        .l()
-        .unwrap();
+        )?
+        .l()?;
 
-        call_static!(
+        Ok(try_call_static!(
             "frc/robot/Wrapper",
             "getValue",
             "(Lcom/ctre/phoenix6/StatusSignal;)D",
             &[JValue::Object(&status_signal).as_jni()],
             ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        )?
+        .d()?)
     }
 
     pub fn get_id(&self) -> i32 {
@@ -229,47 +249,51 @@ impl Talon {
     }
 
     pub fn follow(&self, master: &Talon, inverted: bool) {
+        self.try_follow(master, inverted).unwrap()
+    }
+
+    /// Like [`Talon::follow`], but returns an error instead of panicking
+    pub fn try_follow(&self, master: &Talon, inverted: bool) -> Result<(), FrcError> {
         let alignment = if inverted {
-            call_static!(
+            try_call_static!(
                 "frc/robot/Wrapper",
                 "invertFollow",
                 "()Lcom/ctre/phoenix6/signals/MotorAlignmentValue;",
                 &Vec::new(),
                 ReturnType::Object
-            )
-            .l()
-            .unwrap()
+            )?
+            .l()?
         } else {
-            call_static!(
+            try_call_static!(
                 "frc/robot/Wrapper",
                 "allignedFollow",
                 "()Lcom/ctre/phoenix6/signals/MotorAlignmentValue;",
                 &Vec::new(),
                 ReturnType::Object
-            )
-            .l()
-            .unwrap()
+            )?
+            .l()?
         };
 
-        let follower = create!(
+        let follower = try_create!(
             "com/ctre/phoenix6/controls/Follower",
             "(ILcom/ctre/phoenix6/signals/MotorAlignmentValue;)V",
             &[
                 JValue::Int(master.get_id()).as_jni(),
                 JValue::Object(&alignment).as_jni(),
             ]
-        );
+        )?;
 
-        call!(
+        try_call!(
             self.instance.as_obj(),
             "com/ctre/phoenix6/hardware/core/CoreTalonFX",
             "setControl",
             "(Lcom/ctre/phoenix6/controls/Follower;)Lcom/ctre/phoenix6/StatusCode;",
             &[JValue::Object(&follower).as_jni()],
             ReturnType::Object
-        )
-        .l()
-        .unwrap();
+        )?
+        .l()?;
+
+        Ok(())
     }
 
     pub fn zero(&self) {
@@ -317,3 +341,23 @@ impl Talon {
         .unwrap()
     }
 }
+
+impl MotorController for Talon {
+    fn set_percent(&mut self, percent: f64) {
+        self.set(ControlMode::Percent, percent);
+    }
+
+    fn stop(&mut self) {
+        Talon::stop(self);
+    }
+}
+
+impl Encoder for Talon {
+    fn position(&mut self) -> Angle {
+        Angle::new::<revolution>(self.get_position())
+    }
+
+    fn velocity(&mut self) -> AngularVelocity {
+        AngularVelocity::new::<revolution_per_second>(self.get_velocity())
+    }
+}
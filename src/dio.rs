@@ -3,7 +3,10 @@ use jni::{
     signature::{Primitive, ReturnType},
 };
 
+use crate::call::try_create;
 use crate::call::*;
+use crate::error::FrcError;
+use crate::hardware::DigitalInput;
 
 pub struct DIO {
     instance: GlobalRef,
@@ -11,13 +14,19 @@ pub struct DIO {
 
 impl DIO {
     pub fn new(port: i32) -> Self {
-        let instance = create!(
+        Self::try_new(port).unwrap()
+    }
+
+    /// Like [`DIO::new`], but returns an error instead of panicking
+    pub fn try_new(port: i32) -> Result<Self, FrcError> {
+        let instance = try_create!(
             "edu/wpi/first/wpilibj/DigitalInput",
             "(I)V",
             &[JValue::Int(port).as_jni()]
-        );
+        )
+        .map_err(FrcError::device("DigitalInput", port))?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
 
     pub fn get(&self) -> bool {
@@ -33,3 +42,9 @@ impl DIO {
         .unwrap()
     }
 }
+
+impl DigitalInput for DIO {
+    fn get(&self) -> bool {
+        DIO::get(self)
+    }
+}
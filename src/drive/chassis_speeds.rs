@@ -0,0 +1,103 @@
+use std::ops::{Add, Mul, Neg, Sub};
+use uom::si::f64::{AngularVelocity, Velocity};
+
+use crate::geometry::Rotation2d;
+
+/// How fast the robot is moving, relative to the robot unless noted otherwise.
+///
+/// `vx` is forward, `vy` is left and `omega` is counter-clockwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChassisSpeeds {
+    pub vx: Velocity,
+    pub vy: Velocity,
+    pub omega: AngularVelocity,
+}
+
+impl ChassisSpeeds {
+    pub fn new(vx: Velocity, vy: Velocity, omega: AngularVelocity) -> Self {
+        Self { vx, vy, omega }
+    }
+
+    /// Convert speeds relative to the field into speeds relative to a robot facing `heading`
+    pub fn from_field_relative(
+        vx: Velocity,
+        vy: Velocity,
+        omega: AngularVelocity,
+        heading: Rotation2d,
+    ) -> Self {
+        Self {
+            vx: vx * heading.cos() + vy * heading.sin(),
+            vy: -vx * heading.sin() + vy * heading.cos(),
+            omega,
+        }
+    }
+
+    /// Convert these robot relative speeds into speeds relative to the field
+    pub fn to_field_relative(&self, heading: Rotation2d) -> Self {
+        Self::from_field_relative(self.vx, self.vy, self.omega, heading.inverse())
+    }
+}
+
+impl Add for ChassisSpeeds {
+    type Output = ChassisSpeeds;
+
+    fn add(self, other: ChassisSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds::new(
+            self.vx + other.vx,
+            self.vy + other.vy,
+            self.omega + other.omega,
+        )
+    }
+}
+
+impl Sub for ChassisSpeeds {
+    type Output = ChassisSpeeds;
+
+    fn sub(self, other: ChassisSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds::new(
+            self.vx - other.vx,
+            self.vy - other.vy,
+            self.omega - other.omega,
+        )
+    }
+}
+
+impl Neg for ChassisSpeeds {
+    type Output = ChassisSpeeds;
+
+    fn neg(self) -> ChassisSpeeds {
+        ChassisSpeeds::new(-self.vx, -self.vy, -self.omega)
+    }
+}
+
+impl Mul<f64> for ChassisSpeeds {
+    type Output = ChassisSpeeds;
+
+    fn mul(self, scalar: f64) -> ChassisSpeeds {
+        ChassisSpeeds::new(self.vx * scalar, self.vy * scalar, self.omega * scalar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::velocity::meter_per_second;
+
+    #[test]
+    fn field_relative_round_trip() {
+        let heading = Rotation2d::from_degrees(90.);
+        let speeds = ChassisSpeeds::from_field_relative(
+            Velocity::new::<meter_per_second>(1.),
+            Velocity::new::<meter_per_second>(0.),
+            AngularVelocity::default(),
+            heading,
+        );
+
+        // facing left on the field, driving along field +x is driving to the robot's right
+        assert!(speeds.vx.get::<meter_per_second>().abs() < 1e-9);
+        assert!((speeds.vy.get::<meter_per_second>() + 1.).abs() < 1e-9);
+
+        let field = speeds.to_field_relative(heading);
+        assert!((field.vx.get::<meter_per_second>() - 1.).abs() < 1e-9);
+    }
+}
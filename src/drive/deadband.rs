@@ -0,0 +1,27 @@
+/// Zero `value` inside `deadband` and rescale the rest so the output still covers -1 to 1
+/// without a jump at the edge of the deadband
+pub fn apply_deadband(value: f64, deadband: f64) -> f64 {
+    if value.abs() <= deadband {
+        0.
+    } else {
+        (value - deadband.copysign(value)) / (1. - deadband)
+    }
+}
+
+/// Square `value` keeping its sign, for finer control at low speeds
+pub fn square_input(value: f64) -> f64 {
+    (value * value).copysign(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadband_rescales() {
+        assert_eq!(apply_deadband(0.05, 0.1), 0.);
+        assert!((apply_deadband(0.55, 0.1) - 0.5).abs() < 1e-9);
+        assert!((apply_deadband(-1., 0.1) + 1.).abs() < 1e-9);
+        assert_eq!(square_input(-0.5), -0.25);
+    }
+}
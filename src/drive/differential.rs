@@ -0,0 +1,270 @@
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{AngularVelocity, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+
+use crate::drive::{apply_deadband, square_input, ChassisSpeeds};
+use crate::hardware::MotorController;
+
+/// Speeds of the left and right sides of a differential drive.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DifferentialDriveWheelSpeeds {
+    pub left: Velocity,
+    pub right: Velocity,
+}
+
+impl DifferentialDriveWheelSpeeds {
+    pub fn new(left: Velocity, right: Velocity) -> Self {
+        Self { left, right }
+    }
+
+    /// Scale both sides down evenly so neither is faster than `max_speed`
+    pub fn desaturate(&mut self, max_speed: Velocity) {
+        let fastest = self.left.abs().max(self.right.abs());
+
+        if fastest > max_speed {
+            let scale = (max_speed / fastest).value;
+            self.left *= scale;
+            self.right *= scale;
+        }
+    }
+}
+
+/// Converts between chassis speeds and the speeds of each side of a tank drive.
+#[derive(Clone, Copy, Debug)]
+pub struct DifferentialDriveKinematics {
+    pub track_width: Length,
+}
+
+impl DifferentialDriveKinematics {
+    /// `track_width` is the distance between the left and right wheels
+    pub fn new(track_width: Length) -> Self {
+        Self { track_width }
+    }
+
+    /// Wheel speeds for `speeds`, ignoring `vy` since a tank drive can't strafe
+    pub fn to_wheel_speeds(&self, speeds: ChassisSpeeds) -> DifferentialDriveWheelSpeeds {
+        let turn = Velocity::new::<meter_per_second>(
+            speeds.omega.get::<radian_per_second>() * self.track_width.get::<meter>() / 2.,
+        );
+
+        DifferentialDriveWheelSpeeds::new(speeds.vx - turn, speeds.vx + turn)
+    }
+
+    pub fn to_chassis_speeds(&self, wheels: DifferentialDriveWheelSpeeds) -> ChassisSpeeds {
+        let omega = (wheels.right - wheels.left).get::<meter_per_second>()
+            / self.track_width.get::<meter>();
+
+        ChassisSpeeds::new(
+            (wheels.left + wheels.right) / 2.,
+            Velocity::default(),
+            AngularVelocity::new::<radian_per_second>(omega),
+        )
+    }
+}
+
+/// Duty cycles for each side of a differential drive, from -1 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DifferentialDriveOutput {
+    pub left: f64,
+    pub right: f64,
+}
+
+impl DifferentialDriveOutput {
+    /// Split `forward` and counter-clockwise `rotation` between the sides, scaling both down
+    /// together when their sum would saturate
+    pub fn arcade(forward: f64, rotation: f64, squared: bool) -> Self {
+        let (mut forward, mut rotation) = (forward.clamp(-1., 1.), rotation.clamp(-1., 1.));
+        if squared {
+            forward = square_input(forward);
+            rotation = square_input(rotation);
+        }
+
+        let greater = forward.abs().max(rotation.abs());
+        let lesser = forward.abs().min(rotation.abs());
+        if greater == 0. {
+            return Self::default();
+        }
+
+        let saturated = (greater + lesser) / greater;
+        Self {
+            left: (forward - rotation) / saturated,
+            right: (forward + rotation) / saturated,
+        }
+    }
+
+    /// Make `rotation` set the curvature of the path rather than the turning rate, so the robot
+    /// turns the same arc at any speed. Without `turn_in_place` the robot can't turn while
+    /// stopped.
+    pub fn curvature(forward: f64, rotation: f64, turn_in_place: bool) -> Self {
+        let (forward, rotation) = (forward.clamp(-1., 1.), rotation.clamp(-1., 1.));
+
+        let (left, right) = if turn_in_place {
+            (forward - rotation, forward + rotation)
+        } else {
+            (
+                forward - forward.abs() * rotation,
+                forward + forward.abs() * rotation,
+            )
+        };
+
+        let fastest = left.abs().max(right.abs());
+        if fastest > 1. {
+            Self {
+                left: left / fastest,
+                right: right / fastest,
+            }
+        } else {
+            Self { left, right }
+        }
+    }
+
+    pub fn tank(left: f64, right: f64, squared: bool) -> Self {
+        let (left, right) = (left.clamp(-1., 1.), right.clamp(-1., 1.));
+
+        if squared {
+            Self {
+                left: square_input(left),
+                right: square_input(right),
+            }
+        } else {
+            Self { left, right }
+        }
+    }
+}
+
+/// Drives a tank drivetrain from joystick inputs.
+///
+/// Works with any [`MotorController`], so the sides can be a `Talon`, a `Spark`, or a group
+/// of followers driven by a leader.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::drive::DifferentialDrive;
+/// use frcrs::input::Joystick;
+/// use frcrs::rev::{MotorType, Spark};
+///
+/// let stick = Joystick::new(0);
+/// let mut drive = DifferentialDrive::new(
+///     Spark::new(1, MotorType::Brushless),
+///     Spark::new(2, MotorType::Brushless),
+/// );
+///
+/// drive.arcade(-stick.get_y(), -stick.get_x(), true);
+/// ```
+pub struct DifferentialDrive<L, R> {
+    left: L,
+    right: R,
+    deadband: f64,
+}
+
+impl<L: MotorController, R: MotorController> DifferentialDrive<L, R> {
+    /// A drive with a deadband of 0.02
+    pub fn new(left: L, right: R) -> Self {
+        Self {
+            left,
+            right,
+            deadband: 0.02,
+        }
+    }
+
+    pub fn set_deadband(&mut self, deadband: f64) {
+        self.deadband = deadband;
+    }
+
+    /// See [`DifferentialDriveOutput::arcade`]
+    pub fn arcade(&mut self, forward: f64, rotation: f64, squared: bool) {
+        self.set(DifferentialDriveOutput::arcade(
+            apply_deadband(forward, self.deadband),
+            apply_deadband(rotation, self.deadband),
+            squared,
+        ));
+    }
+
+    /// See [`DifferentialDriveOutput::curvature`]
+    pub fn curvature(&mut self, forward: f64, rotation: f64, turn_in_place: bool) {
+        self.set(DifferentialDriveOutput::curvature(
+            apply_deadband(forward, self.deadband),
+            apply_deadband(rotation, self.deadband),
+            turn_in_place,
+        ));
+    }
+
+    pub fn tank(&mut self, left: f64, right: f64, squared: bool) {
+        self.set(DifferentialDriveOutput::tank(
+            apply_deadband(left, self.deadband),
+            apply_deadband(right, self.deadband),
+            squared,
+        ));
+    }
+
+    pub fn set(&mut self, output: DifferentialDriveOutput) {
+        self.left.set_percent(output.left);
+        self.right.set_percent(output.right);
+    }
+
+    pub fn stop(&mut self) {
+        self.left.stop();
+        self.right.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::fake::FakeMotor;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn kinematics_round_trip() {
+        let kinematics = DifferentialDriveKinematics::new(Length::new::<meter>(0.5));
+        let speeds = ChassisSpeeds::new(
+            Velocity::new::<meter_per_second>(1.),
+            Velocity::default(),
+            AngularVelocity::new::<radian_per_second>(2.),
+        );
+
+        let wheels = kinematics.to_wheel_speeds(speeds);
+        assert_close(wheels.left.get::<meter_per_second>(), 0.5);
+        assert_close(wheels.right.get::<meter_per_second>(), 1.5);
+
+        let measured = kinematics.to_chassis_speeds(wheels);
+        assert_close(measured.vx.get::<meter_per_second>(), 1.);
+        assert_close(measured.omega.get::<radian_per_second>(), 2.);
+    }
+
+    #[test]
+    fn arcade_saturates_evenly() {
+        let output = DifferentialDriveOutput::arcade(1., 0.5, false);
+
+        assert_close(output.left, 0.5 / 1.5);
+        assert_close(output.right, 1.);
+    }
+
+    #[test]
+    fn curvature_needs_speed_to_turn() {
+        let stopped = DifferentialDriveOutput::curvature(0., 1., false);
+        assert_eq!(stopped, DifferentialDriveOutput::default());
+
+        let spinning = DifferentialDriveOutput::curvature(0., 1., true);
+        assert_close(spinning.left, -1.);
+        assert_close(spinning.right, 1.);
+    }
+
+    #[test]
+    fn drive_applies_deadband() {
+        let left = FakeMotor::new();
+        let right = FakeMotor::new();
+        let mut drive = DifferentialDrive::new(left.clone(), right.clone());
+        drive.set_deadband(0.1);
+
+        drive.tank(0.05, -0.55, false);
+
+        assert_eq!(left.output(), 0.);
+        assert_close(right.output(), -0.5);
+    }
+}
@@ -0,0 +1,201 @@
+use std::f64::consts::PI;
+use uom::si::angle::degree;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{Angle, AngularVelocity, Length, Time, Velocity};
+use uom::si::length::meter;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+use crate::drive::ChassisSpeeds;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::pid::PIDController;
+use crate::trajectory::Pose;
+
+/// Follows a sampled trajectory with a swerve or mecanum drive.
+///
+/// The velocities in each sample are used as feedforward, and a PID loop per axis corrects
+/// the error between the sample and the estimated pose, in meters and radians. The heading
+/// loop is switched to continuous input, so it turns the short way round.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::drive::{ChassisSpeeds, HolonomicDriveController};
+/// use frcrs::geometry::Pose2d;
+/// use frcrs::trajectory::Path;
+/// use frcrs::pid::PIDController;
+/// use uom::si::f64::Time;
+/// use uom::si::time::{millisecond, second};
+///
+/// # fn example(path: Path, estimated_pose: Pose2d, elapsed: Time) {
+/// let mut controller = HolonomicDriveController::new(
+///     PIDController::new(2., 0., 0.),
+///     PIDController::new(2., 0., 0.),
+///     PIDController::new(4., 0., 0.),
+/// );
+///
+/// // every loop
+/// let speeds = controller.calculate(
+///     estimated_pose,
+///     &path.get(elapsed),
+///     Time::new::<millisecond>(20.),
+/// );
+/// let robot_relative = ChassisSpeeds::from_field_relative(
+///     speeds.vx,
+///     speeds.vy,
+///     speeds.omega,
+///     estimated_pose.rotation,
+/// );
+///
+/// if elapsed >= path.length() && controller.at_reference() {
+///     // done
+/// }
+/// # }
+/// ```
+pub struct HolonomicDriveController {
+    x: PIDController,
+    y: PIDController,
+    heading: PIDController,
+    translation_tolerance: Length,
+    heading_tolerance: Angle,
+    error: Option<(Translation2d, Rotation2d)>,
+    enabled: bool,
+}
+
+impl HolonomicDriveController {
+    /// A controller with a tolerance of 5cm and 2°
+    pub fn new(x: PIDController, y: PIDController, mut heading: PIDController) -> Self {
+        heading.enable_continuous_input(-PI, PI);
+
+        Self {
+            x,
+            y,
+            heading,
+            translation_tolerance: Length::new::<meter>(0.05),
+            heading_tolerance: Angle::new::<degree>(2.),
+            error: None,
+            enabled: true,
+        }
+    }
+
+    /// How close the robot has to be for [`HolonomicDriveController::at_reference`], on each
+    /// axis
+    pub fn set_tolerance(&mut self, translation: Length, heading: Angle) {
+        self.translation_tolerance = translation;
+        self.heading_tolerance = heading;
+    }
+
+    /// Disabling the controller leaves only the feedforward
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Whether the last pose passed to [`HolonomicDriveController::calculate`] was within
+    /// tolerance of its reference
+    pub fn at_reference(&self) -> bool {
+        let Some((translation, rotation)) = self.error else {
+            return false;
+        };
+
+        translation.x.abs() <= self.translation_tolerance
+            && translation.y.abs() <= self.translation_tolerance
+            && rotation.angle().abs() <= self.heading_tolerance
+    }
+
+    /// Field relative speeds that move the robot from `current` onto `reference`
+    pub fn calculate(&mut self, current: Pose2d, reference: &Pose, dt: Time) -> ChassisSpeeds {
+        let target = reference.to_pose2d();
+        let translation = target.translation - current.translation;
+        let rotation = target.rotation - current.rotation;
+        self.error = Some((translation, rotation));
+
+        let dt = dt.get::<second>();
+        let feedforward = ChassisSpeeds::new(
+            reference.velocity_x,
+            reference.velocity_y,
+            reference.angular_velocity,
+        );
+        if !self.enabled {
+            return feedforward;
+        }
+
+        let vx = self
+            .x
+            .update(target.x().get::<meter>(), current.x().get::<meter>(), dt);
+        let vy = self
+            .y
+            .update(target.y().get::<meter>(), current.y().get::<meter>(), dt);
+        let omega = self
+            .heading
+            .update(target.rotation.radians(), current.rotation.radians(), dt);
+
+        feedforward
+            + ChassisSpeeds::new(
+                Velocity::new::<meter_per_second>(vx),
+                Velocity::new::<meter_per_second>(vy),
+                AngularVelocity::new::<radian_per_second>(omega),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(x: f64, y: f64, heading: f64) -> Pose {
+        Pose {
+            x: Length::new::<meter>(x),
+            y: Length::new::<meter>(y),
+            heading: Angle::new::<degree>(heading),
+            angular_velocity: AngularVelocity::default(),
+            velocity_x: Velocity::new::<meter_per_second>(1.),
+            velocity_y: Velocity::default(),
+        }
+    }
+
+    fn pose(x: f64, y: f64, heading: f64) -> Pose2d {
+        Pose2d::new(
+            Translation2d::from_meters(x, y),
+            Rotation2d::from_degrees(heading),
+        )
+    }
+
+    fn controller() -> HolonomicDriveController {
+        HolonomicDriveController::new(
+            PIDController::new(1., 0., 0.),
+            PIDController::new(1., 0., 0.),
+            PIDController::new(1., 0., 0.),
+        )
+    }
+
+    #[test]
+    fn corrects_on_top_of_feedforward() {
+        let mut controller = controller();
+        let dt = Time::new::<second>(0.02);
+
+        let speeds = controller.calculate(pose(0.8, 0.1, 0.), &reference(1., 0., 0.), dt);
+
+        assert!((speeds.vx.get::<meter_per_second>() - 1.2).abs() < 1e-9);
+        assert!((speeds.vy.get::<meter_per_second>() - -0.1).abs() < 1e-9);
+        assert!(!controller.at_reference());
+
+        controller.set_enabled(false);
+        let speeds = controller.calculate(pose(0.8, 0.1, 0.), &reference(1., 0., 0.), dt);
+        assert_eq!(speeds.vx.get::<meter_per_second>(), 1.);
+    }
+
+    #[test]
+    fn heading_turns_short_way() {
+        let mut controller = controller();
+
+        let speeds = controller.calculate(
+            pose(1., 0., 179.),
+            &reference(1., 0., -179.),
+            Time::new::<second>(0.02),
+        );
+
+        let omega = speeds.omega.get::<radian_per_second>();
+        assert!((omega - 2f64.to_radians()).abs() < 1e-9);
+        assert!(controller.at_reference());
+    }
+}
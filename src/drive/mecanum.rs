@@ -0,0 +1,315 @@
+use nalgebra::{DMatrix, DVector};
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+
+use crate::drive::{apply_deadband, square_input, ChassisSpeeds};
+use crate::geometry::{Rotation2d, Translation2d};
+use crate::hardware::MotorController;
+
+/// Speeds of each wheel of a mecanum drive.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MecanumDriveWheelSpeeds {
+    pub front_left: Velocity,
+    pub front_right: Velocity,
+    pub rear_left: Velocity,
+    pub rear_right: Velocity,
+}
+
+impl MecanumDriveWheelSpeeds {
+    /// Scale every wheel down evenly so none is faster than `max_speed`
+    pub fn desaturate(&mut self, max_speed: Velocity) {
+        let fastest = self
+            .wheels()
+            .iter()
+            .map(|speed| speed.abs())
+            .fold(Velocity::default(), Velocity::max);
+
+        if fastest > max_speed {
+            let scale = (max_speed / fastest).value;
+            self.front_left *= scale;
+            self.front_right *= scale;
+            self.rear_left *= scale;
+            self.rear_right *= scale;
+        }
+    }
+
+    fn wheels(&self) -> [Velocity; 4] {
+        [
+            self.front_left,
+            self.front_right,
+            self.rear_left,
+            self.rear_right,
+        ]
+    }
+}
+
+/// Converts between chassis speeds and the speeds of each wheel of a mecanum drive.
+///
+/// Wheel positions are relative to the center of the robot, +x forward and +y left.
+pub struct MecanumDriveKinematics {
+    wheels: [Translation2d; 4],
+    forward: DMatrix<f64>,
+}
+
+impl MecanumDriveKinematics {
+    pub fn new(
+        front_left: Translation2d,
+        front_right: Translation2d,
+        rear_left: Translation2d,
+        rear_right: Translation2d,
+    ) -> Self {
+        let wheels = [front_left, front_right, rear_left, rear_right];
+        let forward = Self::inverse_matrix(&wheels, Translation2d::default())
+            .pseudo_inverse(1e-9)
+            .expect("wheel positions are degenerate");
+
+        Self { wheels, forward }
+    }
+
+    pub fn to_wheel_speeds(&self, speeds: ChassisSpeeds) -> MecanumDriveWheelSpeeds {
+        self.to_wheel_speeds_around(speeds, Translation2d::default())
+    }
+
+    /// Like [`MecanumDriveKinematics::to_wheel_speeds`], but rotating around
+    /// `center_of_rotation` instead of the center of the robot
+    pub fn to_wheel_speeds_around(
+        &self,
+        speeds: ChassisSpeeds,
+        center_of_rotation: Translation2d,
+    ) -> MecanumDriveWheelSpeeds {
+        let chassis = DVector::from_vec(vec![
+            speeds.vx.get::<meter_per_second>(),
+            speeds.vy.get::<meter_per_second>(),
+            speeds.omega.get::<radian_per_second>(),
+        ]);
+        let wheels = Self::inverse_matrix(&self.wheels, center_of_rotation) * chassis;
+        let speed = |i: usize| Velocity::new::<meter_per_second>(wheels[i]);
+
+        MecanumDriveWheelSpeeds {
+            front_left: speed(0),
+            front_right: speed(1),
+            rear_left: speed(2),
+            rear_right: speed(3),
+        }
+    }
+
+    /// The chassis speeds that best fit the measured wheel speeds
+    pub fn to_chassis_speeds(&self, wheels: MecanumDriveWheelSpeeds) -> ChassisSpeeds {
+        let wheels = DVector::from_iterator(
+            4,
+            wheels
+                .wheels()
+                .iter()
+                .map(|speed| speed.get::<meter_per_second>()),
+        );
+        let chassis = &self.forward * wheels;
+
+        ChassisSpeeds::new(
+            Velocity::new::<meter_per_second>(chassis[0]),
+            Velocity::new::<meter_per_second>(chassis[1]),
+            AngularVelocity::new::<radian_per_second>(chassis[2]),
+        )
+    }
+
+    // rollers at 45° make the left front and right rear wheels push right when driven forward
+    fn inverse_matrix(
+        wheels: &[Translation2d; 4],
+        center_of_rotation: Translation2d,
+    ) -> DMatrix<f64> {
+        let [fl, fr, rl, rr] = wheels.map(|wheel| {
+            let offset = wheel - center_of_rotation;
+            (offset.x.get::<meter>(), offset.y.get::<meter>())
+        });
+
+        #[rustfmt::skip]
+        let matrix = DMatrix::from_row_slice(4, 3, &[
+            1., -1., -(fl.0 + fl.1),
+            1., 1., fr.0 - fr.1,
+            1., 1., rl.0 - rl.1,
+            1., -1., -(rr.0 + rr.1),
+        ]);
+
+        matrix
+    }
+}
+
+/// Duty cycles for each wheel of a mecanum drive, from -1 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MecanumDriveOutput {
+    pub front_left: f64,
+    pub front_right: f64,
+    pub rear_left: f64,
+    pub rear_right: f64,
+}
+
+impl MecanumDriveOutput {
+    /// Drive `forward`, `left` and counter-clockwise `rotation`. Pass the robot's heading to
+    /// drive relative to the field, or [`Rotation2d::default`] to drive relative to the robot.
+    pub fn cartesian(forward: f64, left: f64, rotation: f64, heading: Rotation2d) -> Self {
+        let (forward, left) = (forward.clamp(-1., 1.), left.clamp(-1., 1.));
+        let rotation = rotation.clamp(-1., 1.);
+
+        let x = forward * heading.cos() + left * heading.sin();
+        let y = -forward * heading.sin() + left * heading.cos();
+
+        let wheels = [
+            x - y - rotation,
+            x + y + rotation,
+            x + y - rotation,
+            x - y + rotation,
+        ];
+        let fastest = wheels.iter().fold(1f64, |max, wheel| max.max(wheel.abs()));
+        let [front_left, front_right, rear_left, rear_right] = wheels.map(|wheel| wheel / fastest);
+
+        Self {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+        }
+    }
+}
+
+/// Drives a mecanum drivetrain from joystick inputs.
+pub struct MecanumDrive<FL, FR, RL, RR> {
+    front_left: FL,
+    front_right: FR,
+    rear_left: RL,
+    rear_right: RR,
+    deadband: f64,
+}
+
+impl<FL, FR, RL, RR> MecanumDrive<FL, FR, RL, RR>
+where
+    FL: MotorController,
+    FR: MotorController,
+    RL: MotorController,
+    RR: MotorController,
+{
+    /// A drive with a deadband of 0.02
+    pub fn new(front_left: FL, front_right: FR, rear_left: RL, rear_right: RR) -> Self {
+        Self {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+            deadband: 0.02,
+        }
+    }
+
+    pub fn set_deadband(&mut self, deadband: f64) {
+        self.deadband = deadband;
+    }
+
+    /// See [`MecanumDriveOutput::cartesian`], optionally squaring the translation inputs
+    pub fn cartesian(
+        &mut self,
+        forward: f64,
+        left: f64,
+        rotation: f64,
+        heading: Rotation2d,
+        squared: bool,
+    ) {
+        let mut forward = apply_deadband(forward, self.deadband);
+        let mut left = apply_deadband(left, self.deadband);
+        if squared {
+            forward = square_input(forward);
+            left = square_input(left);
+        }
+
+        self.set(MecanumDriveOutput::cartesian(
+            forward,
+            left,
+            apply_deadband(rotation, self.deadband),
+            heading,
+        ));
+    }
+
+    pub fn set(&mut self, output: MecanumDriveOutput) {
+        self.front_left.set_percent(output.front_left);
+        self.front_right.set_percent(output.front_right);
+        self.rear_left.set_percent(output.rear_left);
+        self.rear_right.set_percent(output.rear_right);
+    }
+
+    pub fn stop(&mut self) {
+        self.front_left.stop();
+        self.front_right.stop();
+        self.rear_left.stop();
+        self.rear_right.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::fake::FakeMotor;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    fn kinematics() -> MecanumDriveKinematics {
+        MecanumDriveKinematics::new(
+            Translation2d::from_meters(0.3, 0.3),
+            Translation2d::from_meters(0.3, -0.3),
+            Translation2d::from_meters(-0.3, 0.3),
+            Translation2d::from_meters(-0.3, -0.3),
+        )
+    }
+
+    #[test]
+    fn strafe_left() {
+        let kinematics = kinematics();
+        let speeds = ChassisSpeeds::new(
+            Velocity::default(),
+            Velocity::new::<meter_per_second>(1.),
+            AngularVelocity::default(),
+        );
+
+        let wheels = kinematics.to_wheel_speeds(speeds);
+        assert_close(wheels.front_left.get::<meter_per_second>(), -1.);
+        assert_close(wheels.front_right.get::<meter_per_second>(), 1.);
+        assert_close(wheels.rear_left.get::<meter_per_second>(), 1.);
+        assert_close(wheels.rear_right.get::<meter_per_second>(), -1.);
+
+        let measured = kinematics.to_chassis_speeds(wheels);
+        assert_close(measured.vy.get::<meter_per_second>(), 1.);
+        assert_close(measured.vx.get::<meter_per_second>(), 0.);
+    }
+
+    #[test]
+    fn spin_round_trip() {
+        let kinematics = kinematics();
+        let speeds = ChassisSpeeds::new(
+            Velocity::new::<meter_per_second>(0.5),
+            Velocity::default(),
+            AngularVelocity::new::<radian_per_second>(1.),
+        );
+
+        let measured = kinematics.to_chassis_speeds(kinematics.to_wheel_speeds(speeds));
+
+        assert_close(measured.vx.get::<meter_per_second>(), 0.5);
+        assert_close(measured.omega.get::<radian_per_second>(), 1.);
+    }
+
+    #[test]
+    fn field_relative_cartesian() {
+        let front_left = FakeMotor::new();
+        let front_right = FakeMotor::new();
+        let mut drive = MecanumDrive::new(
+            front_left.clone(),
+            front_right.clone(),
+            FakeMotor::new(),
+            FakeMotor::new(),
+        );
+
+        // facing left on the field, driving field forward means strafing right
+        drive.cartesian(1., 0., 0., Rotation2d::from_degrees(90.), false);
+
+        assert_close(front_left.output(), 1.);
+        assert_close(front_right.output(), -1.);
+    }
+}
@@ -1,3 +1,21 @@
+mod chassis_speeds;
+mod deadband;
+mod differential;
+mod holonomic_controller;
+mod mecanum;
+mod odometry;
+mod pose_estimator;
 mod swerve;
+mod swerve_kinematics;
+mod swerve_module;
 
+pub use chassis_speeds::*;
+pub use deadband::*;
+pub use differential::*;
+pub use holonomic_controller::*;
+pub use mecanum::*;
+pub use odometry::*;
+pub use pose_estimator::*;
 pub use swerve::*;
+pub use swerve_kinematics::*;
+pub use swerve_module::*;
@@ -0,0 +1,257 @@
+use uom::si::angle::radian;
+use uom::si::f64::{Angle, Length};
+
+use crate::drive::{SwerveDriveKinematics, SwerveModulePosition};
+use crate::geometry::{Pose2d, Rotation2d, Twist2d};
+
+/// Tracks the robot's heading on the field from a gyro that may have been zeroed anywhere.
+///
+/// Only differences between gyro readings are used, and those go through [`Rotation2d`], so
+/// gyros that wrap at ±180° and gyros that count past 360° both work.
+#[derive(Clone, Copy, Debug)]
+struct Heading {
+    offset: Rotation2d,
+    previous: Rotation2d,
+}
+
+impl Heading {
+    fn new(gyro_angle: Rotation2d, pose: Pose2d) -> Self {
+        Self {
+            offset: pose.rotation - gyro_angle,
+            previous: pose.rotation,
+        }
+    }
+
+    /// Field relative heading, and how far it turned since the last update
+    fn update(&mut self, gyro_angle: Rotation2d) -> (Rotation2d, Angle) {
+        let angle = gyro_angle + self.offset;
+        let turned = Angle::new::<radian>((angle - self.previous).radians());
+        self.previous = angle;
+
+        (angle, turned)
+    }
+}
+
+/// Integrates swerve module positions and a gyro into a field pose.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::drive::{SwerveDriveKinematics, SwerveDriveOdometry, SwerveModulePosition};
+/// use frcrs::geometry::{Pose2d, Rotation2d, Translation2d};
+/// use uom::si::f64::Length;
+/// use uom::si::length::meter;
+///
+/// let kinematics = SwerveDriveKinematics::new(vec![
+///     Translation2d::from_meters(0.3, 0.3),
+///     Translation2d::from_meters(0.3, -0.3),
+///     Translation2d::from_meters(-0.3, 0.3),
+///     Translation2d::from_meters(-0.3, -0.3),
+/// ]);
+/// let mut positions = [SwerveModulePosition::default(); 4];
+/// let mut odometry = SwerveDriveOdometry::new(
+///     kinematics,
+///     Rotation2d::default(),
+///     &positions,
+///     Pose2d::default(),
+/// );
+///
+/// // every loop, with readings from the modules and the gyro
+/// for position in &mut positions {
+///     position.distance = Length::new::<meter>(1.);
+/// }
+/// let pose = odometry.update(Rotation2d::default(), &positions);
+///
+/// assert!((pose.x().get::<meter>() - 1.).abs() < 1e-9);
+/// ```
+pub struct SwerveDriveOdometry {
+    kinematics: SwerveDriveKinematics,
+    pose: Pose2d,
+    heading: Heading,
+    previous: Vec<SwerveModulePosition>,
+}
+
+impl SwerveDriveOdometry {
+    pub fn new(
+        kinematics: SwerveDriveKinematics,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+        initial_pose: Pose2d,
+    ) -> Self {
+        Self {
+            kinematics,
+            pose: initial_pose,
+            heading: Heading::new(gyro_angle, initial_pose),
+            previous: positions.to_vec(),
+        }
+    }
+
+    pub fn kinematics(&self) -> &SwerveDriveKinematics {
+        &self.kinematics
+    }
+
+    pub fn pose(&self) -> Pose2d {
+        self.pose
+    }
+
+    /// Move the robot to `pose`. The gyro and modules don't need to be zeroed, their current
+    /// readings are taken as the new starting point.
+    pub fn reset_position(
+        &mut self,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+        pose: Pose2d,
+    ) {
+        self.pose = pose;
+        self.heading = Heading::new(gyro_angle, pose);
+        self.previous = positions.to_vec();
+    }
+
+    /// Integrate the movement since the last update, returning the new pose
+    pub fn update(&mut self, gyro_angle: Rotation2d, positions: &[SwerveModulePosition]) -> Pose2d {
+        let (angle, turned) = self.heading.update(gyro_angle);
+
+        // the gyro is much better at measuring rotation than the wheels
+        let mut twist = self.kinematics.to_twist(&self.previous, positions);
+        twist.dtheta = turned;
+        self.previous.copy_from_slice(positions);
+
+        self.pose = Pose2d::new(self.pose.exp(twist).translation, angle);
+        self.pose
+    }
+}
+
+/// Integrates the distance driven by each side of a tank drive and a gyro into a field pose.
+pub struct DifferentialDriveOdometry {
+    pose: Pose2d,
+    heading: Heading,
+    previous_left: Length,
+    previous_right: Length,
+}
+
+impl DifferentialDriveOdometry {
+    pub fn new(gyro_angle: Rotation2d, left: Length, right: Length, initial_pose: Pose2d) -> Self {
+        Self {
+            pose: initial_pose,
+            heading: Heading::new(gyro_angle, initial_pose),
+            previous_left: left,
+            previous_right: right,
+        }
+    }
+
+    pub fn pose(&self) -> Pose2d {
+        self.pose
+    }
+
+    /// Move the robot to `pose`, taking the current readings as the new starting point
+    pub fn reset_position(
+        &mut self,
+        gyro_angle: Rotation2d,
+        left: Length,
+        right: Length,
+        pose: Pose2d,
+    ) {
+        *self = Self::new(gyro_angle, left, right, pose);
+    }
+
+    /// Integrate the movement since the last update, returning the new pose
+    pub fn update(&mut self, gyro_angle: Rotation2d, left: Length, right: Length) -> Pose2d {
+        let (angle, turned) = self.heading.update(gyro_angle);
+
+        let forward = ((left - self.previous_left) + (right - self.previous_right)) / 2.;
+        self.previous_left = left;
+        self.previous_right = right;
+
+        let twist = Twist2d::new(forward, Length::default(), turned);
+        self.pose = Pose2d::new(self.pose.exp(twist).translation, angle);
+        self.pose
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Translation2d;
+    use std::f64::consts::PI;
+    use uom::si::length::meter;
+
+    fn meters(distance: f64) -> Length {
+        Length::new::<meter>(distance)
+    }
+
+    fn square() -> SwerveDriveKinematics {
+        SwerveDriveKinematics::new(vec![
+            Translation2d::from_meters(0.5, 0.5),
+            Translation2d::from_meters(0.5, -0.5),
+            Translation2d::from_meters(-0.5, 0.5),
+            Translation2d::from_meters(-0.5, -0.5),
+        ])
+    }
+
+    #[test]
+    fn swerve_strafes_on_field() {
+        let start = [SwerveModulePosition::default(); 4];
+        let mut odometry = SwerveDriveOdometry::new(
+            square(),
+            Rotation2d::from_degrees(30.),
+            &start,
+            Pose2d::new(
+                Translation2d::from_meters(1., 1.),
+                Rotation2d::from_degrees(90.),
+            ),
+        );
+
+        // wheels pointing left relative to the robot, which faces left on the field
+        let moved = [SwerveModulePosition::new(meters(2.), Rotation2d::from_degrees(90.)); 4];
+        let pose = odometry.update(Rotation2d::from_degrees(30.), &moved);
+
+        assert!((pose.x().get::<meter>() - -1.).abs() < 1e-9);
+        assert!((pose.y().get::<meter>() - 1.).abs() < 1e-9);
+        assert!((pose.rotation.degrees() - 90.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn heading_survives_gyro_wrap() {
+        let mut odometry = DifferentialDriveOdometry::new(
+            Rotation2d::from_degrees(179.),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+        );
+
+        // turning 2° counter-clockwise wraps a ±180° gyro to -179°
+        let pose = odometry.update(Rotation2d::from_degrees(-179.), meters(0.), meters(0.));
+        assert!((pose.rotation.degrees() - 2.).abs() < 1e-9);
+
+        // and a continuous gyro that has counted several turns reads the same
+        let pose = odometry.update(
+            Rotation2d::from_degrees(181. + 720.),
+            meters(0.),
+            meters(0.),
+        );
+        assert!((pose.rotation.degrees() - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn differential_drives_an_arc() {
+        let mut odometry = DifferentialDriveOdometry::new(
+            Rotation2d::default(),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+        );
+
+        // a quarter circle of radius 1
+        let distance = meters(PI / 2.);
+        let pose = odometry.update(Rotation2d::from_degrees(90.), distance, distance);
+
+        assert!((pose.x().get::<meter>() - 1.).abs() < 1e-9);
+        assert!((pose.y().get::<meter>() - 1.).abs() < 1e-9);
+
+        odometry.reset_position(Rotation2d::default(), distance, distance, Pose2d::default());
+        assert_eq!(
+            odometry.update(Rotation2d::default(), distance, distance),
+            Pose2d::default()
+        );
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use uom::si::f64::Length;
+
+use crate::drive::{
+    DifferentialDriveOdometry, SwerveDriveKinematics, SwerveDriveOdometry, SwerveModulePosition,
+};
+use crate::geometry::{Pose2d, Rotation2d, Twist2d};
+
+/// How long odometry is remembered, vision measurements older than this are ignored
+const HISTORY: Duration = Duration::from_millis(1500);
+
+/// A robot pose seen by a camera.
+#[derive(Clone, Copy, Debug)]
+pub struct VisionMeasurement {
+    pub pose: Pose2d,
+    /// When the image was captured, not when the result arrived
+    pub timestamp: Instant,
+    /// Standard deviations of x and y in meters and of the heading in radians. Larger values
+    /// trust the measurement less, infinity ignores that part of it.
+    pub std_devs: [f64; 3],
+}
+
+#[derive(Clone, Copy, Debug)]
+struct VisionUpdate {
+    timestamp: Instant,
+    /// The estimate after applying the measurement
+    pose: Pose2d,
+    /// Raw odometry at the time of the measurement
+    odometry: Pose2d,
+}
+
+impl VisionUpdate {
+    /// Carry this correction forward to a later odometry pose
+    fn compensate(&self, odometry: Pose2d) -> Pose2d {
+        self.pose.exp(self.odometry.log(odometry))
+    }
+}
+
+/// The part of a pose estimator shared by every drivetrain: a history of odometry poses and
+/// the vision corrections applied on top of it.
+///
+/// This is the steady state form of a Kalman filter with one state per axis, as used by
+/// WPILib. Applying a measurement blends it into the estimate at the time it was captured,
+/// then replays the odometry recorded since, so a late measurement doesn't drag the robot
+/// back to where it used to be.
+struct Estimator {
+    /// Variance of the odometry on each axis
+    q: [f64; 3],
+    odometry: VecDeque<(Instant, Pose2d)>,
+    vision: VecDeque<VisionUpdate>,
+    estimate: Pose2d,
+}
+
+impl Estimator {
+    fn new(pose: Pose2d, state_std_devs: [f64; 3]) -> Self {
+        Self {
+            q: state_std_devs.map(|std_dev| std_dev * std_dev),
+            odometry: VecDeque::new(),
+            vision: VecDeque::new(),
+            estimate: pose,
+        }
+    }
+
+    fn reset(&mut self, pose: Pose2d) {
+        self.odometry.clear();
+        self.vision.clear();
+        self.estimate = pose;
+    }
+
+    fn record(&mut self, timestamp: Instant, odometry: Pose2d) -> Pose2d {
+        while self
+            .odometry
+            .front()
+            .is_some_and(|&(time, _)| time + HISTORY < timestamp)
+        {
+            self.odometry.pop_front();
+        }
+        self.odometry.push_back((timestamp, odometry));
+
+        // keep the newest correction from before the history, later poses build on it
+        let oldest = self.odometry[0].0;
+        while self.vision.len() > 1 && self.vision[1].timestamp <= oldest {
+            self.vision.pop_front();
+        }
+
+        self.estimate = match self.vision.back() {
+            Some(update) => update.compensate(odometry),
+            None => odometry,
+        };
+        self.estimate
+    }
+
+    fn odometry_at(&self, timestamp: Instant) -> Option<Pose2d> {
+        let after = self.odometry.partition_point(|&(time, _)| time < timestamp);
+
+        match (
+            after.checked_sub(1).map(|i| self.odometry[i]),
+            self.odometry.get(after),
+        ) {
+            (Some((start_time, start)), Some(&(end_time, end))) => {
+                let t =
+                    (timestamp - start_time).as_secs_f64() / (end_time - start_time).as_secs_f64();
+                Some(start.interpolate(end, t))
+            }
+            (None, Some(&(_, pose))) | (Some((_, pose)), None) => Some(pose),
+            (None, None) => None,
+        }
+    }
+
+    fn add_vision_measurement(&mut self, measurement: VisionMeasurement) {
+        let Some(&(oldest, _)) = self.odometry.front() else {
+            return;
+        };
+        if measurement.timestamp < oldest {
+            return;
+        }
+        let Some(odometry) = self.odometry_at(measurement.timestamp) else {
+            return;
+        };
+
+        let before = self
+            .vision
+            .iter()
+            .rev()
+            .find(|update| update.timestamp <= measurement.timestamp);
+        let estimate = match before {
+            Some(update) => update.compensate(odometry),
+            None => odometry,
+        };
+
+        let twist = estimate.log(measurement.pose);
+        let gain = |i: usize| {
+            let q = self.q[i];
+            let r = measurement.std_devs[i] * measurement.std_devs[i];
+            if q == 0. {
+                0.
+            } else {
+                q / (q + (q * r).sqrt())
+            }
+        };
+        let correction = Twist2d::new(
+            twist.dx * gain(0),
+            twist.dy * gain(1),
+            twist.dtheta * gain(2),
+        );
+
+        // corrections made after this one were based on the old estimate
+        self.vision
+            .retain(|update| update.timestamp < measurement.timestamp);
+        self.vision.push_back(VisionUpdate {
+            timestamp: measurement.timestamp,
+            pose: estimate.exp(correction),
+            odometry,
+        });
+
+        let &(_, latest) = self.odometry.back().unwrap();
+        self.estimate = self.vision.back().unwrap().compensate(latest);
+    }
+}
+
+/// Fuses swerve odometry with latency compensated vision measurements.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::drive::{SwerveDriveKinematics, SwerveDrivePoseEstimator, SwerveModulePosition};
+/// use frcrs::geometry::{Pose2d, Rotation2d, Translation2d};
+/// use frcrs::limelight::Limelight;
+/// use std::time::Instant;
+///
+/// # async fn example(limelight: Limelight, positions: [SwerveModulePosition; 4], heading: Rotation2d) {
+/// let kinematics = SwerveDriveKinematics::new(vec![
+///     Translation2d::from_meters(0.3, 0.3),
+///     Translation2d::from_meters(0.3, -0.3),
+///     Translation2d::from_meters(-0.3, 0.3),
+///     Translation2d::from_meters(-0.3, -0.3),
+/// ]);
+/// let mut estimator =
+///     SwerveDrivePoseEstimator::new(kinematics, heading, &positions, Pose2d::default());
+///
+/// // every loop
+/// estimator.update(heading, &positions);
+/// if let Ok(results) = limelight.results().await {
+///     if let Some(measurement) = results.vision_measurement(Instant::now(), false) {
+///         estimator.add_vision_measurement(measurement);
+///     }
+/// }
+/// # }
+/// ```
+pub struct SwerveDrivePoseEstimator {
+    odometry: SwerveDriveOdometry,
+    estimator: Estimator,
+}
+
+impl SwerveDrivePoseEstimator {
+    /// An estimator trusting odometry to 0.1m and 0.1rad
+    pub fn new(
+        kinematics: SwerveDriveKinematics,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+        initial_pose: Pose2d,
+    ) -> Self {
+        Self::with_std_devs(
+            kinematics,
+            gyro_angle,
+            positions,
+            initial_pose,
+            [0.1, 0.1, 0.1],
+        )
+    }
+
+    /// `state_std_devs` are how far off the odometry is expected to be on x and y in meters, and
+    /// on heading in radians
+    pub fn with_std_devs(
+        kinematics: SwerveDriveKinematics,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+        initial_pose: Pose2d,
+        state_std_devs: [f64; 3],
+    ) -> Self {
+        Self {
+            odometry: SwerveDriveOdometry::new(kinematics, gyro_angle, positions, initial_pose),
+            estimator: Estimator::new(initial_pose, state_std_devs),
+        }
+    }
+
+    pub fn pose(&self) -> Pose2d {
+        self.estimator.estimate
+    }
+
+    pub fn reset_position(
+        &mut self,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+        pose: Pose2d,
+    ) {
+        self.odometry.reset_position(gyro_angle, positions, pose);
+        self.estimator.reset(pose);
+    }
+
+    pub fn update(&mut self, gyro_angle: Rotation2d, positions: &[SwerveModulePosition]) -> Pose2d {
+        self.update_at(Instant::now(), gyro_angle, positions)
+    }
+
+    /// Like [`SwerveDrivePoseEstimator::update`], with readings taken at `timestamp`
+    pub fn update_at(
+        &mut self,
+        timestamp: Instant,
+        gyro_angle: Rotation2d,
+        positions: &[SwerveModulePosition],
+    ) -> Pose2d {
+        let odometry = self.odometry.update(gyro_angle, positions);
+        self.estimator.record(timestamp, odometry)
+    }
+
+    /// Correct the estimate with a vision measurement from up to 1.5s ago
+    pub fn add_vision_measurement(&mut self, measurement: VisionMeasurement) {
+        self.estimator.add_vision_measurement(measurement);
+    }
+}
+
+/// Fuses tank drive odometry with latency compensated vision measurements.
+pub struct DifferentialDrivePoseEstimator {
+    odometry: DifferentialDriveOdometry,
+    estimator: Estimator,
+}
+
+impl DifferentialDrivePoseEstimator {
+    /// An estimator trusting odometry to 0.02m and 0.01rad
+    pub fn new(gyro_angle: Rotation2d, left: Length, right: Length, initial_pose: Pose2d) -> Self {
+        Self::with_std_devs(gyro_angle, left, right, initial_pose, [0.02, 0.02, 0.01])
+    }
+
+    /// See [`SwerveDrivePoseEstimator::with_std_devs`]
+    pub fn with_std_devs(
+        gyro_angle: Rotation2d,
+        left: Length,
+        right: Length,
+        initial_pose: Pose2d,
+        state_std_devs: [f64; 3],
+    ) -> Self {
+        Self {
+            odometry: DifferentialDriveOdometry::new(gyro_angle, left, right, initial_pose),
+            estimator: Estimator::new(initial_pose, state_std_devs),
+        }
+    }
+
+    pub fn pose(&self) -> Pose2d {
+        self.estimator.estimate
+    }
+
+    pub fn reset_position(
+        &mut self,
+        gyro_angle: Rotation2d,
+        left: Length,
+        right: Length,
+        pose: Pose2d,
+    ) {
+        self.odometry.reset_position(gyro_angle, left, right, pose);
+        self.estimator.reset(pose);
+    }
+
+    pub fn update(&mut self, gyro_angle: Rotation2d, left: Length, right: Length) -> Pose2d {
+        self.update_at(Instant::now(), gyro_angle, left, right)
+    }
+
+    /// Like [`DifferentialDrivePoseEstimator::update`], with readings taken at `timestamp`
+    pub fn update_at(
+        &mut self,
+        timestamp: Instant,
+        gyro_angle: Rotation2d,
+        left: Length,
+        right: Length,
+    ) -> Pose2d {
+        let odometry = self.odometry.update(gyro_angle, left, right);
+        self.estimator.record(timestamp, odometry)
+    }
+
+    /// Correct the estimate with a vision measurement from up to 1.5s ago
+    pub fn add_vision_measurement(&mut self, measurement: VisionMeasurement) {
+        self.estimator.add_vision_measurement(measurement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Translation2d;
+    use uom::si::length::meter;
+
+    fn meters(distance: f64) -> Length {
+        Length::new::<meter>(distance)
+    }
+
+    fn at(x: f64, y: f64) -> Pose2d {
+        Pose2d::new(Translation2d::from_meters(x, y), Rotation2d::default())
+    }
+
+    /// Drive forward 1m per 100ms for a second, returning the time of each update
+    fn drive(estimator: &mut DifferentialDrivePoseEstimator, start: Instant) -> Vec<Instant> {
+        (1..=10)
+            .map(|i| {
+                let time = start + Duration::from_millis(100 * i);
+                let distance = meters(i as f64);
+                estimator.update_at(time, Rotation2d::default(), distance, distance);
+                time
+            })
+            .collect()
+    }
+
+    #[test]
+    fn equal_trust_meets_halfway() {
+        let start = Instant::now();
+        let mut estimator = DifferentialDrivePoseEstimator::with_std_devs(
+            Rotation2d::default(),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+            [1., 1., 1.],
+        );
+        estimator.update_at(start, Rotation2d::default(), meters(0.), meters(0.));
+
+        estimator.add_vision_measurement(VisionMeasurement {
+            pose: at(0., 2.),
+            timestamp: start,
+            std_devs: [1., 1., 1.],
+        });
+
+        // q / (q + sqrt(q r)) with q = r gives half the difference
+        assert!((estimator.pose().y().get::<meter>() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn late_measurement_is_replayed() {
+        let start = Instant::now();
+        let mut estimator = DifferentialDrivePoseEstimator::new(
+            Rotation2d::default(),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+        );
+        let times = drive(&mut estimator, start);
+        assert!((estimator.pose().x().get::<meter>() - 10.).abs() < 1e-9);
+
+        // a camera that saw the robot 1m to the left of odometry, 500ms ago
+        estimator.add_vision_measurement(VisionMeasurement {
+            pose: at(5., 1.),
+            timestamp: times[4],
+            std_devs: [0., 0., f64::INFINITY],
+        });
+
+        // fully trusted, so the whole offset carries forward to the current pose
+        let pose = estimator.pose();
+        assert!((pose.x().get::<meter>() - 10.).abs() < 1e-9);
+        assert!((pose.y().get::<meter>() - 1.).abs() < 1e-9);
+        assert!(pose.rotation.radians().abs() < 1e-9);
+
+        // and to poses after it
+        estimator.update_at(
+            times[9] + Duration::from_millis(100),
+            Rotation2d::default(),
+            meters(11.),
+            meters(11.),
+        );
+        assert!((estimator.pose().y().get::<meter>() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn interpolates_between_updates() {
+        let start = Instant::now();
+        let mut estimator = DifferentialDrivePoseEstimator::new(
+            Rotation2d::default(),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+        );
+        let times = drive(&mut estimator, start);
+
+        // odometry read 5.5m halfway between two updates, vision agrees on x
+        estimator.add_vision_measurement(VisionMeasurement {
+            pose: at(5.5, 0.),
+            timestamp: times[4] + Duration::from_millis(50),
+            std_devs: [0., 0., 0.],
+        });
+
+        assert!((estimator.pose().x().get::<meter>() - 10.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_stale_measurements() {
+        let start = Instant::now();
+        let mut estimator = DifferentialDrivePoseEstimator::new(
+            Rotation2d::default(),
+            meters(0.),
+            meters(0.),
+            Pose2d::default(),
+        );
+        drive(&mut estimator, start + Duration::from_secs(2));
+
+        estimator.add_vision_measurement(VisionMeasurement {
+            pose: at(0., 5.),
+            timestamp: start,
+            std_devs: [0., 0., 0.],
+        });
+
+        assert_eq!(estimator.pose().y(), meters(0.));
+    }
+}
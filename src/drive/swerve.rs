@@ -13,6 +13,8 @@ pub struct WheelSpeeds {
     pub wa4: f64,
 }
 
+/// Fixed 32x32 four module swerve math, see [`crate::drive::SwerveDriveKinematics`] for other
+/// layouts
 pub struct Swerve;
 
 impl Swerve {
@@ -40,14 +42,7 @@ impl Swerve {
         let wa3 = a.atan2(d) * (180. / PI);
         let wa4 = a.atan2(c) * (180. / PI);
 
-        let mut max = ws1;
-        if ws2 > max {
-            max = ws2;
-        } else if ws3 > max {
-            max = ws3;
-        } else if ws4 > max {
-            max = ws4
-        }
+        let max = ws1.max(ws2).max(ws3).max(ws4);
         if max > 1. {
             ws1 /= max;
             ws2 /= max;
@@ -151,6 +146,23 @@ mod tests {
         assert_eq!((angle.0, in_range), (-1., 0.));
     }
 
+    #[test]
+    fn calc_normalizes_every_module() {
+        // module 3 is the fastest, which the old else-if chain skipped when module 2 was
+        // faster than module 1
+        let wheel_speeds = Swerve::calculate(-1., 1., -1., 0.);
+
+        let fastest = [
+            wheel_speeds.ws1,
+            wheel_speeds.ws2,
+            wheel_speeds.ws3,
+            wheel_speeds.ws4,
+        ]
+        .into_iter()
+        .fold(0., f64::max);
+        assert!((fastest - 1.).abs() < 1e-9);
+    }
+
     #[test]
     fn calc() {
         let wheel_speeds = Swerve::calculate(0.5, 0., 0., 0.);
@@ -0,0 +1,343 @@
+use nalgebra::{DMatrix, DVector};
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{Angle, AngularVelocity, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+
+use crate::drive::ChassisSpeeds;
+use crate::geometry::{Rotation2d, Translation2d, Twist2d};
+
+/// The speed and direction of one swerve module.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SwerveModuleState {
+    pub speed: Velocity,
+    pub angle: Rotation2d,
+}
+
+impl SwerveModuleState {
+    pub fn new(speed: Velocity, angle: Rotation2d) -> Self {
+        Self { speed, angle }
+    }
+
+    /// The equivalent state that turns the module less than 90° from `current`,
+    /// reversing the wheel if needed
+    pub fn optimize(&self, current: Rotation2d) -> Self {
+        if (self.angle - current).cos() < 0. {
+            Self::new(-self.speed, self.angle + Rotation2d::from_degrees(180.))
+        } else {
+            *self
+        }
+    }
+}
+
+/// How far one swerve module's wheel has rolled, and which way it points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SwerveModulePosition {
+    pub distance: Length,
+    pub angle: Rotation2d,
+}
+
+impl SwerveModulePosition {
+    pub fn new(distance: Length, angle: Rotation2d) -> Self {
+        Self { distance, angle }
+    }
+}
+
+/// Converts between chassis speeds and the states of any number of swerve modules.
+///
+/// Module positions are relative to the center of the robot, +x forward and +y left.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::drive::{ChassisSpeeds, SwerveDriveKinematics};
+/// use frcrs::geometry::Translation2d;
+/// use uom::si::f64::{AngularVelocity, Length, Velocity};
+/// use uom::si::velocity::meter_per_second;
+///
+/// let mut kinematics = SwerveDriveKinematics::new(vec![
+///     Translation2d::from_meters(0.3, 0.3),
+///     Translation2d::from_meters(0.3, -0.3),
+///     Translation2d::from_meters(-0.3, 0.3),
+///     Translation2d::from_meters(-0.3, -0.3),
+/// ]);
+///
+/// let speeds = ChassisSpeeds::new(
+///     Velocity::new::<meter_per_second>(2.),
+///     Velocity::default(),
+///     AngularVelocity::default(),
+/// );
+/// let mut states = kinematics.to_module_states(speeds);
+/// SwerveDriveKinematics::desaturate(&mut states, Velocity::new::<meter_per_second>(1.));
+///
+/// assert_eq!(states[0].speed, Velocity::new::<meter_per_second>(1.));
+/// ```
+#[derive(Clone)]
+pub struct SwerveDriveKinematics {
+    modules: Vec<Translation2d>,
+    headings: Vec<Rotation2d>,
+    forward: DMatrix<f64>,
+}
+
+impl SwerveDriveKinematics {
+    /// # Panics
+    /// If there are fewer than two modules.
+    pub fn new(modules: Vec<Translation2d>) -> Self {
+        assert!(modules.len() >= 2, "swerve needs at least two modules");
+
+        let inverse = Self::inverse_matrix(&modules, Translation2d::default());
+        let forward = inverse
+            .pseudo_inverse(1e-9)
+            .expect("module positions are degenerate");
+
+        Self {
+            headings: vec![Rotation2d::default(); modules.len()],
+            modules,
+            forward,
+        }
+    }
+
+    pub fn modules(&self) -> &[Translation2d] {
+        &self.modules
+    }
+
+    /// Set the angles modules hold while the robot is stopped
+    pub fn reset_headings(&mut self, headings: &[Rotation2d]) {
+        self.headings.copy_from_slice(headings);
+    }
+
+    /// Module states that drive the robot at `speeds`, in the order the modules were given.
+    ///
+    /// When `speeds` is zero, each module keeps its last angle instead of snapping to zero.
+    pub fn to_module_states(&mut self, speeds: ChassisSpeeds) -> Vec<SwerveModuleState> {
+        self.to_module_states_around(speeds, Translation2d::default())
+    }
+
+    /// Like [`SwerveDriveKinematics::to_module_states`], but rotating around
+    /// `center_of_rotation` instead of the center of the robot
+    pub fn to_module_states_around(
+        &mut self,
+        speeds: ChassisSpeeds,
+        center_of_rotation: Translation2d,
+    ) -> Vec<SwerveModuleState> {
+        let vx = speeds.vx.get::<meter_per_second>();
+        let vy = speeds.vy.get::<meter_per_second>();
+        let omega = speeds.omega.get::<radian_per_second>();
+
+        if vx == 0. && vy == 0. && omega == 0. {
+            return self
+                .headings
+                .iter()
+                .map(|&angle| SwerveModuleState::new(Velocity::default(), angle))
+                .collect();
+        }
+
+        let chassis = DVector::from_vec(vec![vx, vy, omega]);
+        let modules = Self::inverse_matrix(&self.modules, center_of_rotation) * chassis;
+
+        let states = modules
+            .as_slice()
+            .chunks(2)
+            .map(|v| {
+                SwerveModuleState::new(
+                    Velocity::new::<meter_per_second>(v[0].hypot(v[1])),
+                    Rotation2d::from_components(v[0], v[1]),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (heading, state) in self.headings.iter_mut().zip(&states) {
+            *heading = state.angle;
+        }
+
+        states
+    }
+
+    /// The chassis speeds that best fit the measured module states.
+    ///
+    /// # Panics
+    /// If `states` doesn't have one state per module.
+    pub fn to_chassis_speeds(&self, states: &[SwerveModuleState]) -> ChassisSpeeds {
+        assert_eq!(states.len(), self.modules.len(), "one state per module");
+
+        let modules = DVector::from_iterator(
+            states.len() * 2,
+            states.iter().flat_map(|state| {
+                let speed = state.speed.get::<meter_per_second>();
+                [speed * state.angle.cos(), speed * state.angle.sin()]
+            }),
+        );
+        let chassis = &self.forward * modules;
+
+        ChassisSpeeds::new(
+            Velocity::new::<meter_per_second>(chassis[0]),
+            Velocity::new::<meter_per_second>(chassis[1]),
+            AngularVelocity::new::<radian_per_second>(chassis[2]),
+        )
+    }
+
+    /// How the robot moved between two sets of module positions, assuming it followed an arc.
+    ///
+    /// # Panics
+    /// If either slice doesn't have one position per module.
+    pub fn to_twist(
+        &self,
+        start: &[SwerveModulePosition],
+        end: &[SwerveModulePosition],
+    ) -> Twist2d {
+        assert_eq!(start.len(), self.modules.len(), "one position per module");
+
+        let deltas = start
+            .iter()
+            .zip(end)
+            .map(|(start, end)| {
+                let distance = (end.distance - start.distance).get::<meter>();
+                SwerveModuleState::new(Velocity::new::<meter_per_second>(distance), end.angle)
+            })
+            .collect::<Vec<_>>();
+
+        // moving at these speeds for one second covers the same distance
+        let speeds = self.to_chassis_speeds(&deltas);
+        Twist2d::new(
+            Length::new::<meter>(speeds.vx.get::<meter_per_second>()),
+            Length::new::<meter>(speeds.vy.get::<meter_per_second>()),
+            Angle::new::<radian>(speeds.omega.get::<radian_per_second>()),
+        )
+    }
+
+    /// Scale every state down evenly so none is faster than `max_speed`, keeping the
+    /// ratios between them so the robot still moves in the requested direction
+    pub fn desaturate(states: &mut [SwerveModuleState], max_speed: Velocity) {
+        let fastest = states
+            .iter()
+            .map(|state| state.speed.abs())
+            .fold(Velocity::default(), Velocity::max);
+
+        if fastest > max_speed {
+            let scale = (max_speed / fastest).value;
+            for state in states {
+                state.speed *= scale;
+            }
+        }
+    }
+
+    // each module contributes the rows [1 0 -y] and [0 1 x]
+    fn inverse_matrix(
+        modules: &[Translation2d],
+        center_of_rotation: Translation2d,
+    ) -> DMatrix<f64> {
+        let mut matrix = DMatrix::zeros(modules.len() * 2, 3);
+
+        for (i, module) in modules.iter().enumerate() {
+            let offset = *module - center_of_rotation;
+            matrix[(i * 2, 0)] = 1.;
+            matrix[(i * 2, 2)] = -offset.y.get::<meter>();
+            matrix[(i * 2 + 1, 1)] = 1.;
+            matrix[(i * 2 + 1, 2)] = offset.x.get::<meter>();
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> SwerveDriveKinematics {
+        SwerveDriveKinematics::new(vec![
+            Translation2d::from_meters(0.5, 0.5),
+            Translation2d::from_meters(0.5, -0.5),
+            Translation2d::from_meters(-0.5, 0.5),
+            Translation2d::from_meters(-0.5, -0.5),
+        ])
+    }
+
+    fn mps(speed: f64) -> Velocity {
+        Velocity::new::<meter_per_second>(speed)
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn spin_in_place() {
+        let mut kinematics = square();
+        let speeds = ChassisSpeeds::new(
+            mps(0.),
+            mps(0.),
+            AngularVelocity::new::<radian_per_second>(1.),
+        );
+
+        let states = kinematics.to_module_states(speeds);
+
+        for state in &states {
+            assert_close(state.speed.get::<meter_per_second>(), 0.5f64.hypot(0.5));
+        }
+        assert_close(states[0].angle.degrees(), 135.);
+        assert_close(states[3].angle.degrees(), -45.);
+
+        let measured = kinematics.to_chassis_speeds(&states);
+        assert_close(measured.omega.get::<radian_per_second>(), 1.);
+        assert_close(measured.vx.get::<meter_per_second>(), 0.);
+    }
+
+    #[test]
+    fn rotate_around_a_module() {
+        let mut kinematics = square();
+        let speeds = ChassisSpeeds::new(
+            mps(0.),
+            mps(0.),
+            AngularVelocity::new::<radian_per_second>(1.),
+        );
+
+        let states =
+            kinematics.to_module_states_around(speeds, Translation2d::from_meters(0.5, 0.5));
+
+        assert_close(states[0].speed.get::<meter_per_second>(), 0.);
+        assert_close(states[1].speed.get::<meter_per_second>(), 1.);
+        assert_close(states[3].speed.get::<meter_per_second>(), 2f64.sqrt());
+    }
+
+    #[test]
+    fn keeps_heading_when_stopped() {
+        let mut kinematics = square();
+
+        kinematics.to_module_states(ChassisSpeeds::new(
+            mps(0.),
+            mps(1.),
+            AngularVelocity::default(),
+        ));
+        let states = kinematics.to_module_states(ChassisSpeeds::default());
+
+        assert_close(states[2].angle.degrees(), 90.);
+        assert_close(states[2].speed.get::<meter_per_second>(), 0.);
+    }
+
+    #[test]
+    fn desaturate_keeps_ratios() {
+        let mut states = [
+            SwerveModuleState::new(mps(4.), Rotation2d::default()),
+            SwerveModuleState::new(mps(-2.), Rotation2d::default()),
+            SwerveModuleState::new(mps(1.), Rotation2d::default()),
+        ];
+
+        SwerveDriveKinematics::desaturate(&mut states, mps(2.));
+
+        assert_close(states[0].speed.get::<meter_per_second>(), 2.);
+        assert_close(states[1].speed.get::<meter_per_second>(), -1.);
+        assert_close(states[2].speed.get::<meter_per_second>(), 0.5);
+    }
+
+    #[test]
+    fn optimize_reverses_wheel() {
+        let state = SwerveModuleState::new(mps(1.), Rotation2d::from_degrees(170.));
+
+        let optimized = state.optimize(Rotation2d::from_degrees(0.));
+
+        assert_close(optimized.speed.get::<meter_per_second>(), -1.);
+        assert_close(optimized.angle.degrees(), -10.);
+    }
+}
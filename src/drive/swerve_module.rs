@@ -0,0 +1,208 @@
+use std::f64::consts::PI;
+
+use uom::si::angle::revolution;
+use uom::si::angular_velocity::revolution_per_second;
+use uom::si::f64::{Angle, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+
+use crate::ctre::{CanCoder, Talon};
+use crate::drive::{SwerveModulePosition, SwerveModuleState};
+use crate::geometry::Rotation2d;
+use crate::hardware::{AbsoluteEncoder, Encoder, MotorController};
+
+/// The measurements of one swerve module.
+#[derive(Clone, Copy, Debug)]
+pub struct SwerveModuleConfig {
+    /// Drive motor rotations per wheel rotation
+    pub drive_gear_ratio: f64,
+    pub wheel_diameter: Length,
+    /// What the absolute encoder reads when the wheel points forward
+    pub encoder_offset: Angle,
+    /// Wheel speed at full output, used to convert speeds into duty cycles
+    pub max_speed: Velocity,
+    /// Steer output per radian of angle error
+    pub steer_p: f64,
+}
+
+/// A drive motor, a steer motor and an absolute encoder on the steering axis.
+///
+/// The drive motor runs open loop, scaled by the cosine of the steering error so the wheel
+/// doesn't push sideways while it turns. Steering is a proportional loop on the absolute
+/// encoder, so the module never needs homing.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::ctre::{CanCoder, Talon};
+/// use frcrs::drive::{SwerveModule, SwerveModuleConfig, SwerveModuleState};
+/// use frcrs::geometry::Rotation2d;
+/// use uom::si::angle::degree;
+/// use uom::si::f64::{Angle, Length, Velocity};
+/// use uom::si::length::inch;
+/// use uom::si::velocity::meter_per_second;
+///
+/// let config = SwerveModuleConfig {
+///     drive_gear_ratio: 6.75,
+///     wheel_diameter: Length::new::<inch>(4.),
+///     encoder_offset: Angle::new::<degree>(37.),
+///     max_speed: Velocity::new::<meter_per_second>(4.5),
+///     steer_p: 0.8,
+/// };
+///
+/// let mut front_left = SwerveModule::new(
+///     Talon::new(1, None),
+///     Talon::new(2, None),
+///     CanCoder::new(3, None),
+///     config,
+/// );
+///
+/// front_left.set_desired_state(SwerveModuleState::new(
+///     Velocity::new::<meter_per_second>(1.),
+///     Rotation2d::from_degrees(90.),
+/// ));
+/// ```
+pub struct SwerveModule<D = Talon, S = Talon, E = CanCoder> {
+    drive: D,
+    steer: S,
+    encoder: E,
+    config: SwerveModuleConfig,
+}
+
+impl<D, S, E> SwerveModule<D, S, E>
+where
+    D: MotorController + Encoder,
+    S: MotorController,
+    E: AbsoluteEncoder,
+{
+    pub fn new(drive: D, steer: S, encoder: E, config: SwerveModuleConfig) -> Self {
+        Self {
+            drive,
+            steer,
+            encoder,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &SwerveModuleConfig {
+        &self.config
+    }
+
+    /// Which way the wheel points, zero being forward
+    pub fn angle(&mut self) -> Rotation2d {
+        Rotation2d::new(self.encoder.absolute_position() - self.config.encoder_offset)
+    }
+
+    pub fn get_state(&mut self) -> SwerveModuleState {
+        let wheel_rotations =
+            self.drive.velocity().get::<revolution_per_second>() / self.config.drive_gear_ratio;
+        let speed = self.circumference().get::<meter>() * wheel_rotations;
+
+        SwerveModuleState::new(Velocity::new::<meter_per_second>(speed), self.angle())
+    }
+
+    pub fn get_position(&mut self) -> SwerveModulePosition {
+        let wheel_rotations =
+            self.drive.position().get::<revolution>() / self.config.drive_gear_ratio;
+
+        SwerveModulePosition::new(self.circumference() * wheel_rotations, self.angle())
+    }
+
+    /// Steer towards `state` the short way round and drive at its speed
+    pub fn set_desired_state(&mut self, state: SwerveModuleState) {
+        let current = self.angle();
+        let state = state.optimize(current);
+        let error = state.angle - current;
+
+        let drive = (state.speed / self.config.max_speed).value * error.cos();
+        self.drive.set_percent(drive.clamp(-1., 1.));
+        self.steer
+            .set_percent((error.radians() * self.config.steer_p).clamp(-1., 1.));
+    }
+
+    pub fn stop(&mut self) {
+        self.drive.stop();
+        self.steer.stop();
+    }
+
+    fn circumference(&self) -> Length {
+        self.config.wheel_diameter * PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::fake::{FakeAbsoluteEncoder, FakeMotor};
+    use uom::si::angle::degree;
+    use uom::si::angular_velocity::revolution_per_second;
+    use uom::si::f64::AngularVelocity;
+
+    fn module() -> (
+        SwerveModule<FakeMotor, FakeMotor, FakeAbsoluteEncoder>,
+        FakeMotor,
+        FakeMotor,
+        FakeAbsoluteEncoder,
+    ) {
+        let drive = FakeMotor::new();
+        let steer = FakeMotor::new();
+        let encoder = FakeAbsoluteEncoder::new(Angle::new::<degree>(30.));
+
+        let config = SwerveModuleConfig {
+            drive_gear_ratio: 5.,
+            wheel_diameter: Length::new::<meter>(1. / PI),
+            encoder_offset: Angle::new::<degree>(30.),
+            max_speed: Velocity::new::<meter_per_second>(4.),
+            steer_p: 0.5,
+        };
+        let module = SwerveModule::new(drive.clone(), steer.clone(), encoder.clone(), config);
+
+        (module, drive, steer, encoder)
+    }
+
+    #[test]
+    fn measures_wheel_travel() {
+        let (mut module, drive, _, encoder) = module();
+
+        drive.set_position(Angle::new::<revolution>(10.));
+        drive.set_velocity(AngularVelocity::new::<revolution_per_second>(5.));
+        encoder.set(Angle::new::<degree>(120.));
+
+        let position = module.get_position();
+        assert!((position.distance.get::<meter>() - 2.).abs() < 1e-9);
+        assert!((position.angle.degrees() - 90.).abs() < 1e-9);
+
+        let state = module.get_state();
+        assert!((state.speed.get::<meter_per_second>() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drives_forward_when_aligned() {
+        let (mut module, drive, steer, _) = module();
+
+        module.set_desired_state(SwerveModuleState::new(
+            Velocity::new::<meter_per_second>(2.),
+            Rotation2d::default(),
+        ));
+
+        assert!((drive.output() - 0.5).abs() < 1e-9);
+        assert!(steer.output().abs() < 1e-9);
+    }
+
+    #[test]
+    fn reverses_instead_of_turning_around() {
+        let (mut module, drive, steer, encoder) = module();
+        // pointing 10° left of forward, asked to point backwards
+        encoder.set(Angle::new::<degree>(40.));
+
+        module.set_desired_state(SwerveModuleState::new(
+            Velocity::new::<meter_per_second>(2.),
+            Rotation2d::from_degrees(180.),
+        ));
+
+        // turns 10° clockwise and drives backwards, slowed by the remaining error
+        assert!(steer.output() < 0.);
+        let expected = -0.5 * 10f64.to_radians().cos();
+        assert!((drive.output() - expected).abs() < 1e-9);
+    }
+}
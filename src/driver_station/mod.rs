@@ -0,0 +1,231 @@
+//! Where the robot gets its control word, match data and joystick readings from.
+//!
+//! By default everything is read from the WPILib HAL through JNI. Tests can install a
+//! [`SimDriverStation`] with [`set_source`] to drive the robot without a JVM. The source is
+//! per-thread, matching the single threaded `LocalSet` the robot runs in.
+//!
+//! [`NativeDriverStation`] speaks the driver station's network protocol directly, for running
+//! robot code without the HAL.
+
+mod native;
+pub mod protocol;
+mod sim;
+
+pub use native::*;
+pub use sim::*;
+
+use std::cell::RefCell;
+
+use jni::objects::JValue;
+use jni::signature::{Primitive, ReturnType};
+
+use crate::call::{call_static, try_call_static};
+use crate::error::FrcError;
+use crate::input::RobotState;
+use crate::AllianceStation;
+
+/// A provider of driver station data.
+///
+/// Methods are called while the current source is borrowed, so they must not call back into
+/// functions that read the driver station, like [`crate::alliance_station`] or
+/// [`RobotState::get`], which would panic.
+pub trait DriverStationSource {
+    /// Initialize the HAL
+    fn init(&mut self) -> Result<(), FrcError>;
+
+    /// Show "robot code" on the driver station
+    fn observe_user_program_starting(&mut self);
+
+    /// Pull the latest data from the driver station, called once per loop
+    fn refresh(&mut self);
+
+    fn robot_state(&mut self) -> RobotState;
+    fn alliance_station(&mut self) -> AllianceStation;
+
+    /// Seconds left in the current period of the match
+    fn match_time(&mut self) -> f64;
+
+    fn stick_axis(&mut self, stick: i32, axis: i32) -> f64;
+
+    /// Button states as a bitfield, button 1 is the least significant bit
+    fn stick_buttons(&mut self, stick: i32) -> u32;
+
+    /// Angle of a POV hat in degrees, or -1 when released
+    fn stick_pov(&mut self, stick: i32, pov: i32) -> i32;
+
+    /// Set the rumble of a controller, each side from 0 to 1
+    fn set_rumble(&mut self, stick: i32, left: f64, right: f64);
+
+    /// Whether the robot loop should return, the real driver station never asks for this
+    fn shutdown_requested(&mut self) -> bool {
+        false
+    }
+}
+
+thread_local! {
+    static SOURCE: RefCell<Box<dyn DriverStationSource>> = RefCell::new(Box::new(HalDriverStation));
+    static DEFERRED: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
+}
+
+/// Replace the driver station source for the current thread
+pub fn set_source(source: impl DriverStationSource + 'static) {
+    SOURCE.with(|cell| *cell.borrow_mut() = Box::new(source));
+}
+
+pub(crate) fn with_source<T>(f: impl FnOnce(&mut dyn DriverStationSource) -> T) -> T {
+    let result = SOURCE.with(|cell| f(cell.borrow_mut().as_mut()));
+
+    // run anything the source deferred now that it isn't borrowed
+    loop {
+        let deferred = DEFERRED.with(|cell| std::mem::take(&mut *cell.borrow_mut()));
+        if deferred.is_empty() {
+            break result;
+        }
+        for action in deferred {
+            action();
+        }
+    }
+}
+
+/// Run `action` once the current source is no longer borrowed, so it may read the driver station
+pub(crate) fn defer(action: impl FnOnce() + 'static) {
+    DEFERRED.with(|cell| cell.borrow_mut().push(Box::new(action)));
+}
+
+/// Reads the driver station through the WPILib HAL.
+pub struct HalDriverStation;
+
+impl DriverStationSource for HalDriverStation {
+    fn init(&mut self) -> Result<(), FrcError> {
+        let initialized = try_call_static!(
+            "edu/wpi/first/hal/HAL",
+            "initialize",
+            "(II)Z",
+            &[JValue::Int(500).as_jni(), JValue::Int(1).as_jni()],
+            ReturnType::Primitive(Primitive::Boolean)
+        )?
+        .z()?;
+
+        if initialized {
+            Ok(())
+        } else {
+            Err(FrcError::HalInit)
+        }
+    }
+
+    fn observe_user_program_starting(&mut self) {
+        call_static!(
+            "edu/wpi/first/hal/DriverStationJNI",
+            "observeUserProgramStarting",
+            "()V",
+            &Vec::new(),
+            ReturnType::Primitive(Primitive::Void)
+        );
+    }
+
+    fn refresh(&mut self) {
+        call_static!(
+            "edu/wpi/first/wpilibj/DriverStation",
+            "refreshData",
+            "()V",
+            &Vec::new(),
+            ReturnType::Primitive(Primitive::Void)
+        );
+    }
+
+    fn robot_state(&mut self) -> RobotState {
+        let word = call_static!(
+            "edu/wpi/first/hal/DriverStationJNI",
+            "nativeGetControlWord",
+            "()I",
+            &[],
+            ReturnType::Primitive(Primitive::Int)
+        )
+        .i()
+        .unwrap();
+
+        RobotState::from_control_word(word)
+    }
+
+    fn alliance_station(&mut self) -> AllianceStation {
+        let station = call_static!(
+            "frc/robot/Wrapper",
+            "getAllianceStation",
+            "()I",
+            &Vec::new(),
+            ReturnType::Primitive(Primitive::Int)
+        )
+        .i()
+        .unwrap();
+
+        AllianceStation(station as u8)
+    }
+
+    fn match_time(&mut self) -> f64 {
+        call_static!(
+            "frc/robot/Wrapper",
+            "getMatchTime",
+            "()D",
+            &Vec::new(),
+            ReturnType::Primitive(Primitive::Double)
+        )
+        .d()
+        .unwrap()
+    }
+
+    fn stick_axis(&mut self, stick: i32, axis: i32) -> f64 {
+        call_static!(
+            "edu/wpi/first/wpilibj/DriverStation",
+            "getStickAxis",
+            "(II)D",
+            &[JValue::Int(stick).as_jni(), JValue::Int(axis).as_jni()],
+            ReturnType::Primitive(Primitive::Double)
+        )
+        .d()
+        .unwrap()
+    }
+
+    fn stick_buttons(&mut self, stick: i32) -> u32 {
+        call_static!(
+            "edu/wpi/first/wpilibj/DriverStation",
+            "getStickButtons",
+            "(I)I",
+            &[JValue::Int(stick).as_jni()],
+            ReturnType::Primitive(Primitive::Int)
+        )
+        .i()
+        .unwrap() as u32
+    }
+
+    fn stick_pov(&mut self, stick: i32, pov: i32) -> i32 {
+        call_static!(
+            "edu/wpi/first/wpilibj/DriverStation",
+            "getStickPOV",
+            "(II)I",
+            &[JValue::Int(stick).as_jni(), JValue::Int(pov).as_jni()],
+            ReturnType::Primitive(Primitive::Int)
+        )
+        .i()
+        .unwrap()
+    }
+
+    fn set_rumble(&mut self, stick: i32, left: f64, right: f64) {
+        // same scaling as GenericHID.setRumble
+        let scale = |value: f64| (value.clamp(0., 1.) * 65535.) as u16 as i16;
+
+        call_static!(
+            "edu/wpi/first/hal/DriverStationJNI",
+            "setJoystickOutputs",
+            "(BISS)I",
+            &[
+                JValue::Byte(stick as i8).as_jni(),
+                JValue::Int(0).as_jni(),
+                JValue::Short(scale(left)).as_jni(),
+                JValue::Short(scale(right)).as_jni()
+            ],
+            ReturnType::Primitive(Primitive::Int)
+        )
+        .i()
+        .unwrap();
+    }
+}
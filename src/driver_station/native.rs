@@ -0,0 +1,525 @@
+use std::io::{self, ErrorKind, Read};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::FrcError;
+use crate::input::RobotState;
+use crate::AllianceStation;
+
+use super::protocol::{
+    Control, ControlPacket, JoystickData, MatchInfo, StatusPacket, TcpTag, DS_UDP_PORT,
+    ROBOT_TCP_PORT, ROBOT_UDP_PORT,
+};
+use super::DriverStationSource;
+
+/// How often the network threads check whether they should exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Longest wait after repeated socket errors, so a broken socket doesn't spin or flood stderr
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Where to listen for the driver station.
+#[derive(Clone, Debug)]
+pub struct NativeConfig {
+    pub address: IpAddr,
+    /// Port control packets arrive on
+    pub udp_port: u16,
+    /// Port on the driver station status replies are sent to
+    pub reply_port: u16,
+    /// Port match info and game data arrive on
+    pub tcp_port: u16,
+    /// The robot is disabled if no control packet arrives for this long
+    pub timeout: Duration,
+}
+
+impl Default for NativeConfig {
+    fn default() -> Self {
+        Self {
+            address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            udp_port: ROBOT_UDP_PORT,
+            reply_port: DS_UDP_PORT,
+            tcp_port: ROBOT_TCP_PORT,
+            timeout: Duration::from_millis(500),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    latest: Option<(Instant, ControlPacket)>,
+    match_info: MatchInfo,
+    game_data: String,
+    battery_voltage: f64,
+    robot_code: bool,
+    brownout: bool,
+    /// The mode the robot code is running in, as of the last refresh
+    control: Control,
+}
+
+struct Connection {
+    shared: Arc<Mutex<Shared>>,
+    running: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+    udp_addr: SocketAddr,
+    tcp_addr: SocketAddr,
+    timeout: Duration,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Talks to the driver station directly, without going through the HAL.
+///
+/// Packets are received on background threads, and [`DriverStationSource::refresh`] takes a
+/// snapshot of the latest one, the same way `DriverStation.refreshData` works in WPILib.
+///
+/// On a roboRIO the NI communication daemon already owns the driver station ports, so this is
+/// meant for running robot code on other hardware or against a stand-in driver station.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::driver_station::{set_source, NativeDriverStation};
+///
+/// set_source(NativeDriverStation::start().unwrap());
+/// ```
+#[derive(Clone)]
+pub struct NativeDriverStation {
+    connection: Arc<Connection>,
+    snapshot: Option<ControlPacket>,
+}
+
+impl NativeDriverStation {
+    /// Listen on the standard driver station ports
+    pub fn start() -> io::Result<Self> {
+        Self::with_config(NativeConfig::default())
+    }
+
+    pub fn with_config(config: NativeConfig) -> io::Result<Self> {
+        let udp = UdpSocket::bind((config.address, config.udp_port))?;
+        udp.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let tcp = TcpListener::bind((config.address, config.tcp_port))?;
+        tcp.set_nonblocking(true)?;
+
+        let shared = Arc::new(Mutex::new(Shared {
+            battery_voltage: 12.,
+            ..Default::default()
+        }));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let udp_addr = udp.local_addr()?;
+        let tcp_addr = tcp.local_addr()?;
+
+        let threads = vec![
+            {
+                let shared = shared.clone();
+                let running = running.clone();
+                thread::spawn(move || udp_loop(udp, config.reply_port, shared, running))
+            },
+            {
+                let shared = shared.clone();
+                let running = running.clone();
+                thread::spawn(move || tcp_loop(tcp, shared, running))
+            },
+        ];
+
+        Ok(Self {
+            connection: Arc::new(Connection {
+                shared,
+                running,
+                threads,
+                udp_addr,
+                tcp_addr,
+                timeout: config.timeout,
+            }),
+            snapshot: None,
+        })
+    }
+
+    /// Address control packets are received on
+    pub fn udp_addr(&self) -> SocketAddr {
+        self.connection.udp_addr
+    }
+
+    /// Address the driver station's TCP connection is accepted on
+    pub fn tcp_addr(&self) -> SocketAddr {
+        self.connection.tcp_addr
+    }
+
+    /// Battery voltage reported to the driver station
+    pub fn set_battery_voltage(&self, volts: f64) {
+        self.shared().battery_voltage = volts;
+    }
+
+    /// Whether the driver station shows the robot as browned out
+    pub fn set_brownout(&self, brownout: bool) {
+        self.shared().brownout = brownout;
+    }
+
+    pub fn match_info(&self) -> MatchInfo {
+        self.shared().match_info.clone()
+    }
+
+    pub fn game_data(&self) -> String {
+        self.shared().game_data.clone()
+    }
+
+    /// Whether a control packet arrived within the timeout, as of the last refresh
+    pub fn connected(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    fn shared(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.connection.shared.lock().unwrap()
+    }
+
+    fn joystick<T: Default>(&self, stick: i32, f: impl FnOnce(&JoystickData) -> T) -> T {
+        self.snapshot
+            .as_ref()
+            .and_then(|packet| packet.joysticks.get(stick as usize))
+            .map(f)
+            .unwrap_or_default()
+    }
+}
+
+fn udp_loop(
+    socket: UdpSocket,
+    reply_port: u16,
+    shared: Arc<Mutex<Shared>>,
+    running: Arc<AtomicBool>,
+) {
+    let mut buffer = [0u8; 1500];
+    let mut backoff = POLL_INTERVAL;
+
+    while running.load(Ordering::SeqCst) {
+        let (len, from) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => {
+                eprintln!("Driver station socket error: {}", e);
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        };
+        backoff = POLL_INTERVAL;
+
+        let packet = match ControlPacket::decode(&buffer[..len]) {
+            Ok(packet) => packet,
+            Err(e) => {
+                eprintln!("Bad driver station packet: {}", e);
+                continue;
+            }
+        };
+
+        let reply = {
+            let mut shared = shared.lock().unwrap();
+            let reply = StatusPacket {
+                sequence: packet.sequence,
+                control: shared.control,
+                brownout: shared.brownout,
+                robot_code: shared.robot_code,
+                battery_voltage: shared.battery_voltage,
+            };
+            shared.latest = Some((Instant::now(), packet));
+            reply
+        };
+
+        if let Err(e) = socket.send_to(&reply.encode(), (from.ip(), reply_port)) {
+            eprintln!("Failed to reply to driver station: {}", e);
+        }
+    }
+}
+
+fn tcp_loop(listener: TcpListener, shared: Arc<Mutex<Shared>>, running: Arc<AtomicBool>) {
+    let mut backoff = POLL_INTERVAL;
+
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                backoff = POLL_INTERVAL;
+
+                let shared = shared.clone();
+                let running = running.clone();
+                thread::spawn(move || {
+                    if let Err(e) = tcp_connection(stream, shared, running) {
+                        eprintln!("Driver station TCP connection closed: {}", e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                eprintln!("Failed to accept driver station connection: {}", e);
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+fn tcp_connection(
+    mut stream: TcpStream,
+    shared: Arc<Mutex<Shared>>,
+    running: Arc<AtomicBool>,
+) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let mut pending = Vec::new();
+    let mut buffer = [0u8; 1024];
+
+    while running.load(Ordering::SeqCst) {
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(len) => pending.extend_from_slice(&buffer[..len]),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e),
+        }
+
+        loop {
+            let (tag, used) = match TcpTag::decode(&pending) {
+                Ok(Some(decoded)) => decoded,
+                Ok(None) => break,
+                Err(e) => return Err(io::Error::new(ErrorKind::InvalidData, e)),
+            };
+            pending.drain(..used);
+
+            let mut shared = shared.lock().unwrap();
+            match tag {
+                TcpTag::MatchInfo(info) => shared.match_info = info,
+                TcpTag::GameData(data) => shared.game_data = data,
+                TcpTag::Other(_) => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl DriverStationSource for NativeDriverStation {
+    fn init(&mut self) -> Result<(), FrcError> {
+        Ok(())
+    }
+
+    fn observe_user_program_starting(&mut self) {
+        self.shared().robot_code = true;
+    }
+
+    fn refresh(&mut self) {
+        let timeout = self.connection.timeout;
+        let mut shared = self.shared();
+        let latest = shared
+            .latest
+            .as_ref()
+            .filter(|(received, _)| received.elapsed() < timeout)
+            .map(|(_, packet)| packet.clone());
+
+        // status replies report what the robot code sees, which is disabled without a packet
+        shared.control = latest
+            .as_ref()
+            .map(|packet| packet.control)
+            .unwrap_or_default();
+        drop(shared);
+
+        self.snapshot = latest;
+    }
+
+    fn robot_state(&mut self) -> RobotState {
+        // without a driver station the robot must stay disabled
+        let word = self
+            .snapshot
+            .as_ref()
+            .map(|packet| packet.control.control_word())
+            .unwrap_or(0);
+
+        RobotState::from_control_word(word)
+    }
+
+    fn alliance_station(&mut self) -> AllianceStation {
+        self.snapshot
+            .as_ref()
+            // stations 0..=5 on the wire are red 1 through blue 3, anything else is unknown
+            .and_then(|packet| packet.station.checked_add(1).filter(|&s| s <= 6))
+            .map(AllianceStation)
+            .unwrap_or(AllianceStation(0))
+    }
+
+    fn match_time(&mut self) -> f64 {
+        self.snapshot
+            .as_ref()
+            .and_then(|packet| packet.countdown)
+            .map(|countdown| countdown as f64)
+            .unwrap_or(-1.)
+    }
+
+    fn stick_axis(&mut self, stick: i32, axis: i32) -> f64 {
+        self.joystick(stick, |joystick| joystick.axis(axis as usize))
+    }
+
+    fn stick_buttons(&mut self, stick: i32) -> u32 {
+        self.joystick(stick, |joystick| joystick.button_word())
+    }
+
+    fn stick_pov(&mut self, stick: i32, pov: i32) -> i32 {
+        self.snapshot
+            .as_ref()
+            .and_then(|packet| packet.joysticks.get(stick as usize))
+            .and_then(|joystick| joystick.povs.get(pov as usize))
+            .map(|&angle| angle as i32)
+            .unwrap_or(-1)
+    }
+
+    /// Joystick outputs aren't part of the UDP protocol, so rumble is ignored
+    fn set_rumble(&mut self, _stick: i32, _left: f64, _right: f64) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver_station::protocol::{Control, MatchType, Mode};
+    use crate::input::RobotMode;
+    use std::io::Write;
+
+    fn robot() -> (NativeDriverStation, UdpSocket) {
+        let ds = UdpSocket::bind("127.0.0.1:0").unwrap();
+        ds.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+
+        let robot = NativeDriverStation::with_config(NativeConfig {
+            address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            udp_port: 0,
+            reply_port: ds.local_addr().unwrap().port(),
+            tcp_port: 0,
+            timeout: Duration::from_millis(200),
+        })
+        .unwrap();
+
+        (robot, ds)
+    }
+
+    fn send(ds: &UdpSocket, robot: &NativeDriverStation, packet: &ControlPacket) -> StatusPacket {
+        ds.send_to(&packet.encode(), robot.udp_addr()).unwrap();
+
+        let mut buffer = [0u8; 64];
+        let len = ds.recv(&mut buffer).unwrap();
+        StatusPacket::decode(&buffer[..len]).unwrap()
+    }
+
+    #[test]
+    fn control_and_status() {
+        let (mut robot, ds) = robot();
+        robot.observe_user_program_starting();
+        robot.set_battery_voltage(12.25);
+
+        let packet = ControlPacket {
+            sequence: 99,
+            control: Control {
+                mode: Mode::Autonomous,
+                enabled: true,
+                ..Default::default()
+            },
+            station: 3,
+            countdown: Some(14.),
+            joysticks: vec![JoystickData {
+                axes: vec![0, -64],
+                buttons: vec![false, true],
+                povs: vec![180],
+            }],
+            ..Default::default()
+        };
+
+        let status = send(&ds, &robot, &packet);
+        assert_eq!(status.sequence, 99);
+        assert!(status.robot_code);
+        assert_eq!(status.battery_voltage, 12.25);
+        // the robot code hasn't picked up the packet yet
+        assert_eq!(status.control, Control::default());
+        assert!(!status.brownout);
+
+        robot.refresh();
+        robot.set_brownout(true);
+
+        let status = send(&ds, &robot, &packet);
+        assert_eq!(status.control, packet.control);
+        assert!(status.brownout);
+
+        assert_eq!(robot.robot_state().mode(), RobotMode::Auto);
+        assert!(robot.alliance_station().blue());
+        assert_eq!(robot.match_time(), 14.);
+        assert_eq!(robot.stick_axis(0, 1), -0.5);
+        assert_eq!(robot.stick_buttons(0), 0b10);
+        assert_eq!(robot.stick_pov(0, 0), 180);
+        assert_eq!(robot.stick_pov(1, 0), -1);
+    }
+
+    #[test]
+    fn invalid_station_is_unknown() {
+        let (mut robot, ds) = robot();
+
+        let packet = ControlPacket {
+            station: 255,
+            ..Default::default()
+        };
+        send(&ds, &robot, &packet);
+
+        robot.refresh();
+        assert_eq!(robot.alliance_station(), AllianceStation(0));
+    }
+
+    #[test]
+    fn disables_when_packets_stop() {
+        let (mut robot, ds) = robot();
+
+        let packet = ControlPacket {
+            control: Control {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        send(&ds, &robot, &packet);
+
+        robot.refresh();
+        assert!(robot.robot_state().enabled());
+
+        thread::sleep(Duration::from_millis(250));
+        robot.refresh();
+        assert!(!robot.connected());
+        assert!(!robot.robot_state().enabled());
+    }
+
+    #[test]
+    fn match_info_over_tcp() {
+        let (robot, _ds) = robot();
+
+        let mut stream = TcpStream::connect(robot.tcp_addr()).unwrap();
+        let info = MatchInfo {
+            event_name: "MNDU".to_string(),
+            match_type: MatchType::Qualification,
+            match_number: 12,
+            replay_number: 0,
+        };
+        stream
+            .write_all(&TcpTag::MatchInfo(info.clone()).encode())
+            .unwrap();
+        stream
+            .write_all(&TcpTag::GameData("B".to_string()).encode())
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while robot.game_data().is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(robot.game_data(), "B");
+        assert_eq!(robot.match_info(), info);
+    }
+}
@@ -0,0 +1,612 @@
+//! Encoding and decoding of the FRC driver station protocol (2015 onwards).
+//!
+//! The driver station sends a [`ControlPacket`] over UDP to port 1110 on the robot every 20ms,
+//! and the robot answers each one with a [`StatusPacket`] to port 1150 on the driver station.
+//! Data that doesn't change every packet, like match info and game data, is sent over TCP port
+//! 1740 as [`TcpTag`]s.
+//!
+//! Both directions are implemented so the same types can be used to write a stand-in driver
+//! station for testing.
+
+use thiserror::Error;
+
+pub const ROBOT_UDP_PORT: u16 = 1110;
+pub const DS_UDP_PORT: u16 = 1150;
+pub const ROBOT_TCP_PORT: u16 = 1740;
+
+const COMM_VERSION: u8 = 0x01;
+
+const TAG_COUNTDOWN: u8 = 0x07;
+const TAG_JOYSTICK: u8 = 0x0c;
+
+const TCP_TAG_MATCH_INFO: u8 = 0x07;
+const TCP_TAG_GAME_DATA: u8 = 0x0e;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProtocolError {
+    #[error("packet too short, expected at least {expected} bytes but got {actual}")]
+    TooShort { expected: usize, actual: usize },
+
+    #[error("unsupported protocol version {0}")]
+    Version(u8),
+
+    #[error("malformed tag 0x{0:02x}")]
+    MalformedTag(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Teleop,
+    Test,
+    Autonomous,
+}
+
+impl Mode {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            1 => Mode::Test,
+            2 => Mode::Autonomous,
+            _ => Mode::Teleop,
+        }
+    }
+
+    fn bits(&self) -> u8 {
+        match self {
+            Mode::Teleop => 0,
+            Mode::Test => 1,
+            Mode::Autonomous => 2,
+        }
+    }
+}
+
+/// Control flags sent by the driver station.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Control {
+    pub mode: Mode,
+    pub enabled: bool,
+    pub fms_attached: bool,
+    pub emergency_stop: bool,
+}
+
+impl Control {
+    fn from_byte(byte: u8) -> Self {
+        Self {
+            mode: Mode::from_bits(byte),
+            enabled: byte & 0x04 != 0,
+            fms_attached: byte & 0x08 != 0,
+            emergency_stop: byte & 0x80 != 0,
+        }
+    }
+
+    fn byte(&self) -> u8 {
+        let mut byte = self.mode.bits();
+        if self.enabled {
+            byte |= 0x04;
+        }
+        if self.fms_attached {
+            byte |= 0x08;
+        }
+        if self.emergency_stop {
+            byte |= 0x80;
+        }
+        byte
+    }
+
+    /// The control word as the HAL reports it
+    pub fn control_word(&self) -> i32 {
+        let mut word = 0;
+        if self.enabled {
+            word |= 1;
+        }
+        match self.mode {
+            Mode::Autonomous => word |= 1 << 1,
+            Mode::Test => word |= 1 << 2,
+            Mode::Teleop => {}
+        }
+        if self.emergency_stop {
+            word |= 1 << 3;
+        }
+        if self.fms_attached {
+            word |= 1 << 4;
+        }
+        word | 1 << 5
+    }
+}
+
+/// Joystick state as sent by the driver station.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct JoystickData {
+    /// Raw axis readings, -128 to 127
+    pub axes: Vec<i8>,
+    /// Button states, button 1 first
+    pub buttons: Vec<bool>,
+    /// POV angles in degrees, -1 when released
+    pub povs: Vec<i16>,
+}
+
+impl JoystickData {
+    /// Axis reading scaled to -1 to 1
+    pub fn axis(&self, axis: usize) -> f64 {
+        match self.axes.get(axis) {
+            Some(&raw) if raw < 0 => raw as f64 / 128.,
+            Some(&raw) => raw as f64 / 127.,
+            None => 0.,
+        }
+    }
+
+    /// Button states as a bitfield, button 1 is the least significant bit
+    pub fn button_word(&self) -> u32 {
+        self.buttons
+            .iter()
+            .take(32)
+            .enumerate()
+            .fold(0, |word, (i, &pressed)| word | (pressed as u32) << i)
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, ProtocolError> {
+        let malformed = || ProtocolError::MalformedTag(TAG_JOYSTICK);
+        let mut cursor = data.iter().copied();
+        let mut next = || cursor.next().ok_or_else(malformed);
+
+        let axis_count = next()? as usize;
+        let axes = (0..axis_count)
+            .map(|_| next().map(|b| b as i8))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let button_count = next()? as usize;
+        let button_bytes = (0..button_count.div_ceil(8))
+            .map(|_| next())
+            .collect::<Result<Vec<_>, _>>()?;
+        // buttons are packed big endian, button 1 in the low bit of the last byte
+        let buttons = (0..button_count)
+            .map(|i| button_bytes[button_bytes.len() - 1 - i / 8] & (1 << (i % 8)) != 0)
+            .collect();
+
+        let pov_count = next()? as usize;
+        let povs = (0..pov_count)
+            .map(|_| Ok(i16::from_be_bytes([next()?, next()?])))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            axes,
+            buttons,
+            povs,
+        })
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.axes.len() as u8);
+        out.extend(self.axes.iter().map(|&a| a as u8));
+
+        out.push(self.buttons.len() as u8);
+        let mut bytes = vec![0u8; self.buttons.len().div_ceil(8)];
+        let len = bytes.len();
+        for (i, &pressed) in self.buttons.iter().enumerate() {
+            if pressed {
+                bytes[len - 1 - i / 8] |= 1 << (i % 8);
+            }
+        }
+        out.extend(bytes);
+
+        out.push(self.povs.len() as u8);
+        for pov in &self.povs {
+            out.extend(pov.to_be_bytes());
+        }
+    }
+}
+
+/// Packet sent from the driver station to the robot.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ControlPacket {
+    pub sequence: u16,
+    pub control: Control,
+    pub request_reboot: bool,
+    pub request_restart_code: bool,
+    /// 0 to 2 for red 1 to 3, 3 to 5 for blue 1 to 3
+    pub station: u8,
+    /// Seconds left in the period, if the driver station is counting down
+    pub countdown: Option<f32>,
+    /// One entry per joystick slot, in order
+    pub joysticks: Vec<JoystickData>,
+}
+
+impl ControlPacket {
+    pub fn decode(packet: &[u8]) -> Result<Self, ProtocolError> {
+        if packet.len() < 6 {
+            return Err(ProtocolError::TooShort {
+                expected: 6,
+                actual: packet.len(),
+            });
+        }
+
+        if packet[2] != COMM_VERSION {
+            return Err(ProtocolError::Version(packet[2]));
+        }
+
+        let mut decoded = Self {
+            sequence: u16::from_be_bytes([packet[0], packet[1]]),
+            control: Control::from_byte(packet[3]),
+            request_reboot: packet[4] & 0x08 != 0,
+            request_restart_code: packet[4] & 0x04 != 0,
+            station: packet[5],
+            countdown: None,
+            joysticks: Vec::new(),
+        };
+
+        let mut rest = &packet[6..];
+        while let Some((&size, tail)) = rest.split_first() {
+            let size = size as usize;
+            // an empty tag has no id byte, the next byte already belongs to the following tag
+            if size == 0 {
+                return Err(ProtocolError::MalformedTag(0));
+            }
+
+            let Some(&id) = tail.first() else {
+                return Err(ProtocolError::TooShort {
+                    expected: packet.len() + size,
+                    actual: packet.len(),
+                });
+            };
+            if tail.len() < size {
+                return Err(ProtocolError::MalformedTag(id));
+            }
+
+            let data = &tail[1..size];
+            match id {
+                TAG_COUNTDOWN => {
+                    let bytes = data
+                        .try_into()
+                        .map_err(|_| ProtocolError::MalformedTag(id))?;
+                    decoded.countdown = Some(f32::from_be_bytes(bytes));
+                }
+                TAG_JOYSTICK => decoded.joysticks.push(JoystickData::decode(data)?),
+                // date and timezone aren't needed
+                _ => {}
+            }
+
+            rest = &tail[size..];
+        }
+
+        Ok(decoded)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut request = 0;
+        if self.request_reboot {
+            request |= 0x08;
+        }
+        if self.request_restart_code {
+            request |= 0x04;
+        }
+
+        let mut out = self.sequence.to_be_bytes().to_vec();
+        out.extend([COMM_VERSION, self.control.byte(), request, self.station]);
+
+        if let Some(countdown) = self.countdown {
+            out.extend([5, TAG_COUNTDOWN]);
+            out.extend(countdown.to_be_bytes());
+        }
+
+        for joystick in &self.joysticks {
+            let mut data = vec![TAG_JOYSTICK];
+            joystick.encode(&mut data);
+            out.push(data.len() as u8);
+            out.extend(data);
+        }
+
+        out
+    }
+}
+
+/// Reply sent from the robot to the driver station.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct StatusPacket {
+    /// Sequence number of the control packet being answered
+    pub sequence: u16,
+    pub control: Control,
+    pub brownout: bool,
+    /// Whether user code is running
+    pub robot_code: bool,
+    pub battery_voltage: f64,
+}
+
+impl StatusPacket {
+    pub fn decode(packet: &[u8]) -> Result<Self, ProtocolError> {
+        if packet.len() < 8 {
+            return Err(ProtocolError::TooShort {
+                expected: 8,
+                actual: packet.len(),
+            });
+        }
+
+        if packet[2] != COMM_VERSION {
+            return Err(ProtocolError::Version(packet[2]));
+        }
+
+        Ok(Self {
+            sequence: u16::from_be_bytes([packet[0], packet[1]]),
+            control: Control::from_byte(packet[3] & !0x18),
+            brownout: packet[3] & 0x10 != 0,
+            robot_code: packet[4] & 0x20 != 0,
+            battery_voltage: packet[5] as f64 + packet[6] as f64 / 256.,
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut status = self.control.byte() & !0x08;
+        if self.brownout {
+            status |= 0x10;
+        }
+
+        let mut trace = 0x10; // is a roboRIO
+        if self.robot_code {
+            trace |= 0x20;
+        }
+        trace |= match (self.control.enabled, self.control.mode) {
+            (false, _) => 0x01,
+            (true, Mode::Teleop) => 0x02,
+            (true, Mode::Autonomous) => 0x04,
+            (true, Mode::Test) => 0x08,
+        };
+
+        let voltage = self.battery_voltage.clamp(0., 255.);
+        let integer = voltage.trunc();
+
+        let mut out = self.sequence.to_be_bytes().to_vec();
+        out.extend([
+            COMM_VERSION,
+            status,
+            trace,
+            integer as u8,
+            ((voltage - integer) * 256.) as u8,
+            0,
+        ]);
+        out
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MatchType {
+    #[default]
+    None,
+    Practice,
+    Qualification,
+    Elimination,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct MatchInfo {
+    pub event_name: String,
+    pub match_type: MatchType,
+    pub match_number: u16,
+    pub replay_number: u8,
+}
+
+/// Messages sent from the driver station over TCP.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TcpTag {
+    MatchInfo(MatchInfo),
+    GameData(String),
+    /// A tag that isn't understood, such as joystick descriptors
+    Other(u8),
+}
+
+impl TcpTag {
+    /// Decode the first tag in `buffer`, returning it and the number of bytes consumed, or
+    /// `None` if the buffer doesn't hold a complete tag yet
+    pub fn decode(buffer: &[u8]) -> Result<Option<(Self, usize)>, ProtocolError> {
+        if buffer.len() < 2 {
+            return Ok(None);
+        }
+
+        let size = u16::from_be_bytes([buffer[0], buffer[1]]) as usize;
+        if buffer.len() < size + 2 {
+            return Ok(None);
+        }
+        if size == 0 {
+            return Err(ProtocolError::MalformedTag(0));
+        }
+
+        let id = buffer[2];
+        let data = &buffer[3..size + 2];
+        let malformed = || ProtocolError::MalformedTag(id);
+
+        let tag = match id {
+            TCP_TAG_MATCH_INFO => {
+                let (&name_len, data) = data.split_first().ok_or_else(malformed)?;
+                let name_len = name_len as usize;
+                if data.len() < name_len + 4 {
+                    return Err(malformed());
+                }
+
+                let match_type = match data[name_len] {
+                    1 => MatchType::Practice,
+                    2 => MatchType::Qualification,
+                    3 => MatchType::Elimination,
+                    _ => MatchType::None,
+                };
+
+                TcpTag::MatchInfo(MatchInfo {
+                    event_name: String::from_utf8_lossy(&data[..name_len]).into_owned(),
+                    match_type,
+                    match_number: u16::from_be_bytes([data[name_len + 1], data[name_len + 2]]),
+                    replay_number: data[name_len + 3],
+                })
+            }
+            TCP_TAG_GAME_DATA => TcpTag::GameData(String::from_utf8_lossy(data).into_owned()),
+            other => TcpTag::Other(other),
+        };
+
+        Ok(Some((tag, size + 2)))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let (id, data) = match self {
+            TcpTag::MatchInfo(info) => {
+                let mut data = vec![info.event_name.len() as u8];
+                data.extend(info.event_name.as_bytes());
+                data.push(match info.match_type {
+                    MatchType::None => 0,
+                    MatchType::Practice => 1,
+                    MatchType::Qualification => 2,
+                    MatchType::Elimination => 3,
+                });
+                data.extend(info.match_number.to_be_bytes());
+                data.push(info.replay_number);
+                (TCP_TAG_MATCH_INFO, data)
+            }
+            TcpTag::GameData(message) => (TCP_TAG_GAME_DATA, message.as_bytes().to_vec()),
+            TcpTag::Other(id) => (*id, Vec::new()),
+        };
+
+        let mut out = ((data.len() + 1) as u16).to_be_bytes().to_vec();
+        out.push(id);
+        out.extend(data);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control_packet() -> ControlPacket {
+        ControlPacket {
+            sequence: 513,
+            control: Control {
+                mode: Mode::Autonomous,
+                enabled: true,
+                fms_attached: true,
+                emergency_stop: false,
+            },
+            request_reboot: false,
+            request_restart_code: false,
+            station: 4,
+            countdown: Some(12.5),
+            joysticks: vec![JoystickData {
+                axes: vec![0, 127, -128, 64],
+                buttons: vec![
+                    true, false, false, false, false, false, false, false, false, true,
+                ],
+                povs: vec![-1, 90],
+            }],
+        }
+    }
+
+    #[test]
+    fn control_packet_layout() {
+        let packet = control_packet().encode();
+
+        assert_eq!(&packet[..6], &[0x02, 0x01, 0x01, 0x0e, 0x00, 0x04]);
+        assert_eq!(&packet[6..8], &[5, TAG_COUNTDOWN]);
+        // joystick tag: axes, two bytes of buttons with button 10 in the high byte, povs
+        assert_eq!(
+            &packet[12..],
+            &[
+                14,
+                TAG_JOYSTICK,
+                4,
+                0,
+                127,
+                0x80,
+                64,
+                10,
+                0x02,
+                0x01,
+                2,
+                0xff,
+                0xff,
+                0,
+                90
+            ]
+        );
+    }
+
+    #[test]
+    fn control_packet_round_trip() {
+        let packet = control_packet();
+
+        assert_eq!(ControlPacket::decode(&packet.encode()), Ok(packet));
+    }
+
+    #[test]
+    fn joystick_scaling() {
+        let joystick = &control_packet().joysticks[0];
+
+        assert_eq!(joystick.axis(1), 1.);
+        assert_eq!(joystick.axis(2), -1.);
+        assert_eq!(joystick.axis(7), 0.);
+        assert_eq!(joystick.button_word(), 0b10_0000_0001);
+    }
+
+    #[test]
+    fn rejects_truncated_tag() {
+        let mut packet = control_packet().encode();
+        packet.pop();
+
+        assert_eq!(
+            ControlPacket::decode(&packet),
+            Err(ProtocolError::MalformedTag(TAG_JOYSTICK))
+        );
+
+        // an empty countdown tag, the following joystick tag's size byte isn't its id
+        let mut packet = control_packet().encode();
+        packet[6] = 0;
+
+        assert_eq!(
+            ControlPacket::decode(&packet),
+            Err(ProtocolError::MalformedTag(0))
+        );
+
+        let mut packet = control_packet().encode();
+        packet.truncate(7);
+
+        assert_eq!(
+            ControlPacket::decode(&packet),
+            Err(ProtocolError::TooShort {
+                expected: 12,
+                actual: 7
+            })
+        );
+    }
+
+    #[test]
+    fn status_packet_battery_voltage() {
+        let status = StatusPacket {
+            sequence: 7,
+            control: Control::default(),
+            brownout: false,
+            robot_code: true,
+            battery_voltage: 12.5,
+        };
+
+        let packet = status.encode();
+        assert_eq!(packet[4], 0x31);
+        assert_eq!((packet[5], packet[6]), (12, 128));
+
+        assert_eq!(StatusPacket::decode(&packet), Ok(status));
+    }
+
+    #[test]
+    fn tcp_tags_round_trip() {
+        let info = TcpTag::MatchInfo(MatchInfo {
+            event_name: "MNDU".to_string(),
+            match_type: MatchType::Qualification,
+            match_number: 42,
+            replay_number: 1,
+        });
+        let game_data = TcpTag::GameData("R".to_string());
+
+        let mut buffer = info.encode();
+        buffer.extend(game_data.encode());
+
+        let (first, used) = TcpTag::decode(&buffer).unwrap().unwrap();
+        assert_eq!(first, info);
+
+        let (second, _) = TcpTag::decode(&buffer[used..]).unwrap().unwrap();
+        assert_eq!(second, game_data);
+
+        assert_eq!(TcpTag::decode(&buffer[..used - 1]), Ok(None));
+    }
+}
@@ -0,0 +1,493 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::error::FrcError;
+use crate::input::{RobotMode, RobotState};
+use crate::AllianceStation;
+
+use super::DriverStationSource;
+
+const AXIS_COUNT: usize = 12;
+const POV_COUNT: usize = 4;
+
+#[derive(Clone)]
+struct SimStick {
+    axes: [f64; AXIS_COUNT],
+    buttons: u32,
+    povs: [i32; POV_COUNT],
+    rumble: (f64, f64),
+}
+
+impl Default for SimStick {
+    fn default() -> Self {
+        Self {
+            axes: [0.; AXIS_COUNT],
+            buttons: 0,
+            povs: [-1; POV_COUNT],
+            rumble: (0., 0.),
+        }
+    }
+}
+
+type Action = Box<dyn FnOnce(&SimDriverStation)>;
+
+struct SimState {
+    enabled: bool,
+    mode: RobotMode,
+    emergency_stop: bool,
+    fms_attached: bool,
+    alliance_station: AllianceStation,
+    match_time: f64,
+    sticks: HashMap<i32, SimStick>,
+
+    period: Duration,
+    time: Duration,
+    refreshed: bool,
+    script: Vec<(Duration, Action)>,
+    shutdown: bool,
+}
+
+/// An in-memory driver station for tests.
+///
+/// The simulated driver station keeps its own clock, which advances by a fixed period on every
+/// refresh regardless of wall time, so scripted sequences replay identically on every run.
+/// Clones share the same state, so a test can keep a handle after passing one to
+/// [`super::set_source`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use frcrs::driver_station::{set_source, SimDriverStation};
+/// use frcrs::input::RobotMode;
+///
+/// let ds = SimDriverStation::new();
+/// ds.at(Duration::ZERO, |ds| ds.set_mode(RobotMode::Auto));
+/// ds.at(Duration::from_millis(100), |ds| ds.set_mode(RobotMode::Teleop));
+/// ds.at(Duration::from_millis(200), |ds| ds.shutdown());
+///
+/// set_source(ds.clone());
+/// ```
+#[derive(Clone)]
+pub struct SimDriverStation {
+    state: Rc<RefCell<SimState>>,
+}
+
+impl Default for SimDriverStation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimDriverStation {
+    /// A disabled driver station on red 1, with the clock advancing 4ms per refresh
+    pub fn new() -> Self {
+        Self::with_period(Duration::from_millis(4))
+    }
+
+    /// A disabled driver station with the clock advancing `period` per refresh
+    pub fn with_period(period: Duration) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(SimState {
+                enabled: false,
+                mode: RobotMode::Teleop,
+                emergency_stop: false,
+                fms_attached: false,
+                alliance_station: AllianceStation::RED_1,
+                match_time: -1.,
+                sticks: HashMap::new(),
+
+                period,
+                time: Duration::ZERO,
+                refreshed: false,
+                script: Vec::new(),
+                shutdown: false,
+            })),
+        }
+    }
+
+    /// Run `action` on the first refresh at or after `time`.
+    ///
+    /// Actions run after the refresh has released the driver station source, so they may read
+    /// it through [`crate::alliance_station`], [`RobotState::get`] and the like.
+    pub fn at(&self, time: Duration, action: impl FnOnce(&SimDriverStation) + 'static) {
+        let mut state = self.state.borrow_mut();
+        let index = state.script.partition_point(|(at, _)| *at <= time);
+        state.script.insert(index, (time, Box::new(action)));
+    }
+
+    /// Script a match: autonomous, a short disabled gap, teleop, then shut down
+    pub fn schedule_match(&self, auto: Duration, delay: Duration, teleop: Duration) {
+        self.at(Duration::ZERO, move |ds| {
+            ds.set_match_time(auto.as_secs_f64());
+            ds.set_mode(RobotMode::Auto);
+        });
+        self.at(auto, |ds| {
+            ds.set_match_time(0.);
+            ds.set_mode(RobotMode::Disabled);
+        });
+        self.at(auto + delay, move |ds| {
+            ds.set_match_time(teleop.as_secs_f64());
+            ds.set_mode(RobotMode::Teleop);
+        });
+        self.at(auto + delay + teleop, |ds| {
+            ds.set_match_time(0.);
+            ds.set_mode(RobotMode::Disabled);
+            ds.shutdown();
+        });
+    }
+
+    /// Time of the latest refresh, the first refresh is at zero
+    pub fn time(&self) -> Duration {
+        self.state.borrow().time
+    }
+
+    /// Enable the robot in `mode`, or disable it with [`RobotMode::Disabled`]
+    pub fn set_mode(&self, mode: RobotMode) {
+        let mut state = self.state.borrow_mut();
+        state.enabled = mode != RobotMode::Disabled;
+        if state.enabled {
+            state.mode = mode;
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.borrow_mut().enabled = enabled;
+    }
+
+    pub fn set_emergency_stop(&self, emergency_stop: bool) {
+        self.state.borrow_mut().emergency_stop = emergency_stop;
+    }
+
+    pub fn set_fms_attached(&self, attached: bool) {
+        self.state.borrow_mut().fms_attached = attached;
+    }
+
+    pub fn set_alliance_station(&self, station: AllianceStation) {
+        self.state.borrow_mut().alliance_station = station;
+    }
+
+    /// Set the seconds left in the period, which counts down while enabled
+    pub fn set_match_time(&self, seconds: f64) {
+        self.state.borrow_mut().match_time = seconds;
+    }
+
+    pub fn set_axis(&self, stick: i32, axis: usize, value: f64) {
+        self.stick(stick, |s| s.axes[axis] = value.clamp(-1., 1.));
+    }
+
+    /// Press or release a button, numbered from 1 like the driver station
+    pub fn set_button(&self, stick: i32, button: usize, pressed: bool) {
+        let mask = 1 << (button - 1);
+        self.stick(stick, |s| {
+            if pressed {
+                s.buttons |= mask
+            } else {
+                s.buttons &= !mask
+            }
+        });
+    }
+
+    /// Set a POV hat to `angle` degrees, or -1 to release it
+    pub fn set_pov(&self, stick: i32, pov: usize, angle: i32) {
+        self.stick(stick, |s| s.povs[pov] = angle);
+    }
+
+    /// Last rumble requested for a controller as `(left, right)`
+    pub fn rumble(&self, stick: i32) -> (f64, f64) {
+        self.state
+            .borrow()
+            .sticks
+            .get(&stick)
+            .map(|s| s.rumble)
+            .unwrap_or((0., 0.))
+    }
+
+    /// Ask the robot loop to return
+    pub fn shutdown(&self) {
+        self.state.borrow_mut().shutdown = true;
+    }
+
+    fn stick(&self, stick: i32, f: impl FnOnce(&mut SimStick)) {
+        f(self.state.borrow_mut().sticks.entry(stick).or_default());
+    }
+
+    fn read_stick<T>(&self, stick: i32, f: impl FnOnce(&SimStick) -> T) -> T {
+        match self.state.borrow().sticks.get(&stick) {
+            Some(s) => f(s),
+            None => f(&SimStick::default()),
+        }
+    }
+}
+
+impl DriverStationSource for SimDriverStation {
+    fn init(&mut self) -> Result<(), FrcError> {
+        Ok(())
+    }
+
+    fn observe_user_program_starting(&mut self) {}
+
+    fn refresh(&mut self) {
+        let due = {
+            let mut state = self.state.borrow_mut();
+
+            // the first refresh happens at time zero
+            if state.refreshed {
+                let period = state.period;
+                state.time += period;
+
+                if state.enabled && state.match_time > 0. {
+                    state.match_time = (state.match_time - period.as_secs_f64()).max(0.);
+                }
+            }
+            state.refreshed = true;
+
+            let now = state.time;
+            let count = state.script.partition_point(|(at, _)| *at <= now);
+            state.script.drain(..count).collect::<Vec<_>>()
+        };
+
+        for (_, action) in due {
+            let ds = self.clone();
+            super::defer(move || action(&ds));
+        }
+    }
+
+    fn robot_state(&mut self) -> RobotState {
+        let state = self.state.borrow();
+
+        let mut word = 0;
+        if state.enabled {
+            word |= 1;
+        }
+        match state.mode {
+            RobotMode::Auto => word |= 1 << 1,
+            RobotMode::Test => word |= 1 << 2,
+            RobotMode::Teleop | RobotMode::Disabled => {}
+        }
+        if state.emergency_stop {
+            word |= 1 << 3;
+        }
+        if state.fms_attached {
+            word |= 1 << 4;
+        }
+        // the driver station is always attached
+        word |= 1 << 5;
+
+        RobotState::from_control_word(word)
+    }
+
+    fn alliance_station(&mut self) -> AllianceStation {
+        self.state.borrow().alliance_station
+    }
+
+    fn match_time(&mut self) -> f64 {
+        self.state.borrow().match_time
+    }
+
+    fn stick_axis(&mut self, stick: i32, axis: i32) -> f64 {
+        self.read_stick(stick, |s| {
+            s.axes.get(axis as usize).copied().unwrap_or_default()
+        })
+    }
+
+    fn stick_buttons(&mut self, stick: i32) -> u32 {
+        self.read_stick(stick, |s| s.buttons)
+    }
+
+    fn stick_pov(&mut self, stick: i32, pov: i32) -> i32 {
+        self.read_stick(stick, |s| s.povs.get(pov as usize).copied().unwrap_or(-1))
+    }
+
+    fn set_rumble(&mut self, stick: i32, left: f64, right: f64) {
+        self.stick(stick, |s| s.rumble = (left, right));
+    }
+
+    fn shutdown_requested(&mut self) -> bool {
+        self.state.borrow().shutdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver_station::set_source;
+    use crate::input::Gamepad;
+    use crate::{alliance_station, match_time, refresh_data, Robot};
+    use tokio::task::LocalSet;
+
+    #[derive(Default)]
+    struct Recorder {
+        inits: Vec<RobotMode>,
+        exits: Vec<RobotMode>,
+        /// Every init and exit, in the order they were called
+        hooks: Vec<&'static str>,
+        auto_loops: usize,
+        teleop_loops: usize,
+        robot_loops: usize,
+    }
+
+    impl Robot for Recorder {
+        async fn robot_init(&mut self) {}
+
+        async fn disabled_init(&mut self) {
+            self.inits.push(RobotMode::Disabled);
+            self.hooks.push("disabled_init");
+        }
+
+        async fn autonomous_init(&mut self) {
+            self.inits.push(RobotMode::Auto);
+            self.hooks.push("autonomous_init");
+        }
+
+        async fn teleop_init(&mut self) {
+            self.inits.push(RobotMode::Teleop);
+            self.hooks.push("teleop_init");
+        }
+
+        async fn autonomous_periodic(&mut self) {
+            self.auto_loops += 1;
+        }
+
+        async fn teleop_periodic(&mut self) {
+            self.teleop_loops += 1;
+        }
+
+        async fn robot_periodic(&mut self) {
+            self.robot_loops += 1;
+        }
+
+        fn disabled_exit(&mut self) {
+            self.exits.push(RobotMode::Disabled);
+            self.hooks.push("disabled_exit");
+        }
+
+        fn autonomous_exit(&mut self) {
+            self.exits.push(RobotMode::Auto);
+            self.hooks.push("autonomous_exit");
+        }
+
+        fn teleop_exit(&mut self) {
+            self.exits.push(RobotMode::Teleop);
+            self.hooks.push("teleop_exit");
+        }
+    }
+
+    #[test]
+    fn replay_match() {
+        let ds = SimDriverStation::new();
+        ds.schedule_match(
+            Duration::from_millis(40),
+            Duration::from_millis(20),
+            Duration::from_millis(80),
+        );
+        set_source(ds.clone());
+
+        let mut robot = Recorder::default();
+        robot.start_competition(tokio::runtime::Runtime::new().unwrap(), LocalSet::new());
+
+        assert_eq!(
+            robot.inits,
+            vec![RobotMode::Auto, RobotMode::Disabled, RobotMode::Teleop]
+        );
+        assert_eq!(robot.exits, vec![RobotMode::Auto, RobotMode::Disabled]);
+        assert_eq!(
+            robot.hooks,
+            vec![
+                "autonomous_init",
+                "autonomous_exit",
+                "disabled_init",
+                "disabled_exit",
+                "teleop_init",
+            ]
+        );
+        assert_eq!(robot.auto_loops, 10);
+        assert_eq!(robot.teleop_loops, 20);
+        assert_eq!(robot.robot_loops, 35);
+        assert_eq!(ds.time(), Duration::from_millis(140));
+    }
+
+    #[test]
+    fn first_mode_is_initialized() {
+        let ds = SimDriverStation::new();
+        ds.at(Duration::from_millis(20), |ds| ds.set_mode(RobotMode::Auto));
+        ds.at(Duration::from_millis(40), |ds| ds.shutdown());
+        set_source(ds.clone());
+
+        let mut robot = Recorder::default();
+        robot.start_competition(tokio::runtime::Runtime::new().unwrap(), LocalSet::new());
+
+        assert_eq!(
+            robot.hooks,
+            vec!["disabled_init", "disabled_exit", "autonomous_init"]
+        );
+    }
+
+    #[test]
+    fn actions_can_read_the_driver_station() {
+        let ds = SimDriverStation::new();
+        let seen = Rc::new(RefCell::new(None));
+        let record = seen.clone();
+        ds.at(Duration::ZERO, move |ds| {
+            ds.set_alliance_station(AllianceStation::BLUE_2);
+            *record.borrow_mut() = Some((alliance_station(), RobotState::get().mode()));
+        });
+        set_source(ds.clone());
+
+        refresh_data();
+
+        assert_eq!(
+            *seen.borrow(),
+            Some((AllianceStation::BLUE_2, RobotMode::Disabled))
+        );
+    }
+
+    #[test]
+    fn match_time_counts_down_while_enabled() {
+        let ds = SimDriverStation::with_period(Duration::from_millis(20));
+        set_source(ds.clone());
+
+        ds.set_match_time(15.);
+        refresh_data();
+        refresh_data();
+        assert_eq!(match_time(), 15.);
+
+        ds.set_mode(RobotMode::Auto);
+        for _ in 0..50 {
+            refresh_data();
+        }
+        assert!((match_time() - 14.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn alliance_station_injection() {
+        let ds = SimDriverStation::new();
+        set_source(ds.clone());
+
+        ds.set_alliance_station(AllianceStation::BLUE_2);
+
+        assert!(alliance_station().blue());
+    }
+
+    #[test]
+    fn gamepad_injection() {
+        let ds = SimDriverStation::new();
+        set_source(ds.clone());
+
+        let mut gamepad = Gamepad::new(0);
+
+        ds.set_axis(0, 1, -0.5);
+        ds.set_button(0, 1, true);
+        ds.set_pov(0, 0, 90);
+
+        assert_eq!(gamepad.left_y(), -0.5);
+        assert!(gamepad.a());
+        assert!(!gamepad.b());
+        assert_eq!(gamepad.get_dpad(), 90);
+
+        gamepad.rumble_left(0.25);
+        assert_eq!(ds.rumble(0), (0.25, 0.));
+    }
+}
@@ -0,0 +1,175 @@
+use jni::objects::{JObject, JString, JThrowable, JValue};
+use jni::JNIEnv;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FrcError {
+    #[error("failed to start the JVM: {0}")]
+    JvmStartup(String),
+
+    #[error("JNI call failed: {0}")]
+    Jni(#[from] jni::errors::Error),
+
+    #[error("failed to initialize the HAL")]
+    HalInit,
+
+    #[error("class {0} not found")]
+    ClassNotFound(&'static str),
+
+    #[error("method {class}.{method}{signature} not found")]
+    MethodNotFound {
+        class: &'static str,
+        method: &'static str,
+        signature: &'static str,
+    },
+
+    /// A Java method threw, `stack_trace` is what `printStackTrace` would print
+    #[error("Java exception: {message}")]
+    JavaException {
+        message: String,
+        stack_trace: String,
+    },
+
+    #[error("{device} {id} not found: {source}")]
+    DeviceNotFound {
+        device: &'static str,
+        id: i32,
+        #[source]
+        source: Box<FrcError>,
+    },
+}
+
+impl FrcError {
+    /// Attach the device being constructed to an error
+    pub(crate) fn device(device: &'static str, id: i32) -> impl FnOnce(FrcError) -> FrcError {
+        move |source| FrcError::DeviceNotFound {
+            device,
+            id,
+            source: Box::new(source),
+        }
+    }
+}
+
+/// Convert the result of a JNI call, clearing any Java exception it threw so the JVM stays usable
+pub(crate) fn check<T>(java: &mut JNIEnv, result: jni::errors::Result<T>) -> Result<T, FrcError> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(jni::errors::Error::JavaException) => Err(exception(java)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Like [`check`], but reports a failed `find_class` as [`FrcError::ClassNotFound`]
+pub(crate) fn check_class<T>(
+    java: &mut JNIEnv,
+    class: &'static str,
+    result: jni::errors::Result<T>,
+) -> Result<T, FrcError> {
+    check(java, result).map_err(|e| match e {
+        FrcError::JavaException { .. } => FrcError::ClassNotFound(class),
+        e => e,
+    })
+}
+
+/// Like [`check`], but reports a failed method lookup as [`FrcError::MethodNotFound`]
+pub(crate) fn check_method<T>(
+    java: &mut JNIEnv,
+    class: &'static str,
+    method: &'static str,
+    signature: &'static str,
+    result: jni::errors::Result<T>,
+) -> Result<T, FrcError> {
+    check(java, result).map_err(|e| match e {
+        FrcError::JavaException { message, .. } if message.contains("NoClassDefFoundError") => {
+            FrcError::ClassNotFound(class)
+        }
+        FrcError::JavaException { .. }
+        | FrcError::Jni(jni::errors::Error::MethodNotFound { .. }) => FrcError::MethodNotFound {
+            class,
+            method,
+            signature,
+        },
+        e => e,
+    })
+}
+
+/// Take the pending Java exception, describing it as well as the JVM allows
+fn exception(java: &mut JNIEnv) -> FrcError {
+    let throwable = java.exception_occurred();
+    let _ = java.exception_clear();
+
+    let (message, stack_trace) = match throwable {
+        Ok(throwable) if !throwable.is_null() => describe(java, &throwable).unwrap_or_else(|_| {
+            let _ = java.exception_clear();
+            ("unknown exception".to_string(), String::new())
+        }),
+        _ => ("unknown exception".to_string(), String::new()),
+    };
+
+    FrcError::JavaException {
+        message,
+        stack_trace,
+    }
+}
+
+fn describe(java: &mut JNIEnv, throwable: &JThrowable) -> jni::errors::Result<(String, String)> {
+    let message = java
+        .call_method(throwable, "toString", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    let writer = java.new_object("java/io/StringWriter", "()V", &[])?;
+    let printer = java.new_object(
+        "java/io/PrintWriter",
+        "(Ljava/io/Writer;)V",
+        &[JValue::Object(&writer)],
+    )?;
+    java.call_method(
+        throwable,
+        "printStackTrace",
+        "(Ljava/io/PrintWriter;)V",
+        &[JValue::Object(&printer)],
+    )?;
+    let stack_trace = java
+        .call_method(&writer, "toString", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    Ok((string(java, message)?, string(java, stack_trace)?))
+}
+
+fn string(java: &mut JNIEnv, object: JObject) -> jni::errors::Result<String> {
+    Ok(java.get_string(&JString::from(object))?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn device_not_found_keeps_source() {
+        let error = FrcError::device("TalonFX", 3)(FrcError::HalInit);
+
+        assert_eq!(
+            error.to_string(),
+            "TalonFX 3 not found: failed to initialize the HAL"
+        );
+        assert_eq!(
+            error.source().map(|e| e.to_string()).as_deref(),
+            Some("failed to initialize the HAL")
+        );
+    }
+
+    #[test]
+    fn lookup_errors_name_the_method() {
+        let error = FrcError::MethodNotFound {
+            class: "com/revrobotics/CANSparkBase",
+            method: "set",
+            signature: "(D)V",
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "method com/revrobotics/CANSparkBase.set(D)V not found"
+        );
+    }
+}
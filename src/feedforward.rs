@@ -0,0 +1,447 @@
+use std::marker::PhantomData;
+
+use uom::si::acceleration::meter_per_second_squared;
+use uom::si::angle::radian;
+use uom::si::angular_acceleration::radian_per_second_squared;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::electric_potential::volt;
+use uom::si::f64::{
+    Acceleration, Angle, AngularAcceleration, AngularVelocity, ElectricPotential, Velocity,
+};
+use uom::si::velocity::meter_per_second;
+
+/// A linear or angular velocity a [`SimpleMotorFeedforward`] can work in.
+///
+/// Gains are per meter or per radian.
+pub trait Rate: Copy {
+    type Acceleration: Copy;
+
+    fn to_si(self) -> f64;
+    fn from_si(value: f64) -> Self;
+    fn acceleration_to_si(acceleration: Self::Acceleration) -> f64;
+    fn acceleration_from_si(value: f64) -> Self::Acceleration;
+}
+
+impl Rate for Velocity {
+    type Acceleration = Acceleration;
+
+    fn to_si(self) -> f64 {
+        self.get::<meter_per_second>()
+    }
+
+    fn from_si(value: f64) -> Self {
+        Velocity::new::<meter_per_second>(value)
+    }
+
+    fn acceleration_to_si(acceleration: Acceleration) -> f64 {
+        acceleration.get::<meter_per_second_squared>()
+    }
+
+    fn acceleration_from_si(value: f64) -> Acceleration {
+        Acceleration::new::<meter_per_second_squared>(value)
+    }
+}
+
+impl Rate for AngularVelocity {
+    type Acceleration = AngularAcceleration;
+
+    fn to_si(self) -> f64 {
+        self.get::<radian_per_second>()
+    }
+
+    fn from_si(value: f64) -> Self {
+        AngularVelocity::new::<radian_per_second>(value)
+    }
+
+    fn acceleration_to_si(acceleration: AngularAcceleration) -> f64 {
+        acceleration.get::<radian_per_second_squared>()
+    }
+
+    fn acceleration_from_si(value: f64) -> AngularAcceleration {
+        AngularAcceleration::new::<radian_per_second_squared>(value)
+    }
+}
+
+/// Like `f64::signum`, but zero at rest so static friction isn't fed forward while stopped
+fn direction(velocity: f64) -> f64 {
+    if velocity == 0. {
+        0.
+    } else {
+        velocity.signum()
+    }
+}
+
+/// Voltage to overcome friction, back EMF and inertia of a mechanism with no gravity, like a
+/// flywheel or drivetrain.
+///
+/// Gains are in volts, as SysId reports them: `ks` in volts, `kv` in volts per meter or radian
+/// per second, and `ka` in volts per meter or radian per second squared.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::feedforward::SimpleMotorFeedforward;
+/// use frcrs::rev::{ControlType, MotorType, Spark};
+/// use uom::si::angular_acceleration::radian_per_second_squared;
+/// use uom::si::angular_velocity::revolution_per_minute;
+/// use uom::si::electric_potential::volt;
+/// use uom::si::f64::{AngularAcceleration, AngularVelocity};
+///
+/// let shooter = SimpleMotorFeedforward::<AngularVelocity>::new(0.1, 0.02, 0.001);
+/// let mut spark = Spark::new(4, MotorType::Brushless);
+///
+/// let speed = AngularVelocity::new::<revolution_per_minute>(3000.);
+/// let voltage = shooter.calculate(speed, AngularAcceleration::new::<radian_per_second_squared>(0.));
+/// spark.set_reference_ff(3000., ControlType::Velocity, voltage.get::<volt>());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimpleMotorFeedforward<V = Velocity> {
+    pub ks: f64,
+    pub kv: f64,
+    pub ka: f64,
+    rate: PhantomData<V>,
+}
+
+impl<V: Rate> SimpleMotorFeedforward<V> {
+    pub fn new(ks: f64, kv: f64, ka: f64) -> Self {
+        Self {
+            ks,
+            kv,
+            ka,
+            rate: PhantomData,
+        }
+    }
+
+    pub fn calculate(&self, velocity: V, acceleration: V::Acceleration) -> ElectricPotential {
+        let velocity = velocity.to_si();
+        let acceleration = V::acceleration_to_si(acceleration);
+
+        volts(self.ks * direction(velocity) + self.kv * velocity + self.ka * acceleration)
+    }
+
+    /// Fastest speed forwards while accelerating at `acceleration` with `max_voltage`
+    pub fn max_velocity(&self, max_voltage: ElectricPotential, acceleration: V::Acceleration) -> V {
+        let acceleration = V::acceleration_to_si(acceleration);
+
+        V::from_si((max_voltage.get::<volt>() - self.ks - self.ka * acceleration) / self.kv)
+    }
+
+    /// Fastest speed backwards while accelerating at `acceleration` with `max_voltage`
+    pub fn min_velocity(&self, max_voltage: ElectricPotential, acceleration: V::Acceleration) -> V {
+        let acceleration = V::acceleration_to_si(acceleration);
+
+        V::from_si((-max_voltage.get::<volt>() + self.ks - self.ka * acceleration) / self.kv)
+    }
+
+    /// Hardest forwards acceleration at `velocity` with `max_voltage`
+    pub fn max_acceleration(&self, max_voltage: ElectricPotential, velocity: V) -> V::Acceleration {
+        V::acceleration_from_si(self.acceleration(max_voltage.get::<volt>(), velocity.to_si()))
+    }
+
+    /// Hardest backwards acceleration at `velocity` with `max_voltage`
+    pub fn min_acceleration(&self, max_voltage: ElectricPotential, velocity: V) -> V::Acceleration {
+        V::acceleration_from_si(self.acceleration(-max_voltage.get::<volt>(), velocity.to_si()))
+    }
+
+    fn acceleration(&self, voltage: f64, velocity: f64) -> f64 {
+        (voltage - self.ks * direction(velocity) - self.kv * velocity) / self.ka
+    }
+}
+
+/// [`SimpleMotorFeedforward`] plus a constant `kg` in volts to hold an elevator up against
+/// gravity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ElevatorFeedforward {
+    pub ks: f64,
+    pub kg: f64,
+    pub kv: f64,
+    pub ka: f64,
+}
+
+impl ElevatorFeedforward {
+    pub fn new(ks: f64, kg: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kg, kv, ka }
+    }
+
+    pub fn calculate(&self, velocity: Velocity, acceleration: Acceleration) -> ElectricPotential {
+        let velocity = velocity.get::<meter_per_second>();
+        let acceleration = acceleration.get::<meter_per_second_squared>();
+
+        volts(self.ks * direction(velocity) + self.kg + self.kv * velocity + self.ka * acceleration)
+    }
+
+    /// Fastest speed upwards while accelerating at `acceleration` with `max_voltage`
+    pub fn max_velocity(
+        &self,
+        max_voltage: ElectricPotential,
+        acceleration: Acceleration,
+    ) -> Velocity {
+        let acceleration = acceleration.get::<meter_per_second_squared>();
+
+        Velocity::new::<meter_per_second>(
+            (max_voltage.get::<volt>() - self.ks - self.kg - self.ka * acceleration) / self.kv,
+        )
+    }
+
+    /// Fastest speed downwards while accelerating at `acceleration` with `max_voltage`
+    pub fn min_velocity(
+        &self,
+        max_voltage: ElectricPotential,
+        acceleration: Acceleration,
+    ) -> Velocity {
+        let acceleration = acceleration.get::<meter_per_second_squared>();
+
+        Velocity::new::<meter_per_second>(
+            (-max_voltage.get::<volt>() + self.ks - self.kg - self.ka * acceleration) / self.kv,
+        )
+    }
+
+    /// Hardest upwards acceleration at `velocity` with `max_voltage`
+    pub fn max_acceleration(
+        &self,
+        max_voltage: ElectricPotential,
+        velocity: Velocity,
+    ) -> Acceleration {
+        self.acceleration(max_voltage.get::<volt>(), velocity)
+    }
+
+    /// Hardest downwards acceleration at `velocity` with `max_voltage`
+    pub fn min_acceleration(
+        &self,
+        max_voltage: ElectricPotential,
+        velocity: Velocity,
+    ) -> Acceleration {
+        self.acceleration(-max_voltage.get::<volt>(), velocity)
+    }
+
+    fn acceleration(&self, voltage: f64, velocity: Velocity) -> Acceleration {
+        let velocity = velocity.get::<meter_per_second>();
+
+        Acceleration::new::<meter_per_second_squared>(
+            (voltage - self.ks * direction(velocity) - self.kg - self.kv * velocity) / self.ka,
+        )
+    }
+}
+
+/// [`SimpleMotorFeedforward`] for an arm, where gravity pulls hardest when the arm is level.
+///
+/// `kg` is the voltage to hold the arm horizontal, and angles are measured up from horizontal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArmFeedforward {
+    pub ks: f64,
+    pub kg: f64,
+    pub kv: f64,
+    pub ka: f64,
+}
+
+impl ArmFeedforward {
+    pub fn new(ks: f64, kg: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kg, kv, ka }
+    }
+
+    pub fn calculate(
+        &self,
+        angle: Angle,
+        velocity: AngularVelocity,
+        acceleration: AngularAcceleration,
+    ) -> ElectricPotential {
+        let velocity = velocity.get::<radian_per_second>();
+        let acceleration = acceleration.get::<radian_per_second_squared>();
+
+        volts(
+            self.ks * direction(velocity)
+                + self.kg * angle.get::<radian>().cos()
+                + self.kv * velocity
+                + self.ka * acceleration,
+        )
+    }
+
+    /// Fastest speed upwards at `angle` while accelerating at `acceleration` with
+    /// `max_voltage`
+    pub fn max_velocity(
+        &self,
+        max_voltage: ElectricPotential,
+        angle: Angle,
+        acceleration: AngularAcceleration,
+    ) -> AngularVelocity {
+        let gravity = self.kg * angle.get::<radian>().cos();
+        let acceleration = acceleration.get::<radian_per_second_squared>();
+
+        AngularVelocity::new::<radian_per_second>(
+            (max_voltage.get::<volt>() - self.ks - gravity - self.ka * acceleration) / self.kv,
+        )
+    }
+
+    /// Fastest speed downwards at `angle` while accelerating at `acceleration` with
+    /// `max_voltage`
+    pub fn min_velocity(
+        &self,
+        max_voltage: ElectricPotential,
+        angle: Angle,
+        acceleration: AngularAcceleration,
+    ) -> AngularVelocity {
+        let gravity = self.kg * angle.get::<radian>().cos();
+        let acceleration = acceleration.get::<radian_per_second_squared>();
+
+        AngularVelocity::new::<radian_per_second>(
+            (-max_voltage.get::<volt>() + self.ks - gravity - self.ka * acceleration) / self.kv,
+        )
+    }
+
+    /// Hardest upwards acceleration at `angle` and `velocity` with `max_voltage`
+    pub fn max_acceleration(
+        &self,
+        max_voltage: ElectricPotential,
+        angle: Angle,
+        velocity: AngularVelocity,
+    ) -> AngularAcceleration {
+        self.acceleration(max_voltage.get::<volt>(), angle, velocity)
+    }
+
+    /// Hardest downwards acceleration at `angle` and `velocity` with `max_voltage`
+    pub fn min_acceleration(
+        &self,
+        max_voltage: ElectricPotential,
+        angle: Angle,
+        velocity: AngularVelocity,
+    ) -> AngularAcceleration {
+        self.acceleration(-max_voltage.get::<volt>(), angle, velocity)
+    }
+
+    fn acceleration(
+        &self,
+        voltage: f64,
+        angle: Angle,
+        velocity: AngularVelocity,
+    ) -> AngularAcceleration {
+        let velocity = velocity.get::<radian_per_second>();
+        let gravity = self.kg * angle.get::<radian>().cos();
+
+        AngularAcceleration::new::<radian_per_second_squared>(
+            (voltage - self.ks * direction(velocity) - gravity - self.kv * velocity) / self.ka,
+        )
+    }
+}
+
+fn volts(value: f64) -> ElectricPotential {
+    ElectricPotential::new::<volt>(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::angle::degree;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn simple_motor() {
+        let feedforward = SimpleMotorFeedforward::<Velocity>::new(0.5, 2., 0.25);
+        let voltage = feedforward.calculate(
+            Velocity::new::<meter_per_second>(-1.),
+            Acceleration::new::<meter_per_second_squared>(2.),
+        );
+        assert!(close(voltage.get::<volt>(), -0.5 - 2. + 0.5));
+
+        let stopped = feedforward.calculate(
+            Velocity::new::<meter_per_second>(0.),
+            Acceleration::new::<meter_per_second_squared>(0.),
+        );
+        assert_eq!(stopped.get::<volt>(), 0.);
+
+        // at full speed there's nothing left to accelerate with
+        let max_voltage = volts(12.);
+        let top = feedforward.max_velocity(
+            max_voltage,
+            Acceleration::new::<meter_per_second_squared>(0.),
+        );
+        assert!(close(top.get::<meter_per_second>(), 5.75));
+        assert!(close(
+            feedforward
+                .max_acceleration(max_voltage, top)
+                .get::<meter_per_second_squared>(),
+            0.
+        ));
+        assert!(close(
+            feedforward
+                .min_velocity(
+                    max_voltage,
+                    Acceleration::new::<meter_per_second_squared>(0.)
+                )
+                .get::<meter_per_second>(),
+            -5.75
+        ));
+    }
+
+    #[test]
+    fn angular_simple_motor() {
+        let feedforward = SimpleMotorFeedforward::<AngularVelocity>::new(0., 0.01, 0.);
+
+        let voltage = feedforward.calculate(
+            AngularVelocity::new::<radian_per_second>(600.),
+            AngularAcceleration::new::<radian_per_second_squared>(0.),
+        );
+
+        assert!(close(voltage.get::<volt>(), 6.));
+    }
+
+    #[test]
+    fn elevator_holds_against_gravity() {
+        let feedforward = ElevatorFeedforward::new(0.2, 0.6, 3., 0.1);
+        let hold = feedforward.calculate(
+            Velocity::new::<meter_per_second>(0.),
+            Acceleration::new::<meter_per_second_squared>(0.),
+        );
+        assert!(close(hold.get::<volt>(), 0.6));
+
+        let max_voltage = volts(12.);
+        let up = feedforward.max_velocity(
+            max_voltage,
+            Acceleration::new::<meter_per_second_squared>(0.),
+        );
+        let down = feedforward.min_velocity(
+            max_voltage,
+            Acceleration::new::<meter_per_second_squared>(0.),
+        );
+        // gravity helps on the way down
+        assert!(close(up.get::<meter_per_second>(), 11.2 / 3.));
+        assert!(close(down.get::<meter_per_second>(), -12.4 / 3.));
+        assert!(close(
+            feedforward
+                .min_acceleration(max_voltage, down)
+                .get::<meter_per_second_squared>(),
+            0.
+        ));
+    }
+
+    #[test]
+    fn arm_gravity_follows_angle() {
+        let feedforward = ArmFeedforward::new(0., 1., 1., 1.);
+        let still = AngularVelocity::new::<radian_per_second>(0.);
+        let no_acceleration = AngularAcceleration::new::<radian_per_second_squared>(0.);
+
+        let level = feedforward.calculate(Angle::new::<degree>(0.), still, no_acceleration);
+        let raised = feedforward.calculate(Angle::new::<degree>(60.), still, no_acceleration);
+        let vertical = feedforward.calculate(Angle::new::<degree>(90.), still, no_acceleration);
+        assert!(close(level.get::<volt>(), 1.));
+        assert!(close(raised.get::<volt>(), 0.5));
+        assert!(vertical.get::<volt>().abs() < 1e-9);
+
+        let max_voltage = volts(12.);
+        let angle = Angle::new::<degree>(60.);
+        assert!(close(
+            feedforward
+                .max_velocity(max_voltage, angle, no_acceleration)
+                .get::<radian_per_second>(),
+            11.5
+        ));
+        assert!(close(
+            feedforward
+                .max_acceleration(max_voltage, angle, still)
+                .get::<radian_per_second_squared>(),
+            11.5
+        ));
+    }
+}
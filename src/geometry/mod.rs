@@ -0,0 +1,16 @@
+//! Positions, rotations and movements on the field, following WPILib's conventions.
+//!
+//! Everything is measured in `uom` units and serializes with serde, lengths in meters and
+//! angles in radians, so poses can be published through [`crate::telemetry::Telemetry`].
+
+mod pose;
+mod rotation;
+mod transform;
+mod translation;
+mod twist;
+
+pub use pose::*;
+pub use rotation::*;
+pub use transform::*;
+pub use translation::*;
+pub use twist::*;
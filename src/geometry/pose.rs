@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+use uom::si::angle::radian;
+use uom::si::f64::{Angle, Length};
+use uom::si::length::meter;
+
+use crate::geometry::{
+    Rotation2d, Rotation3d, Transform2d, Transform3d, Translation2d, Translation3d, Twist2d,
+};
+
+/// A position and heading on the field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pose2d {
+    pub translation: Translation2d,
+    pub rotation: Rotation2d,
+}
+
+impl Pose2d {
+    pub fn new(translation: Translation2d, rotation: Rotation2d) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    pub fn x(&self) -> Length {
+        self.translation.x
+    }
+
+    pub fn y(&self) -> Length {
+        self.translation.y
+    }
+
+    /// This pose as seen from `origin`, so `origin + (self.relative_to(origin))` is `self`
+    pub fn relative_to(&self, origin: Pose2d) -> Pose2d {
+        let transform = Transform2d::between(origin, *self);
+        Pose2d::new(transform.translation, transform.rotation)
+    }
+
+    /// The pose reached by following `twist` from this pose along a constant curvature arc
+    pub fn exp(&self, twist: Twist2d) -> Self {
+        let dx = twist.dx.get::<meter>();
+        let dy = twist.dy.get::<meter>();
+        let dtheta = twist.dtheta.get::<radian>();
+
+        // sin(θ)/θ and (1 - cos(θ))/θ, using their series near zero
+        let (s, c) = if dtheta.abs() < 1e-9 {
+            (1. - dtheta * dtheta / 6., dtheta / 2.)
+        } else {
+            (dtheta.sin() / dtheta, (1. - dtheta.cos()) / dtheta)
+        };
+
+        let step = Translation2d::from_meters(dx * s - dy * c, dx * c + dy * s);
+
+        Self::new(
+            self.translation + step.rotate_by(self.rotation),
+            self.rotation + Rotation2d::from_radians(dtheta),
+        )
+    }
+
+    /// The twist that [`Pose2d::exp`] would need to get from this pose to `end`
+    pub fn log(&self, end: Pose2d) -> Twist2d {
+        let translation = (end.translation - self.translation).rotate_by(-self.rotation);
+        let dtheta = (end.rotation - self.rotation).radians();
+        let half_dtheta = dtheta / 2.;
+
+        // (θ/2) / tan(θ/2), using its series near zero
+        let cos_minus_one = dtheta.cos() - 1.;
+        let half_dtheta_by_tan = if cos_minus_one.abs() < 1e-9 {
+            1. - dtheta * dtheta / 12.
+        } else {
+            -(half_dtheta * dtheta.sin()) / cos_minus_one
+        };
+
+        let translation = translation.rotate_by(Rotation2d::from_components(
+            half_dtheta_by_tan,
+            -half_dtheta,
+        )) * half_dtheta_by_tan.hypot(half_dtheta);
+
+        Twist2d::new(translation.x, translation.y, Angle::new::<radian>(dtheta))
+    }
+
+    /// The pose `t` of the way along the arc from this pose to `end`
+    pub fn interpolate(&self, end: Pose2d, t: f64) -> Self {
+        let twist = self.log(end);
+        self.exp(Twist2d::new(twist.dx * t, twist.dy * t, twist.dtheta * t))
+    }
+}
+
+impl Add<Transform2d> for Pose2d {
+    type Output = Pose2d;
+
+    /// Move by `transform`, relative to this pose
+    fn add(self, transform: Transform2d) -> Pose2d {
+        Pose2d::new(
+            self.translation + transform.translation.rotate_by(self.rotation),
+            self.rotation + transform.rotation,
+        )
+    }
+}
+
+impl Sub for Pose2d {
+    type Output = Transform2d;
+
+    /// The transform from `other` to this pose
+    fn sub(self, other: Pose2d) -> Transform2d {
+        Transform2d::between(other, self)
+    }
+}
+
+/// A position and orientation in space, such as the pose of a camera or an AprilTag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pose3d {
+    pub translation: Translation3d,
+    pub rotation: Rotation3d,
+}
+
+impl Pose3d {
+    pub fn new(translation: Translation3d, rotation: Rotation3d) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// This pose as seen from `origin`
+    pub fn relative_to(&self, origin: Pose3d) -> Pose3d {
+        let transform = Transform3d::between(origin, *self);
+        Pose3d::new(transform.translation, transform.rotation)
+    }
+
+    /// The pose on the floor, keeping only the yaw
+    pub fn to_pose2d(&self) -> Pose2d {
+        Pose2d::new(
+            self.translation.to_translation2d(),
+            self.rotation.to_rotation2d(),
+        )
+    }
+}
+
+impl From<Pose2d> for Pose3d {
+    fn from(pose: Pose2d) -> Self {
+        Self::new(pose.translation.into(), pose.rotation.into())
+    }
+}
+
+impl Add<Transform3d> for Pose3d {
+    type Output = Pose3d;
+
+    /// Move by `transform`, relative to this pose
+    fn add(self, transform: Transform3d) -> Pose3d {
+        Pose3d::new(
+            self.translation + transform.translation.rotate_by(self.rotation),
+            transform.rotation + self.rotation,
+        )
+    }
+}
+
+impl Sub for Pose3d {
+    type Output = Transform3d;
+
+    /// The transform from `other` to this pose
+    fn sub(self, other: Pose3d) -> Transform3d {
+        Transform3d::between(other, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn exp_follows_arc() {
+        // a quarter circle of radius 1, driving forward and turning left
+        let twist = Twist2d::new(
+            Length::new::<meter>(PI / 2.),
+            Length::default(),
+            Angle::new::<radian>(PI / 2.),
+        );
+
+        let pose = Pose2d::default().exp(twist);
+
+        assert!((pose.x().get::<meter>() - 1.).abs() < 1e-9);
+        assert!((pose.y().get::<meter>() - 1.).abs() < 1e-9);
+        assert!((pose.rotation.degrees() - 90.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn log_inverts_exp() {
+        let start = Pose2d::new(
+            Translation2d::from_meters(1., -2.),
+            Rotation2d::from_degrees(30.),
+        );
+        let end = Pose2d::new(
+            Translation2d::from_meters(3., 1.),
+            Rotation2d::from_degrees(-100.),
+        );
+
+        let round_trip = start.exp(start.log(end));
+
+        assert!((round_trip.x() - end.x()).get::<meter>().abs() < 1e-9);
+        assert!((round_trip.y() - end.y()).get::<meter>().abs() < 1e-9);
+        assert!(((round_trip.rotation - end.rotation).radians()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transforms_compose() {
+        let start = Pose2d::new(
+            Translation2d::from_meters(2., 1.),
+            Rotation2d::from_degrees(90.),
+        );
+        let end = Pose2d::new(
+            Translation2d::from_meters(1., 3.),
+            Rotation2d::from_degrees(180.),
+        );
+
+        let transform = end - start;
+        // 2m ahead and 1m left of a robot facing +y
+        assert!((transform.translation.x.get::<meter>() - 2.).abs() < 1e-9);
+        assert!((transform.translation.y.get::<meter>() - 1.).abs() < 1e-9);
+
+        let moved = start + transform;
+        assert!((moved.x() - end.x()).get::<meter>().abs() < 1e-9);
+        assert!((moved.y() - end.y()).get::<meter>().abs() < 1e-9);
+        let undone = start + transform + transform.inverse();
+        assert!((undone.x() - start.x()).get::<meter>().abs() < 1e-9);
+    }
+
+    #[test]
+    fn camera_mount() {
+        // a camera 0.3m forward and 0.5m up, pitched 20° up
+        let robot_to_camera = Transform3d::new(
+            Translation3d::from_meters(0.3, 0., 0.5),
+            Rotation3d::new(
+                Angle::default(),
+                Angle::new::<uom::si::angle::degree>(-20.),
+                Angle::default(),
+            ),
+        );
+        let robot = Pose3d::from(Pose2d::new(
+            Translation2d::from_meters(4., 2.),
+            Rotation2d::from_degrees(90.),
+        ));
+
+        let camera = robot + robot_to_camera;
+        assert!((camera.translation.x.get::<meter>() - 4.).abs() < 1e-9);
+        assert!((camera.translation.y.get::<meter>() - 2.3).abs() < 1e-9);
+        assert!((camera.translation.z.get::<meter>() - 0.5).abs() < 1e-9);
+        assert!((camera.to_pose2d().rotation.degrees() - 90.).abs() < 1e-9);
+
+        let back = camera + robot_to_camera.inverse();
+        assert!((back.translation - robot.translation).norm().get::<meter>() < 1e-9);
+    }
+
+    #[test]
+    fn pose_json() {
+        let pose = Pose2d::new(Translation2d::from_meters(1., 2.), Rotation2d::default());
+
+        assert_eq!(
+            serde_json::to_string(&pose).unwrap(),
+            r#"{"translation":{"x":1.0,"y":2.0},"rotation":{"radians":0.0}}"#
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use nalgebra::{Quaternion, UnitQuaternion};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Neg, Sub};
+use uom::si::angle::{degree, radian};
+use uom::si::f64::Angle;
+
+/// A rotation in the plane, stored as a unit vector so composing rotations never drifts.
+///
+/// Counter-clockwise is positive, matching WPILib. Serializes as `{"radians": ...}`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Radians", into = "Radians")]
+pub struct Rotation2d {
+    cos: f64,
+    sin: f64,
+}
+
+impl Default for Rotation2d {
+    fn default() -> Self {
+        Self { cos: 1., sin: 0. }
+    }
+}
+
+impl Rotation2d {
+    pub fn new(angle: Angle) -> Self {
+        Self::from_radians(angle.get::<radian>())
+    }
+
+    pub fn from_radians(radians: f64) -> Self {
+        Self {
+            cos: radians.cos(),
+            sin: radians.sin(),
+        }
+    }
+
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self::from_radians(degrees.to_radians())
+    }
+
+    /// The rotation pointing along `(x, y)`, or no rotation if both are zero
+    pub fn from_components(x: f64, y: f64) -> Self {
+        let magnitude = x.hypot(y);
+        if magnitude > 1e-6 {
+            Self {
+                cos: x / magnitude,
+                sin: y / magnitude,
+            }
+        } else {
+            Self::default()
+        }
+    }
+
+    /// The angle in (-180°, 180°]
+    pub fn angle(&self) -> Angle {
+        Angle::new::<radian>(self.radians())
+    }
+
+    pub fn radians(&self) -> f64 {
+        self.sin.atan2(self.cos)
+    }
+
+    pub fn degrees(&self) -> f64 {
+        self.angle().get::<degree>()
+    }
+
+    pub fn cos(&self) -> f64 {
+        self.cos
+    }
+
+    pub fn sin(&self) -> f64 {
+        self.sin
+    }
+
+    pub fn tan(&self) -> f64 {
+        self.sin / self.cos
+    }
+
+    /// Apply `other` after this rotation
+    pub fn rotate_by(&self, other: Rotation2d) -> Self {
+        Self::from_components(
+            self.cos * other.cos - self.sin * other.sin,
+            self.cos * other.sin + self.sin * other.cos,
+        )
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            cos: self.cos,
+            sin: -self.sin,
+        }
+    }
+}
+
+impl From<Angle> for Rotation2d {
+    fn from(angle: Angle) -> Self {
+        Self::new(angle)
+    }
+}
+
+impl Add for Rotation2d {
+    type Output = Rotation2d;
+
+    fn add(self, other: Rotation2d) -> Rotation2d {
+        self.rotate_by(other)
+    }
+}
+
+impl Sub for Rotation2d {
+    type Output = Rotation2d;
+
+    fn sub(self, other: Rotation2d) -> Rotation2d {
+        self.rotate_by(other.inverse())
+    }
+}
+
+impl Neg for Rotation2d {
+    type Output = Rotation2d;
+
+    fn neg(self) -> Rotation2d {
+        self.inverse()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Radians {
+    radians: f64,
+}
+
+impl From<Radians> for Rotation2d {
+    fn from(angle: Radians) -> Self {
+        Self::from_radians(angle.radians)
+    }
+}
+
+impl From<Rotation2d> for Radians {
+    fn from(rotation: Rotation2d) -> Self {
+        Self {
+            radians: rotation.radians(),
+        }
+    }
+}
+
+/// A rotation in space, stored as a quaternion.
+///
+/// Angles follow WPILib: roll around +x (forward), pitch around +y (left) and yaw around +z
+/// (up), each counter-clockwise positive when looking down the axis. Serializes as
+/// `{"w": ..., "x": ..., "y": ..., "z": ...}`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "QuaternionRepr", into = "QuaternionRepr")]
+pub struct Rotation3d {
+    quaternion: UnitQuaternion<f64>,
+}
+
+impl Default for Rotation3d {
+    fn default() -> Self {
+        Self {
+            quaternion: UnitQuaternion::identity(),
+        }
+    }
+}
+
+impl Rotation3d {
+    /// Roll, then pitch, then yaw, each around the fixed field axes
+    pub fn new(roll: Angle, pitch: Angle, yaw: Angle) -> Self {
+        Self {
+            quaternion: UnitQuaternion::from_euler_angles(
+                roll.get::<radian>(),
+                pitch.get::<radian>(),
+                yaw.get::<radian>(),
+            ),
+        }
+    }
+
+    pub fn from_quaternion(quaternion: UnitQuaternion<f64>) -> Self {
+        Self { quaternion }
+    }
+
+    pub fn quaternion(&self) -> UnitQuaternion<f64> {
+        self.quaternion
+    }
+
+    pub fn roll(&self) -> Angle {
+        Angle::new::<radian>(self.quaternion.euler_angles().0)
+    }
+
+    pub fn pitch(&self) -> Angle {
+        Angle::new::<radian>(self.quaternion.euler_angles().1)
+    }
+
+    pub fn yaw(&self) -> Angle {
+        Angle::new::<radian>(self.quaternion.euler_angles().2)
+    }
+
+    /// Apply `other` after this rotation
+    pub fn rotate_by(&self, other: Rotation3d) -> Self {
+        Self::from_quaternion(other.quaternion * self.quaternion)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self::from_quaternion(self.quaternion.inverse())
+    }
+
+    /// The yaw of this rotation, dropping roll and pitch
+    pub fn to_rotation2d(&self) -> Rotation2d {
+        Rotation2d::new(self.yaw())
+    }
+}
+
+impl From<Rotation2d> for Rotation3d {
+    fn from(rotation: Rotation2d) -> Self {
+        Self::new(Angle::default(), Angle::default(), rotation.angle())
+    }
+}
+
+impl Add for Rotation3d {
+    type Output = Rotation3d;
+
+    fn add(self, other: Rotation3d) -> Rotation3d {
+        self.rotate_by(other)
+    }
+}
+
+impl Sub for Rotation3d {
+    type Output = Rotation3d;
+
+    fn sub(self, other: Rotation3d) -> Rotation3d {
+        self.rotate_by(other.inverse())
+    }
+}
+
+impl Neg for Rotation3d {
+    type Output = Rotation3d;
+
+    fn neg(self) -> Rotation3d {
+        self.inverse()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuaternionRepr {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl From<QuaternionRepr> for Rotation3d {
+    fn from(q: QuaternionRepr) -> Self {
+        Self::from_quaternion(UnitQuaternion::from_quaternion(Quaternion::new(
+            q.w, q.x, q.y, q.z,
+        )))
+    }
+}
+
+impl From<Rotation3d> for QuaternionRepr {
+    fn from(rotation: Rotation3d) -> Self {
+        let q = rotation.quaternion;
+        Self {
+            w: q.w,
+            x: q.i,
+            y: q.j,
+            z: q.k,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around() {
+        let sum = Rotation2d::from_degrees(170.) + Rotation2d::from_degrees(20.);
+
+        assert!((sum.degrees() - -170.).abs() < 1e-9);
+        assert!(((sum - Rotation2d::from_degrees(20.)).degrees() - 170.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn yaw_matches_2d() {
+        let a = Rotation3d::from(Rotation2d::from_degrees(170.));
+        let b = Rotation3d::from(Rotation2d::from_degrees(20.));
+
+        assert!(((a + b).to_rotation2d().degrees() - -170.).abs() < 1e-9);
+        assert!(((a - b).yaw().get::<degree>() - 150.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn serializes_readably() {
+        let json = serde_json::to_string(&Rotation2d::from_radians(0.5)).unwrap();
+        assert_eq!(json, r#"{"radians":0.5}"#);
+
+        let rotation = Rotation3d::new(
+            Angle::new::<degree>(10.),
+            Angle::new::<degree>(-20.),
+            Angle::new::<degree>(30.),
+        );
+        let json = serde_json::to_string(&rotation).unwrap();
+        let parsed: Rotation3d = serde_json::from_str(&json).unwrap();
+        assert!(((parsed - rotation).quaternion().angle()).abs() < 1e-9);
+    }
+}
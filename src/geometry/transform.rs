@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+use crate::geometry::{Pose2d, Pose3d, Rotation2d, Rotation3d, Translation2d, Translation3d};
+
+/// A movement relative to a pose: a translation in that pose's frame, then a rotation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transform2d {
+    pub translation: Translation2d,
+    pub rotation: Rotation2d,
+}
+
+impl Transform2d {
+    pub fn new(translation: Translation2d, rotation: Rotation2d) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// The transform that moves `initial` to `last`
+    pub fn between(initial: Pose2d, last: Pose2d) -> Self {
+        Self::new(
+            (last.translation - initial.translation).rotate_by(-initial.rotation),
+            last.rotation - initial.rotation,
+        )
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(&self) -> Self {
+        Self::new(
+            (-self.translation).rotate_by(-self.rotation),
+            -self.rotation,
+        )
+    }
+
+    /// This transform followed by `other`
+    pub fn then(&self, other: Transform2d) -> Self {
+        Self::between(Pose2d::default(), Pose2d::default() + *self + other)
+    }
+}
+
+impl Mul<f64> for Transform2d {
+    type Output = Transform2d;
+
+    fn mul(self, scalar: f64) -> Transform2d {
+        Transform2d::new(
+            self.translation * scalar,
+            Rotation2d::from_radians(self.rotation.radians() * scalar),
+        )
+    }
+}
+
+/// A movement relative to a pose in space, such as from the robot to a camera.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transform3d {
+    pub translation: Translation3d,
+    pub rotation: Rotation3d,
+}
+
+impl Transform3d {
+    pub fn new(translation: Translation3d, rotation: Rotation3d) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// The transform that moves `initial` to `last`
+    pub fn between(initial: Pose3d, last: Pose3d) -> Self {
+        Self::new(
+            (last.translation - initial.translation).rotate_by(-initial.rotation),
+            last.rotation - initial.rotation,
+        )
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(&self) -> Self {
+        Self::new(
+            (-self.translation).rotate_by(-self.rotation),
+            -self.rotation,
+        )
+    }
+
+    /// This transform followed by `other`
+    pub fn then(&self, other: Transform3d) -> Self {
+        Self::between(Pose3d::default(), Pose3d::default() + *self + other)
+    }
+}
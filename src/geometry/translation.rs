@@ -0,0 +1,194 @@
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use uom::si::f64::Length;
+use uom::si::length::meter;
+
+use crate::geometry::{Rotation2d, Rotation3d};
+
+/// A position or offset in the plane.
+///
+/// Follows the WPILib convention: +x is forward and +y is left. Serializes in meters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Translation2d {
+    pub x: Length,
+    pub y: Length,
+}
+
+impl Translation2d {
+    pub fn new(x: Length, y: Length) -> Self {
+        Self { x, y }
+    }
+
+    pub fn from_meters(x: f64, y: f64) -> Self {
+        Self::new(Length::new::<meter>(x), Length::new::<meter>(y))
+    }
+
+    /// The point `distance` away in the direction of `angle`
+    pub fn from_polar(distance: Length, angle: Rotation2d) -> Self {
+        Self::new(distance * angle.cos(), distance * angle.sin())
+    }
+
+    /// Distance from the origin
+    pub fn norm(&self) -> Length {
+        Length::new::<meter>(self.x.get::<meter>().hypot(self.y.get::<meter>()))
+    }
+
+    /// Direction from the origin
+    pub fn angle(&self) -> Rotation2d {
+        Rotation2d::from_components(self.x.get::<meter>(), self.y.get::<meter>())
+    }
+
+    pub fn distance(&self, other: Translation2d) -> Length {
+        (other - *self).norm()
+    }
+
+    /// Rotate counter-clockwise around the origin
+    pub fn rotate_by(&self, rotation: Rotation2d) -> Self {
+        Self::new(
+            self.x * rotation.cos() - self.y * rotation.sin(),
+            self.x * rotation.sin() + self.y * rotation.cos(),
+        )
+    }
+}
+
+impl Add for Translation2d {
+    type Output = Translation2d;
+
+    fn add(self, other: Translation2d) -> Translation2d {
+        Translation2d::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Translation2d {
+    type Output = Translation2d;
+
+    fn sub(self, other: Translation2d) -> Translation2d {
+        Translation2d::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Translation2d {
+    type Output = Translation2d;
+
+    fn neg(self) -> Translation2d {
+        Translation2d::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<f64> for Translation2d {
+    type Output = Translation2d;
+
+    fn mul(self, scalar: f64) -> Translation2d {
+        Translation2d::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl Div<f64> for Translation2d {
+    type Output = Translation2d;
+
+    fn div(self, scalar: f64) -> Translation2d {
+        Translation2d::new(self.x / scalar, self.y / scalar)
+    }
+}
+
+/// A position or offset in space, +z is up. Serializes in meters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Translation3d {
+    pub x: Length,
+    pub y: Length,
+    pub z: Length,
+}
+
+impl Translation3d {
+    pub fn new(x: Length, y: Length, z: Length) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn from_meters(x: f64, y: f64, z: f64) -> Self {
+        Self::new(
+            Length::new::<meter>(x),
+            Length::new::<meter>(y),
+            Length::new::<meter>(z),
+        )
+    }
+
+    /// Distance from the origin
+    pub fn norm(&self) -> Length {
+        Length::new::<meter>(self.meters().norm())
+    }
+
+    pub fn distance(&self, other: Translation3d) -> Length {
+        (other - *self).norm()
+    }
+
+    pub fn rotate_by(&self, rotation: Rotation3d) -> Self {
+        let rotated = rotation.quaternion() * self.meters();
+        Self::from_meters(rotated.x, rotated.y, rotated.z)
+    }
+
+    /// The position on the floor, dropping the height
+    pub fn to_translation2d(&self) -> Translation2d {
+        Translation2d::new(self.x, self.y)
+    }
+
+    fn meters(&self) -> Vector3<f64> {
+        Vector3::new(
+            self.x.get::<meter>(),
+            self.y.get::<meter>(),
+            self.z.get::<meter>(),
+        )
+    }
+}
+
+impl From<Translation2d> for Translation3d {
+    fn from(translation: Translation2d) -> Self {
+        Self::new(translation.x, translation.y, Length::default())
+    }
+}
+
+impl Add for Translation3d {
+    type Output = Translation3d;
+
+    fn add(self, other: Translation3d) -> Translation3d {
+        Translation3d::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Translation3d {
+    type Output = Translation3d;
+
+    fn sub(self, other: Translation3d) -> Translation3d {
+        Translation3d::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Translation3d {
+    type Output = Translation3d;
+
+    fn neg(self) -> Translation3d {
+        Translation3d::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<f64> for Translation3d {
+    type Output = Translation3d;
+
+    fn mul(self, scalar: f64) -> Translation3d {
+        Translation3d::new(self.x * scalar, self.y * scalar, self.z * scalar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_quarter_turn() {
+        let rotated = Translation2d::from_meters(1., 0.).rotate_by(Rotation2d::from_degrees(90.));
+
+        assert!(rotated.x.get::<meter>().abs() < 1e-9);
+        assert!((rotated.y.get::<meter>() - 1.).abs() < 1e-9);
+        assert!((rotated.norm().get::<meter>() - 1.).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use uom::si::f64::{Angle, Length};
+
+/// A change in pose along an arc, as measured by odometry.
+///
+/// `dx` is forward and `dy` is left, both relative to the pose at the start of the arc.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Twist2d {
+    pub dx: Length,
+    pub dy: Length,
+    pub dtheta: Angle,
+}
+
+impl Twist2d {
+    pub fn new(dx: Length, dy: Length, dtheta: Angle) -> Self {
+        Self { dx, dy, dtheta }
+    }
+}
@@ -0,0 +1,245 @@
+//! In-memory devices for running robot code without a JVM.
+//!
+//! Each fake is a cheap handle to shared state, so a test can keep a clone of the device it
+//! hands to a subsystem and inspect or drive it from the outside.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use uom::si::f64::{Angle, AngularVelocity};
+
+use super::{AbsoluteEncoder, DigitalInput, Encoder, Gyro, MotorController, PneumaticOutput};
+
+#[derive(Default)]
+struct MotorState {
+    output: f64,
+    position: Angle,
+    velocity: AngularVelocity,
+}
+
+/// A motor controller with an integrated encoder.
+///
+/// The commanded output is recorded, and the encoder reports whatever the test last set.
+#[derive(Clone, Default)]
+pub struct FakeMotor {
+    state: Rc<RefCell<MotorState>>,
+}
+
+impl FakeMotor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Last commanded output, from -1 to 1
+    pub fn output(&self) -> f64 {
+        self.state.borrow().output
+    }
+
+    pub fn set_position(&self, position: Angle) {
+        self.state.borrow_mut().position = position;
+    }
+
+    pub fn set_velocity(&self, velocity: AngularVelocity) {
+        self.state.borrow_mut().velocity = velocity;
+    }
+}
+
+impl MotorController for FakeMotor {
+    fn set_percent(&mut self, percent: f64) {
+        self.state.borrow_mut().output = percent.clamp(-1., 1.);
+    }
+
+    fn stop(&mut self) {
+        self.state.borrow_mut().output = 0.;
+    }
+}
+
+impl Encoder for FakeMotor {
+    fn position(&mut self) -> Angle {
+        self.state.borrow().position
+    }
+
+    fn velocity(&mut self) -> AngularVelocity {
+        self.state.borrow().velocity
+    }
+}
+
+/// An absolute encoder reporting a position set by the test.
+#[derive(Clone, Default)]
+pub struct FakeAbsoluteEncoder {
+    position: Rc<Cell<Angle>>,
+}
+
+impl FakeAbsoluteEncoder {
+    pub fn new(position: Angle) -> Self {
+        Self {
+            position: Rc::new(Cell::new(position)),
+        }
+    }
+
+    pub fn set(&self, position: Angle) {
+        self.position.set(position);
+    }
+}
+
+impl AbsoluteEncoder for FakeAbsoluteEncoder {
+    fn absolute_position(&mut self) -> Angle {
+        self.position.get()
+    }
+}
+
+/// A gyro whose raw reading is set by the test. Resetting it zeroes the reported heading
+/// without touching the raw reading, the same way the real devices behave.
+#[derive(Clone, Default)]
+pub struct FakeGyro {
+    raw: Rc<Cell<Angle>>,
+    offset: Rc<Cell<Angle>>,
+}
+
+impl FakeGyro {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the raw heading of the sensor, counter-clockwise positive
+    pub fn set_heading(&self, heading: Angle) {
+        self.raw.set(heading);
+    }
+}
+
+impl Gyro for FakeGyro {
+    fn heading(&mut self) -> Angle {
+        self.raw.get() - self.offset.get()
+    }
+
+    fn reset(&mut self) {
+        self.offset.set(self.raw.get());
+    }
+}
+
+/// A digital input whose level is set by the test.
+#[derive(Clone, Default)]
+pub struct FakeDigitalInput {
+    value: Rc<Cell<bool>>,
+}
+
+impl FakeDigitalInput {
+    pub fn new(value: bool) -> Self {
+        Self {
+            value: Rc::new(Cell::new(value)),
+        }
+    }
+
+    pub fn set(&self, value: bool) {
+        self.value.set(value);
+    }
+}
+
+impl DigitalInput for FakeDigitalInput {
+    fn get(&self) -> bool {
+        self.value.get()
+    }
+}
+
+/// A solenoid that remembers whether it was engaged.
+#[derive(Clone, Default)]
+pub struct FakeSolenoid {
+    engaged: Rc<Cell<bool>>,
+}
+
+impl FakeSolenoid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn toggle(&self) {
+        self.engaged.set(!self.engaged.get());
+    }
+}
+
+impl PneumaticOutput for FakeSolenoid {
+    fn set(&mut self, engaged: bool) {
+        self.engaged.set(engaged);
+    }
+
+    fn get(&self) -> bool {
+        self.engaged.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::angle::degree;
+    use uom::si::angular_velocity::revolution_per_minute;
+
+    struct Shooter<M: MotorController + Encoder, D: DigitalInput> {
+        flywheel: M,
+        beam_break: D,
+    }
+
+    impl<M: MotorController + Encoder, D: DigitalInput> Shooter<M, D> {
+        fn periodic(&mut self) {
+            if self.beam_break.get() {
+                self.flywheel.set_percent(1.);
+            } else {
+                self.flywheel.stop();
+            }
+        }
+
+        fn at_speed(&mut self) -> bool {
+            self.flywheel.velocity().get::<revolution_per_minute>() > 5000.
+        }
+    }
+
+    #[test]
+    fn generic_subsystem() {
+        let flywheel = FakeMotor::new();
+        let beam_break = FakeDigitalInput::new(false);
+
+        let mut shooter = Shooter {
+            flywheel: flywheel.clone(),
+            beam_break: beam_break.clone(),
+        };
+
+        shooter.periodic();
+        assert_eq!(flywheel.output(), 0.);
+
+        beam_break.set(true);
+        shooter.periodic();
+        assert_eq!(flywheel.output(), 1.);
+
+        assert!(!shooter.at_speed());
+        flywheel.set_velocity(AngularVelocity::new::<revolution_per_minute>(5500.));
+        assert!(shooter.at_speed());
+    }
+
+    #[test]
+    fn motor_output_is_clamped() {
+        let mut motor = FakeMotor::new();
+        motor.set_percent(2.);
+
+        assert_eq!(motor.output(), 1.);
+    }
+
+    #[test]
+    fn gyro_reset_zeroes_heading() {
+        let mut gyro = FakeGyro::new();
+        gyro.set_heading(Angle::new::<degree>(90.));
+        gyro.reset();
+
+        assert_eq!(gyro.heading().get::<degree>(), 0.);
+
+        gyro.set_heading(Angle::new::<degree>(135.));
+        assert!((gyro.heading().get::<degree>() - 45.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn solenoid_toggle() {
+        let mut solenoid = FakeSolenoid::new();
+        solenoid.set(true);
+        solenoid.toggle();
+
+        assert!(!solenoid.get());
+    }
+}
@@ -0,0 +1,81 @@
+//! Backend-agnostic device traits.
+//!
+//! Every device in this crate talks to the JVM, so code written against the concrete types
+//! (`Talon`, `Spark`, `Pigeon`, ...) can only ever run on the roboRIO. Subsystems that are
+//! generic over these traits can be handed the real devices on the robot and the in-memory
+//! devices from [`fake`] in `cargo test`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use frcrs::hardware::{Encoder, MotorController};
+//! use frcrs::hardware::fake::FakeMotor;
+//! use uom::si::angle::revolution;
+//!
+//! struct Intake<M: MotorController + Encoder> {
+//!     motor: M,
+//! }
+//!
+//! impl<M: MotorController + Encoder> Intake<M> {
+//!     fn run(&mut self) {
+//!         if self.motor.position().get::<revolution>() < 10. {
+//!             self.motor.set_percent(0.5);
+//!         } else {
+//!             self.motor.stop();
+//!         }
+//!     }
+//! }
+//!
+//! let motor = FakeMotor::new();
+//! let mut intake = Intake { motor: motor.clone() };
+//! intake.run();
+//! assert_eq!(motor.output(), 0.5);
+//! ```
+
+pub mod fake;
+
+use uom::si::f64::{Angle, AngularVelocity};
+
+/// Anything that can drive a mechanism with a duty cycle.
+pub trait MotorController {
+    /// Set the output as a fraction of the supply voltage, from -1 to 1
+    fn set_percent(&mut self, percent: f64);
+
+    /// Stop the motor
+    fn stop(&mut self);
+}
+
+/// A relative encoder, such as the one integrated in a brushless motor.
+pub trait Encoder {
+    /// Position of the rotor since the encoder was last zeroed
+    fn position(&mut self) -> Angle;
+
+    /// Current speed of the rotor
+    fn velocity(&mut self) -> AngularVelocity;
+}
+
+/// An encoder that knows where it is on power up, like a CANcoder.
+pub trait AbsoluteEncoder {
+    /// Absolute position of the sensor
+    fn absolute_position(&mut self) -> Angle;
+}
+
+/// A yaw sensor.
+pub trait Gyro {
+    /// Heading of the robot, counter-clockwise positive
+    fn heading(&mut self) -> Angle;
+
+    /// Set the current heading to zero
+    fn reset(&mut self);
+}
+
+/// A digital input, such as a limit switch or beam break.
+pub trait DigitalInput {
+    fn get(&self) -> bool;
+}
+
+/// A single pneumatic output, such as a solenoid.
+pub trait PneumaticOutput {
+    fn set(&mut self, engaged: bool);
+    fn get(&self) -> bool;
+}
@@ -1,20 +1,14 @@
 use std::future::Future;
 use std::time::Instant;
 
-use crate::{
-    call::{call, call_static, create, once},
-    java,
-};
+use crate::driver_station;
+use crate::input::Trigger;
 use bitvec::prelude::*;
-use jni::{
-    objects::{GlobalRef, JObject, JValue},
-    signature::{Primitive, ReturnType},
-};
-use once_cell::sync::OnceCell;
 use tokio::task::LocalSet;
 
 // https://github.com/wpilibsuite/allwpilib/blob/main/wpilibj/src/main/java/edu/wpi/first/wpilibj/XboxController.java
-enum Buttons {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Buttons {
     LeftBumper = 5,
     RightBumper = 6,
     LeftStick = 9,
@@ -35,6 +29,7 @@ enum Axis {
     RightTrigger = 3,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -76,9 +71,9 @@ impl Direction {
 #[derive(Clone)]
 pub struct Gamepad {
     id: i32,
-    instance: GlobalRef,
     buttons: BitVec,
-    last_updated: Instant,
+    last_updated: Option<Instant>,
+    rumble: (f64, f64),
 }
 
 impl Gamepad {
@@ -88,126 +83,63 @@ impl Gamepad {
     ///
     /// * `id` - The identifier for the gamepad.
     ///
-    /// This function initializes the gamepad instance, sets up an empty button state, and
-    /// initializes the last updated timestamp.
+    /// This function sets up an empty button state, which is read on first use.
+    /// Readings come from the current [`driver_station`] source.
     ///
     /// # Returns
     ///
     /// A new `Gamepad` instance.
     pub fn new(id: i32) -> Self {
-        let instance = create!(
-            "edu/wpi/first/wpilibj/XboxController",
-            "(I)V",
-            &[JValue::Int(id).as_jni()]
-        );
-
         let buttons = bitvec![0; 32];
-        let last_updated = Instant::now();
-
-        let instance = java().new_global_ref(instance).unwrap();
+        let last_updated = None;
 
         Self {
             id,
-            instance,
             buttons,
             last_updated,
+            rumble: (0., 0.),
         }
     }
 
+    fn axis(&self, axis: Axis) -> f64 {
+        driver_station::with_source(|ds| ds.stick_axis(self.id, axis as i32))
+    }
+
     pub fn left_y(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getLeftY",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::LeftY)
     }
 
     pub fn right_y(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getRightY",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::RightY)
     }
 
     pub fn left_x(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getLeftX",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::LeftX)
     }
 
     pub fn right_x(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getRightX",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::RightX)
     }
 
     pub fn left_trigger(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getLeftTriggerAxis",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::LeftTrigger)
     }
 
     pub fn right_trigger(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/XboxController",
-            "getRightTriggerAxis",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::RightTrigger)
     }
 
     fn button(&mut self, id: usize) -> bool {
-        if self.last_updated.elapsed().as_millis() < 15 {
+        if self
+            .last_updated
+            .is_some_and(|updated| updated.elapsed().as_millis() < 15)
+        {
             return self.buttons[id - 1];
         }
 
-        let value = call_static!(
-            "edu/wpi/first/wpilibj/DriverStation",
-            "getStickButtons",
-            "(I)I",
-            &[JValue::Int(self.id).as_jni()],
-            ReturnType::Primitive(Primitive::Int)
-        )
-        .i()
-        .unwrap();
+        let value = driver_station::with_source(|ds| ds.stick_buttons(self.id));
         self.buttons[..].store(value);
-        self.last_updated = Instant::now();
+        self.last_updated = Some(Instant::now());
         self.buttons[id - 1]
     }
 
@@ -252,83 +184,48 @@ impl Gamepad {
     }
 
     pub fn rumble_left(&mut self, strength: f64) {
-        let left = once!(java()
-            .call_static_method(
-                "frc/robot/Wrapper",
-                "kLeftRumble",
-                "()Ledu/wpi/first/wpilibj/GenericHID$RumbleType;",
-                &Vec::new()
-            )
-            .unwrap()
-            .l()
-            .unwrap());
-
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/GenericHID",
-            "setRumble",
-            "(Ledu/wpi/first/wpilibj/GenericHID$RumbleType;D)V",
-            &[
-                JValue::Object(&left).as_jni(),
-                JValue::Double(strength).as_jni(),
-            ],
-            ReturnType::Primitive(Primitive::Void)
-        );
+        self.rumble.0 = strength;
+        driver_station::with_source(|ds| ds.set_rumble(self.id, self.rumble.0, self.rumble.1));
     }
 
     pub fn rumble_right(&mut self, strength: f64) {
-        let left = once!(java()
-            .call_static_method(
-                "frc/robot/Wrapper",
-                "kRightRumble",
-                "()Ledu/wpi/first/wpilibj/GenericHID$RumbleType;",
-                &Vec::new()
-            )
-            .unwrap()
-            .l()
-            .unwrap());
-
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/GenericHID",
-            "setRumble",
-            "(Ledu/wpi/first/wpilibj/GenericHID$RumbleType;D)V",
-            &[
-                JValue::Object(&left).as_jni(),
-                JValue::Double(strength).as_jni(),
-            ],
-            ReturnType::Primitive(Primitive::Void)
-        );
+        self.rumble.1 = strength;
+        driver_station::with_source(|ds| ds.set_rumble(self.id, self.rumble.0, self.rumble.1));
     }
 
     pub fn get_dpad(&self) -> i32 {
-        call!(
-            &self.instance,
-            "edu/wpi/first/wpilibj/GenericHID",
-            "getPOV",
-            "()I",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Int)
-        )
-        .i()
-        .unwrap()
+        driver_station::with_source(|ds| ds.stick_pov(self.id, 0))
     }
 
     pub fn get_dpad_direction(&self) -> Direction {
-        Direction::from_degrees(
-            call!(
-                &self.instance,
-                "edu/wpi/first/wpilibj/GenericHID",
-                "getPOV",
-                "()I",
-                &Vec::new(),
-                ReturnType::Primitive(Primitive::Int)
-            )
-            .i()
-            .unwrap(),
-        )
+        Direction::from_degrees(self.get_dpad())
+    }
+
+    /// A trigger that is true while `button` is held
+    pub fn trigger(&self, button: Buttons) -> Trigger {
+        let mut gamepad = self.clone();
+        Trigger::new(move || gamepad.button(button as usize))
+    }
+
+    /// A trigger that is true while the D-pad points in `direction`
+    pub fn dpad_trigger(&self, direction: Direction) -> Trigger {
+        let gamepad = self.clone();
+        Trigger::new(move || gamepad.get_dpad_direction() == direction)
+    }
+
+    /// A trigger that is true while the left trigger is pressed past `threshold`
+    pub fn left_trigger_above(&self, threshold: f64) -> Trigger {
+        let gamepad = self.clone();
+        Trigger::new(move || gamepad.left_trigger() > threshold)
+    }
+
+    /// A trigger that is true while the right trigger is pressed past `threshold`
+    pub fn right_trigger_above(&self, threshold: f64) -> Trigger {
+        let gamepad = self.clone();
+        Trigger::new(move || gamepad.right_trigger() > threshold)
     }
 
+    #[deprecated(note = "spawns a task on every call, use `Gamepad::trigger` instead")]
     pub fn while_held<F, Fut>(mut self, button_id: usize, local: &LocalSet, action: F)
     where
         F: Fn() -> Fut + Send + Sync + 'static,
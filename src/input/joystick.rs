@@ -1,30 +1,31 @@
 use std::future::Future;
 use std::time::Instant;
 
-use crate::{
-    call::{call, call_static, create},
-    java, JAVA,
-};
+use crate::driver_station;
+use crate::input::Trigger;
 use bitvec::prelude::*;
-use jni::{
-    objects::{GlobalRef, JValue},
-    signature::{Primitive, ReturnType},
-};
 use tokio::task::JoinHandle;
 
+// https://github.com/wpilibsuite/allwpilib/blob/main/wpilibj/src/main/java/edu/wpi/first/wpilibj/Joystick.java
+enum Axis {
+    X = 0,
+    Y = 1,
+    Z = 2,
+    Throttle = 3,
+}
+
 #[derive(Clone)]
 pub struct Joystick {
     id: i32,
-    instance: GlobalRef,
     buttons: BitVec,
-    last_updated: Instant,
+    last_updated: Option<Instant>,
 }
 
 impl Joystick {
     /// Creates a new instance of `Joystick` with the given `id`.
     ///
-    /// This method initializes a new Java object for the joystick and sets up its button states
-    /// and last updated timestamp.
+    /// This method sets up an empty button state, which is read on first use. Readings come from the
+    /// current [`driver_station`] source.
     ///
     /// # Arguments
     /// - `id`: An integer identifier for the joystick.
@@ -32,100 +33,58 @@ impl Joystick {
     /// # Returns
     /// A new instance of `Joystick`.
     pub fn new(id: i32) -> Self {
-        JAVA.attach_current_thread_as_daemon().unwrap();
-        let instance = create!(
-            "edu/wpi/first/wpilibj/Joystick",
-            "(I)V",
-            &[JValue::Int(id).as_jni()]
-        );
-
         let buttons = bitvec![0; 32];
-        let last_updated = Instant::now();
-
-        let instance = java().new_global_ref(instance).unwrap();
+        let last_updated = None;
 
         Self {
             id,
-            instance,
             buttons,
             last_updated,
         }
     }
 
+    fn axis(&self, axis: Axis) -> f64 {
+        driver_station::with_source(|ds| ds.stick_axis(self.id, axis as i32))
+    }
+
     /// Gets the X-axis value of the joystick.
     ///
-    /// This method calls the corresponding Java method to retrieve the X-axis value.
+    /// This method reads the X-axis value from the driver station.
     ///
     /// # Returns
     /// The X-axis value as a `f64`.
     pub fn get_x(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/Joystick",
-            "getX",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::X)
     }
 
     /// Gets the Y-axis value of the joystick.
     ///
-    /// This method calls the corresponding Java method to retrieve the Y-axis value.
+    /// This method reads the Y-axis value from the driver station.
     ///
     /// # Returns
     /// The Y-axis value as a `f64`.
     pub fn get_y(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/Joystick",
-            "getY",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::Y)
     }
 
     /// Gets the Z-axis value of the joystick.
     ///
-    /// This method calls the corresponding Java method to retrieve the Z-axis value.
+    /// This method reads the Z-axis value from the driver station.
     ///
     /// # Returns
     /// The Z-axis value as a `f64`.
     pub fn get_z(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/Joystick",
-            "getZ",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::Z)
     }
 
     /// Gets the throttle value of the joystick.
     ///
-    /// This method calls the corresponding Java method to retrieve the throttle value.
+    /// This method reads the throttle value from the driver station.
     ///
     /// # Returns
     /// The throttle value as a `f64`.
     pub fn get_throttle(&self) -> f64 {
-        call!(
-            self.instance.as_obj(),
-            "edu/wpi/first/wpilibj/Joystick",
-            "getThrottle",
-            "()D",
-            &Vec::new(),
-            ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        self.axis(Axis::Throttle)
     }
 
     /// Gets the state of the specified button by `id`.
@@ -139,36 +98,30 @@ impl Joystick {
     /// # Returns
     /// `true` if the button is pressed, `false` otherwise.
     pub fn get(&mut self, id: usize) -> bool {
-        if self.last_updated.elapsed().as_millis() < 15 {
+        if self
+            .last_updated
+            .is_some_and(|updated| updated.elapsed().as_millis() < 15)
+        {
             return self.buttons[id - 1];
         }
 
-        let value = call_static!(
-            "edu/wpi/first/wpilibj/DriverStation",
-            "getStickButtons",
-            "(I)I",
-            &[JValue::Int(self.id).as_jni()],
-            ReturnType::Primitive(Primitive::Int)
-        )
-        .i()
-        .unwrap();
+        let value = driver_station::with_source(|ds| ds.stick_buttons(self.id));
         self.buttons[..].store(value);
-        self.last_updated = Instant::now();
+        self.last_updated = Some(Instant::now());
         self.buttons[id - 1]
     }
 
     pub fn get_pov(&self) -> i32 {
-        call_static!(
-            "edu/wpi/first/wpilibj/DriverStation",
-            "getStickPOV",
-            "(II)I",
-            &[JValue::Int(self.id).as_jni(), JValue::Int(0).as_jni()],
-            ReturnType::Primitive(Primitive::Int)
-        )
-        .i()
-        .unwrap()
+        driver_station::with_source(|ds| ds.stick_pov(self.id, 0))
+    }
+
+    /// A trigger that is true while button `id` is held, numbered from 1
+    pub fn trigger(&self, id: usize) -> Trigger {
+        let mut joystick = self.clone();
+        Trigger::new(move || joystick.get(id))
     }
 
+    #[deprecated(note = "spawns a task on every call, use `Joystick::trigger` instead")]
     pub fn while_held<F, Fut>(&'static mut self, button_id: usize, action: F)
     where
         F: Fn() -> Fut + Send + Sync + 'static,
@@ -1,15 +1,16 @@
 mod gamepad;
 mod joystick;
+mod trigger;
 
 pub use gamepad::*;
-use jni::signature::{Primitive, ReturnType};
 pub use joystick::*;
+pub use trigger::*;
 
 use bitvec::prelude::*;
 
-use crate::call::call_static;
+use crate::driver_station;
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum RobotMode {
     Disabled,
     Auto,
@@ -24,23 +25,18 @@ pub struct RobotState {
 
 impl RobotState {
     pub fn get() -> Self {
-        let value = call_static!(
-            "edu/wpi/first/hal/DriverStationJNI",
-            "nativeGetControlWord",
-            "()I",
-            &[],
-            ReturnType::Primitive(Primitive::Int)
-        )
-        .i()
-        .unwrap();
+        driver_station::with_source(|ds| ds.robot_state())
+    }
 
+    /// Decode the HAL control word
+    pub fn from_control_word(word: i32) -> Self {
         let mut buttons = bitvec![0; 32];
-        buttons[..].store(value);
+        buttons[..].store(word);
         Self { buttons }
     }
 
     pub fn teleop(&self) -> bool {
-        !self.buttons[1]
+        !self.buttons[1] && !self.buttons[2]
     }
 
     pub fn auto(&self) -> bool {
@@ -0,0 +1,381 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::hardware::DigitalInput;
+use crate::{TaskId, TaskManager};
+
+/// A boolean condition that tasks can be bound to.
+///
+/// Triggers are cheap to clone, and clones read the same condition. Bindings are checked when
+/// [`Bindings::poll`] is called, which should happen once per loop.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::input::{Bindings, Buttons, Gamepad};
+/// use frcrs::TaskManager;
+///
+/// let gamepad = Gamepad::new(0);
+/// let mut bindings = Bindings::new();
+/// let mut tasks = TaskManager::new();
+///
+/// gamepad
+///     .trigger(Buttons::A)
+///     .and(gamepad.right_trigger_above(0.5))
+///     .while_true(&mut bindings, || async { /* shoot */ });
+///
+/// // every loop
+/// bindings.poll(&mut tasks);
+/// ```
+#[derive(Clone)]
+pub struct Trigger {
+    condition: Rc<RefCell<dyn FnMut() -> bool>>,
+}
+
+impl Trigger {
+    pub fn new(condition: impl FnMut() -> bool + 'static) -> Self {
+        Self {
+            condition: Rc::new(RefCell::new(condition)),
+        }
+    }
+
+    /// True while a digital input reads true
+    pub fn from_input(input: impl DigitalInput + 'static) -> Self {
+        Self::new(move || input.get())
+    }
+
+    pub fn get(&self) -> bool {
+        (self.condition.borrow_mut())()
+    }
+
+    pub fn and(&self, other: Trigger) -> Self {
+        let this = self.clone();
+        Self::new(move || this.get() && other.get())
+    }
+
+    pub fn or(&self, other: Trigger) -> Self {
+        let this = self.clone();
+        Self::new(move || this.get() || other.get())
+    }
+
+    pub fn negate(&self) -> Self {
+        let this = self.clone();
+        Self::new(move || !this.get())
+    }
+
+    /// Only become true once the condition has been true for `duration`
+    pub fn debounce(&self, duration: Duration) -> Self {
+        let this = self.clone();
+        let mut since: Option<Instant> = None;
+
+        Self::new(move || {
+            if this.get() {
+                since.get_or_insert_with(Instant::now).elapsed() >= duration
+            } else {
+                since = None;
+                false
+            }
+        })
+    }
+
+    /// Run `task` once each time the trigger becomes true, restarting it if it's still running
+    pub fn on_true<F, Fut>(&self, bindings: &mut Bindings, task: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        bindings.add(self.clone(), Kind::OnTrue, once(task));
+    }
+
+    /// Run `task` once each time the trigger becomes false, restarting it if it's still running
+    pub fn on_false<F, Fut>(&self, bindings: &mut Bindings, task: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        bindings.add(self.clone(), Kind::OnFalse, once(task));
+    }
+
+    /// Run `task` repeatedly through the [`TaskManager`] while the trigger is true
+    pub fn while_true<F, Fut>(&self, bindings: &mut Bindings, task: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        bindings.add(self.clone(), Kind::WhileTrue, repeated(task));
+    }
+
+    /// Start or stop running `task` repeatedly each time the trigger becomes true
+    pub fn toggle_on_true<F, Fut>(&self, bindings: &mut Bindings, task: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        bindings.add(self.clone(), Kind::ToggleOnTrue, repeated(task));
+    }
+}
+
+type Start = Box<dyn FnMut(&mut TaskManager, TaskId)>;
+
+fn once<F, Fut>(mut task: F) -> Start
+where
+    F: FnMut() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    Box::new(move |tasks, id| {
+        tasks.abort_task(id.clone());
+        tasks.run_once(id, task());
+    })
+}
+
+fn repeated<F, Fut>(task: F) -> Start
+where
+    F: FnMut() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    let task = Rc::new(RefCell::new(task));
+
+    Box::new(move |tasks, id| {
+        let task = task.clone();
+        tasks.run_task(id, move || (task.borrow_mut())());
+    })
+}
+
+enum Kind {
+    OnTrue,
+    OnFalse,
+    WhileTrue,
+    ToggleOnTrue,
+}
+
+struct Binding {
+    trigger: Trigger,
+    previous: bool,
+    kind: Kind,
+    id: TaskId,
+    start: Start,
+}
+
+/// The set of trigger bindings to check each loop.
+///
+/// Each binding runs its task in the [`TaskManager`] under its own [`TaskId`]. Triggers start
+/// out false, so a trigger that is already true on the first poll counts as becoming true.
+#[derive(Default)]
+pub struct Bindings {
+    bindings: Vec<Binding>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(&mut self, trigger: Trigger, kind: Kind, start: Start) {
+        self.bindings.push(Binding {
+            trigger,
+            previous: false,
+            kind,
+            id: TaskId::binding(),
+            start,
+        });
+    }
+
+    /// Check every trigger and start or stop tasks on edges
+    pub fn poll(&mut self, tasks: &mut TaskManager) {
+        for binding in &mut self.bindings {
+            let current = binding.trigger.get();
+            let rising = current && !binding.previous;
+            let falling = !current && binding.previous;
+            binding.previous = current;
+
+            let id = binding.id.clone();
+            match binding.kind {
+                Kind::OnTrue | Kind::WhileTrue if rising => (binding.start)(tasks, id),
+                Kind::OnFalse if falling => (binding.start)(tasks, id),
+                Kind::WhileTrue if falling => tasks.abort_task(id),
+                Kind::ToggleOnTrue if rising => {
+                    if tasks.is_running(id.clone()) {
+                        tasks.abort_task(id);
+                    } else {
+                        (binding.start)(tasks, id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver_station::{set_source, SimDriverStation};
+    use crate::hardware::fake::FakeDigitalInput;
+    use crate::input::{Buttons, Direction, Gamepad};
+    use std::cell::Cell;
+    use tokio::task::{yield_now, LocalSet};
+
+    fn run_local(test: impl Future<Output = ()>) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        LocalSet::new().block_on(&runtime, test);
+    }
+
+    fn counter() -> (Rc<Cell<usize>>, impl FnMut() -> std::future::Ready<()>) {
+        let count = Rc::new(Cell::new(0));
+        let counted = count.clone();
+        (count, move || {
+            counted.set(counted.get() + 1);
+            std::future::ready(())
+        })
+    }
+
+    #[test]
+    fn combinators() {
+        let input = FakeDigitalInput::new(false);
+        let a = Trigger::from_input(input.clone());
+        let value = Rc::new(Cell::new(false));
+        let read = value.clone();
+        let b = Trigger::new(move || read.get());
+
+        assert!(!a.and(b.clone()).get());
+        assert!(!a.or(b.clone()).get());
+        assert!(a.negate().get());
+
+        input.set(true);
+        assert!(a.or(b.clone()).get());
+        value.set(true);
+        assert!(a.and(b).get());
+    }
+
+    #[test]
+    fn debounce_waits() {
+        let value = Rc::new(Cell::new(true));
+        let read = value.clone();
+        let debounced = Trigger::new(move || read.get()).debounce(Duration::from_millis(20));
+
+        assert!(!debounced.get());
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(debounced.get());
+
+        value.set(false);
+        assert!(!debounced.get());
+        value.set(true);
+        assert!(!debounced.get());
+    }
+
+    #[test]
+    fn on_true_fires_on_rising_edge() {
+        let ds = SimDriverStation::new();
+        set_source(ds.clone());
+
+        let gamepad = Gamepad::new(0);
+        let (pressed, on_pressed) = counter();
+        let (released, on_released) = counter();
+
+        let mut bindings = Bindings::new();
+        let mut tasks = TaskManager::new();
+        gamepad
+            .trigger(Buttons::A)
+            .on_true(&mut bindings, on_pressed);
+        gamepad
+            .trigger(Buttons::A)
+            .on_false(&mut bindings, on_released);
+
+        run_local(async {
+            for held in [false, true, true, false, true] {
+                ds.set_button(0, 1, held);
+                std::thread::sleep(Duration::from_millis(16));
+                bindings.poll(&mut tasks);
+                yield_now().await;
+            }
+        });
+
+        assert_eq!(pressed.get(), 2);
+        assert_eq!(released.get(), 1);
+    }
+
+    #[test]
+    fn separate_bindings_get_distinct_tasks() {
+        let driver_input = FakeDigitalInput::new(false);
+        let operator_input = FakeDigitalInput::new(false);
+        let (driver_runs, driver_task) = counter();
+        let (operator_runs, operator_task) = counter();
+
+        let mut driver = Bindings::new();
+        let mut operator = Bindings::new();
+        let mut tasks = TaskManager::new();
+        Trigger::from_input(driver_input.clone()).while_true(&mut driver, driver_task);
+        Trigger::from_input(operator_input.clone()).while_true(&mut operator, operator_task);
+
+        run_local(async {
+            tasks.run_task("binding 0", || async {});
+
+            driver_input.set(true);
+            operator_input.set(true);
+            driver.poll(&mut tasks);
+            operator.poll(&mut tasks);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(driver_runs.get() > 0);
+            assert!(operator_runs.get() > 0);
+
+            // releasing one doesn't stop the other
+            driver_input.set(false);
+            driver.poll(&mut tasks);
+            let operator_before = operator_runs.get();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(operator_runs.get() > operator_before);
+            assert!(tasks.is_running("binding 0"));
+        });
+    }
+
+    #[test]
+    fn while_true_and_toggle() {
+        let ds = SimDriverStation::new();
+        set_source(ds.clone());
+
+        let gamepad = Gamepad::new(0);
+        let (held_runs, held_task) = counter();
+        let (toggled_runs, toggled_task) = counter();
+
+        let mut bindings = Bindings::new();
+        let mut tasks = TaskManager::new();
+        gamepad
+            .dpad_trigger(Direction::Up)
+            .while_true(&mut bindings, held_task);
+        gamepad
+            .dpad_trigger(Direction::Down)
+            .toggle_on_true(&mut bindings, toggled_task);
+
+        run_local(async {
+            let step = |ds: &SimDriverStation, angle| ds.set_pov(0, 0, angle);
+
+            step(&ds, 0);
+            bindings.poll(&mut tasks);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            step(&ds, -1);
+            bindings.poll(&mut tasks);
+            let after_release = held_runs.get();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(after_release > 0);
+            assert_eq!(held_runs.get(), after_release);
+
+            step(&ds, 180);
+            bindings.poll(&mut tasks);
+            step(&ds, -1);
+            bindings.poll(&mut tasks);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(toggled_runs.get() > 0);
+
+            step(&ds, 180);
+            bindings.poll(&mut tasks);
+            let after_toggle = toggled_runs.get();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert_eq!(toggled_runs.get(), after_toggle);
+        });
+    }
+}
@@ -1,4 +1,4 @@
-use crate::java;
+use crate::error::FrcError;
 use jni::objects::{GlobalRef, JValue};
 use jni::signature::{Primitive, ReturnType};
 use nalgebra::Vector4;
@@ -14,15 +14,19 @@ pub enum RangingMode {
 
 impl LaserCan {
     pub fn new(id: i32) -> Self {
-        let instance = create!(
+        Self::try_new(id).unwrap()
+    }
+
+    /// Like [`LaserCan::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32) -> Result<Self, FrcError> {
+        let instance = try_create!(
             "au/grapplerobotics/LaserCan",
             "(I)V",
             &[JValue::Int(id).as_jni()]
-        );
+        )
+        .map_err(FrcError::device("LaserCan", id))?;
 
-        Self {
-            instance: java().new_global_ref(instance).unwrap(),
-        }
+        Ok(Self { instance })
     }
 
     pub fn set_ranging_mode(&self, ranging_mode: RangingMode) {
@@ -3,6 +3,8 @@ use jni::{
     signature::{Primitive, ReturnType},
 };
 
+use crate::error::FrcError;
+
 #[derive(Clone)]
 pub struct Led {
     instance: GlobalRef,
@@ -11,48 +13,54 @@ pub struct Led {
 
 impl Led {
     pub fn new(port: i32, count: i32) -> Self {
-        let instance = create!(
+        Self::try_new(port, count).unwrap()
+    }
+
+    /// Like [`Led::new`], but returns an error instead of panicking
+    pub fn try_new(port: i32, count: i32) -> Result<Self, FrcError> {
+        let instance = try_create!(
             "edu/wpi/first/wpilibj/AddressableLED",
             "(I)V",
             &[JValue::Int(port).as_jni()]
-        );
+        )
+        .map_err(FrcError::device("AddressableLED", port))?;
 
-        call!(
+        try_call!(
             instance.clone(),
             "edu/wpi/first/wpilibj/AddressableLED",
             "setLength",
             "(I)V",
             &[JValue::Int(count).as_jni()],
             ReturnType::Primitive(Primitive::Void)
-        );
+        )?;
 
-        let buffer = create!(
+        let buffer = try_create!(
             "edu/wpi/first/wpilibj/AddressableLEDBuffer",
             "(I)V",
             &[JValue::Int(count).as_jni()]
-        );
+        )?;
 
         let obj = buffer.as_obj();
 
-        call!(
+        try_call!(
             &instance,
             "edu/wpi/first/wpilibj/AddressableLED",
             "setData",
             "(Ledu/wpi/first/wpilibj/AddressableLEDBuffer;)V",
             &[JValue::Object(obj).as_jni()],
             ReturnType::Primitive(Primitive::Void)
-        );
+        )?;
 
-        call!(
+        try_call!(
             &instance,
             "edu/wpi/first/wpilibj/AddressableLED",
             "start",
             "()V",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Void)
-        );
+        )?;
 
-        Self { instance, buffer }
+        Ok(Self { instance, buffer })
     }
 
     pub fn set_rgb(&self, idx: i32, r: i32, g: i32, b: i32) {
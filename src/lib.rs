@@ -1,47 +1,56 @@
 pub mod ctre;
 pub mod dio;
 pub mod drive;
+pub mod driver_station;
+pub mod error;
+pub mod feedforward;
+pub mod geometry;
+pub mod hardware;
 pub mod input;
 pub mod navx;
 pub mod networktables;
+pub mod pid;
 pub mod rev;
 #[macro_use]
 pub mod call;
 pub mod laser_can;
 pub mod led;
 pub mod limelight;
+pub mod motion_profile;
 pub mod redux;
+pub mod robot;
+pub mod s_curve;
+pub mod sim;
 pub mod solenoid;
+mod tasks;
 pub mod telemetry;
 pub mod trajectory;
 pub mod trapezoidal;
+pub mod watchdog;
 
+use crate::error::FrcError;
 use crate::input::{RobotMode, RobotState};
+pub use crate::tasks::{TaskId, TaskManager, TaskStatus};
+use crate::watchdog::Watchdog;
 use jni::objects::{JObject, JValue};
 use jni::signature::Primitive;
 use jni::{InitArgsBuilder, JNIEnv, JNIVersion, JavaVM};
 use lazy_static::lazy_static;
-use std::any::TypeId;
 use std::cmp::PartialEq;
-use std::future::Future;
-use std::hash::{Hash, Hasher};
-use tokio::task::{spawn_local, AbortHandle, LocalSet};
-use tokio::time::{interval, sleep};
+use tokio::task::LocalSet;
+use tokio::time::sleep;
 
 #[macro_use]
 extern crate uom;
 
 use crate::drive::ToTalonEncoder;
 use serde::de::IntoDeserializer;
-use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::ops::Range;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-fn create_jvm() -> JavaVM {
+fn create_jvm() -> Result<JavaVM, FrcError> {
     // set JAVA_HOME to /usr/local/frc/JRE/bin/
     let jvm_args = InitArgsBuilder::new()
         .version(JNIVersion::V8)
@@ -50,20 +59,28 @@ fn create_jvm() -> JavaVM {
         .option("-Djava.library.path=/usr/local/frc/third-party/lib")
         .option("-Djava.class.path=/home/lvuser/javastub.jar")
         .build()
-        .unwrap();
+        .map_err(|e| FrcError::JvmStartup(e.to_string()))?;
 
-    let jvm =
-        JavaVM::with_libjvm(jvm_args, || Ok("/usr/local/frc/JRE/lib/client/libjvm.so")).unwrap();
-    jvm.attach_current_thread_as_daemon().unwrap();
-    jvm
+    let jvm = JavaVM::with_libjvm(jvm_args, || Ok("/usr/local/frc/JRE/lib/client/libjvm.so"))
+        .map_err(|e| FrcError::JvmStartup(e.to_string()))?;
+    jvm.attach_current_thread_as_daemon()?;
+    Ok(jvm)
 }
 
 lazy_static! {
-    static ref JAVA: JavaVM = create_jvm();
+    // the error is kept as a message, since errors can't be shared between threads
+    static ref JAVA: Result<JavaVM, String> = create_jvm().map_err(|e| e.to_string());
+}
+
+/// Attach to the JVM, starting it on first use
+fn try_java() -> Result<JNIEnv<'static>, FrcError> {
+    let jvm = JAVA.as_ref().map_err(|e| FrcError::JvmStartup(e.clone()))?;
+
+    Ok(jvm.attach_current_thread_permanently()?)
 }
 
 fn java() -> JNIEnv<'static> {
-    JAVA.attach_current_thread_permanently().unwrap()
+    try_java().unwrap_or_else(|e| panic!("{}", e))
 }
 
 /// Map x (within from) to the same relative spot in to
@@ -114,35 +131,19 @@ mod tests {
 
 pub fn observe_user_program_starting() {
     // Show "robot code" on driver's station
-    call_static!(
-        "edu/wpi/first/hal/DriverStationJNI",
-        "observeUserProgramStarting",
-        "()V",
-        &Vec::new(),
-        jni::signature::ReturnType::Primitive(Primitive::Void)
-    );
+    driver_station::with_source(|ds| ds.observe_user_program_starting());
 }
 
 pub fn refresh_data() {
-    call_static!(
-        "edu/wpi/first/wpilibj/DriverStation",
-        "refreshData",
-        "()V",
-        &Vec::new(),
-        jni::signature::ReturnType::Primitive(Primitive::Void)
-    );
+    driver_station::with_source(|ds| ds.refresh());
 }
 
 pub fn init_hal() -> bool {
-    call_static!(
-        "edu/wpi/first/hal/HAL",
-        "initialize",
-        "(II)Z",
-        &[JValue::Int(500).as_jni(), JValue::Int(1).as_jni()],
-        jni::signature::ReturnType::Primitive(Primitive::Boolean)
-    )
-    .z()
-    .unwrap()
+    try_init_hal().is_ok()
+}
+
+pub fn try_init_hal() -> Result<(), FrcError> {
+    driver_station::with_source(|ds| ds.init())
 }
 
 pub fn hal_report(resource: i32, instance_number: i32, context: i32, feature: String) {
@@ -163,9 +164,17 @@ pub fn hal_report(resource: i32, instance_number: i32, context: i32, feature: St
     .unwrap();
 }
 
-pub struct AllianceStation(u8);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllianceStation(pub(crate) u8);
 
 impl AllianceStation {
+    pub const RED_1: AllianceStation = AllianceStation(1);
+    pub const RED_2: AllianceStation = AllianceStation(2);
+    pub const RED_3: AllianceStation = AllianceStation(3);
+    pub const BLUE_1: AllianceStation = AllianceStation(4);
+    pub const BLUE_2: AllianceStation = AllianceStation(5);
+    pub const BLUE_3: AllianceStation = AllianceStation(6);
+
     pub fn red(&self) -> bool {
         match self.0 {
             1 | 2 | 3 => true,
@@ -181,17 +190,7 @@ impl AllianceStation {
 }
 
 pub fn alliance_station() -> AllianceStation {
-    let station = call_static!(
-        "frc/robot/Wrapper",
-        "getAllianceStation",
-        "()I",
-        &Vec::new(),
-        jni::signature::ReturnType::Primitive(Primitive::Int)
-    )
-    .i()
-    .unwrap();
-
-    AllianceStation(station as u8)
+    driver_station::with_source(|ds| ds.alliance_station())
 }
 
 pub struct AllianceShift(u8);
@@ -226,15 +225,7 @@ pub fn alliance_shift() -> AllianceShift {
 }
 
 pub fn match_time() -> f64 {
-    call_static!(
-        "frc/robot/Wrapper",
-        "getMatchTime",
-        "()D",
-        &Vec::new(),
-        jni::signature::ReturnType::Primitive(Primitive::Double)
-    )
-    .d()
-    .unwrap()
+    driver_station::with_source(|ds| ds.match_time())
 }
 
 pub async fn sleep_hz(mut instant: Instant, hz: i32) {
@@ -265,119 +256,82 @@ macro_rules! container {
     }};
 }
 
-#[derive(Clone)]
-struct TaskId(String);
-
-impl PartialEq for TaskId {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
-    }
-}
+// The robot runs on a single threaded `LocalSet`, so the hook futures never need to be `Send`
+#[allow(async_fn_in_trait)]
+pub trait Robot {
+    async fn robot_init(&mut self);
+    async fn disabled_init(&mut self) {}
+    async fn autonomous_init(&mut self) {}
+    async fn teleop_init(&mut self) {}
+    async fn test_init(&mut self) {}
 
-impl Eq for TaskId {}
+    async fn disabled_periodic(&mut self) {}
+    async fn autonomous_periodic(&mut self) {}
+    async fn teleop_periodic(&mut self) {}
+    async fn test_periodic(&mut self) {}
 
-impl Hash for TaskId {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.hash(state)
-    }
-}
+    /// Called every loop in every mode, after the periodic for the current mode
+    async fn robot_periodic(&mut self) {}
 
-#[derive(Clone)]
-pub struct TaskManager {
-    running_tasks: HashMap<TaskId, (Arc<AtomicBool>, AbortHandle)>,
-}
+    fn disabled_exit(&mut self) {}
+    fn autonomous_exit(&mut self) {}
+    fn teleop_exit(&mut self) {}
+    fn test_exit(&mut self) {}
 
-impl TaskManager {
-    pub fn new() -> Self {
-        Self {
-            running_tasks: HashMap::new(),
-        }
+    /// Time from the start of one loop to the start of the next
+    fn period(&self) -> Duration {
+        Duration::from_millis(4)
     }
 
-    pub fn run_task<F, Fut>(&mut self, mut task_fn: F)
-    where
-        F: FnMut() -> Fut + 'static,
-        Fut: Future<Output = ()> + 'static,
-    {
-        let type_id = format!("{:?}", TypeId::of::<F>());
-        let task_id = TaskId(type_id);
-
-        if !self.running_tasks.contains_key(&task_id) {
-            // println!("Starting new task");
-
-            let running = Arc::new(AtomicBool::new(true));
-            let running_clone = running.clone();
-
-            //let task_fn = Arc::new(task_fn);
-            //let task_fn = task_fn.clone();
-
-            let future = async move {
-                // println!("Task loop starting");
-                let mut interval = interval(Duration::from_millis(4));
-                while running_clone.load(Ordering::SeqCst) {
-                    interval.tick().await;
-                    task_fn().await;
-                }
-                // println!("Task loop ended");
-            };
-
-            let abort_handle = spawn_local(future).abort_handle();
+    /// Command scheduler to run every loop, after the periodic for the current mode
+    fn scheduler(&mut self) -> Option<&mut robot::CommandScheduler> {
+        None
+    }
 
-            self.running_tasks.insert(task_id, (running, abort_handle));
-        } else {
-            // println!("Task already running");
-        }
+    /// Task manager whose tasks are cancelled whenever the robot changes mode
+    fn tasks(&mut self) -> Option<&mut TaskManager> {
+        None
     }
 
-    pub fn abort_task<F, Fut>(&mut self, mut task_fn: F)
+    /// Run the robot, panicking if it fails to start
+    fn start_competition(&mut self, runtime: tokio::runtime::Runtime, local_set: LocalSet)
     where
-        F: FnMut() -> Fut + 'static,
-        Fut: Future<Output = ()> + 'static,
+        Self: 'static,
     {
-        let type_id = format!("{:?}", TypeId::of::<F>());
-        let task_id = TaskId(type_id);
-
-        if let Some((running, abort_handle)) = self.running_tasks.remove(&task_id) {
-            println!("Aborting task");
-            running.store(false, Ordering::SeqCst);
-            abort_handle.abort();
-        } else {
-            // println!("Task not found");
+        if let Err(e) = self.try_start_competition(runtime, local_set) {
+            panic!("Robot failed to start: {}", e);
         }
     }
-}
 
-pub trait Robot {
-    async fn robot_init(&mut self);
-    fn disabled_init(&mut self) {}
-    fn autonomous_init(&mut self) {}
-    fn teleop_init(&mut self) {}
-    fn test_init(&mut self) {}
-
-    async fn disabled_periodic(&mut self) {}
-    async fn autonomous_periodic(&mut self) {}
-    async fn teleop_periodic(&mut self) {}
-    async fn test_periodic(&mut self) {}
-
-    fn start_competition(&mut self, runtime: tokio::runtime::Runtime, local_set: LocalSet)
+    /// Initialize the HAL, then run [`Robot::robot_init`] and the robot loop until the driver
+    /// station source asks to shut down
+    fn try_start_competition(
+        &mut self,
+        runtime: tokio::runtime::Runtime,
+        local_set: LocalSet,
+    ) -> Result<(), FrcError>
     where
         Self: 'static,
     {
         runtime.block_on(local_set.run_until(async {
-            self.robot_init().await;
+            try_init_hal()?;
 
-            if !init_hal() {
-                panic!("Failed to initialize HAL");
-            }
+            self.robot_init().await;
 
             observe_user_program_starting();
 
-            let mut previous_mode = RobotMode::Disabled;
-            let mut last_loop = Instant::now();
-            let mut dt = Duration::from_millis(0);
+            // no mode has run yet, so the first one gets an init without an exit before it
+            let mut previous_mode: Option<RobotMode> = None;
+            let mut watchdog = Watchdog::new(self.period());
 
             loop {
+                watchdog.reset();
                 refresh_data();
+                watchdog.add_epoch("refresh");
+
+                if driver_station::with_source(|ds| ds.shutdown_requested()) {
+                    break;
+                }
 
                 let state = RobotState::get().mode();
 
@@ -404,25 +358,26 @@ pub trait Robot {
                 //     }
                 // }
 
-                if state != previous_mode {
+                if previous_mode.as_ref() != Some(&state) {
+                    if let Some(tasks) = self.tasks() {
+                        tasks.cancel_all();
+                    }
+
+                    match previous_mode {
+                        Some(RobotMode::Disabled) => self.disabled_exit(),
+                        Some(RobotMode::Auto) => self.autonomous_exit(),
+                        Some(RobotMode::Teleop) => self.teleop_exit(),
+                        Some(RobotMode::Test) => self.test_exit(),
+                        None => {}
+                    }
+
                     match state {
-                        RobotMode::Disabled => {
-                            self.disabled_init();
-                            previous_mode = RobotMode::Disabled;
-                        }
-                        RobotMode::Auto => {
-                            self.autonomous_init();
-                            previous_mode = RobotMode::Auto;
-                        }
-                        RobotMode::Teleop => {
-                            self.teleop_init();
-                            previous_mode = RobotMode::Teleop;
-                        }
-                        RobotMode::Test => {
-                            self.test_init();
-                            previous_mode = RobotMode::Test;
-                        }
+                        RobotMode::Disabled => self.disabled_init().await,
+                        RobotMode::Auto => self.autonomous_init().await,
+                        RobotMode::Teleop => self.teleop_init().await,
+                        RobotMode::Test => self.test_init().await,
                     }
+                    watchdog.add_epoch("transition");
                 }
 
                 match state {
@@ -431,47 +386,25 @@ pub trait Robot {
                     RobotMode::Teleop => self.teleop_periodic().await,
                     RobotMode::Test => self.test_periodic().await,
                 }
+                watchdog.add_epoch("periodic");
 
-                previous_mode = state.clone();
+                self.robot_periodic().await;
+                watchdog.add_epoch("robot_periodic");
 
-                // Enforce a periodic loop delay
-                dt = last_loop.elapsed();
-                let elapsed = dt.as_secs_f64();
-                let left = (1. / 250. - elapsed).max(0.);
-                sleep(Duration::from_secs_f64(left)).await;
-                last_loop = Instant::now();
-            }
-        }));
-    }
-}
+                if let Some(scheduler) = self.scheduler() {
+                    scheduler.run();
+                    watchdog.add_epoch("scheduler");
+                }
 
-/*
-#[cfg(test)]
-mod tests {
-    use std::time::Duration;
-    use tokio::time::sleep;
+                previous_mode = Some(state);
 
-    #[test]
-    fn check_type_id() {
-        #[derive(Debug)]
-        enum TestEnum {
-            A,
-            B
-        }
+                watchdog.report().await;
 
-        let task = {
-            move |x: TestEnum| {
-                async move {
-                    println!("{:?}", x);
-                    sleep(Duration::from_secs(1)).await;
-                    println!("Task Finished");
-                }
+                // Wait out the rest of the period, or start right away after an overrun
+                sleep(self.period().saturating_sub(watchdog.elapsed())).await;
             }
-        };
 
-        let mut task_manager = super::TaskManager::new();
-        task_manager.run_task(task(TestEnum::A));
-        task_manager.abort_task(task(TestEnum::A));
-        println!("Should not print finished");
+            Ok(())
+        }))
     }
-}*/
+}
@@ -1,3 +1,5 @@
+use crate::drive::VisionMeasurement;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
 use crate::java;
 use bitvec::macros::internal::funty::Fundamental;
 use jni::objects::{GlobalRef, JObject, JValue};
@@ -5,7 +7,7 @@ use jni::signature::{Primitive, ReturnType};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::net::{Ipv4Addr, SocketAddr};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Clone)]
@@ -20,8 +22,59 @@ pub struct LimelightResults {
     pub botpose_orb_wpiblue: [f64; 6],
     pub botpose_wpiblue: [f64; 6],
     pub botpose_tagcount: i32,
+    /// Average distance from the camera to the visible tags, in meters
+    #[serde(default)]
+    pub botpose_avgdist: f64,
 
     pub stdev_mt1: [f64; 6],
+
+    /// Pipeline latency, in milliseconds
+    #[serde(default)]
+    pub tl: f64,
+    /// Capture latency, in milliseconds
+    #[serde(default)]
+    pub cl: f64,
+}
+
+impl LimelightResults {
+    /// The robot pose for a [`crate::drive::SwerveDrivePoseEstimator`], or `None` if no tags
+    /// are visible.
+    ///
+    /// `received` is when these results arrived, the latency reported by the camera is
+    /// subtracted from it. Uses MegaTag2 (`botpose_orb_wpiblue`) when `megatag2` is set, which
+    /// needs [`Limelight::update_robot_orientation`] every loop and doesn't measure heading.
+    ///
+    /// The standard deviations grow with the square of the distance to the tags and shrink with
+    /// the number of tags, starting from 0.5m and 0.5rad for a single tag 1m away.
+    pub fn vision_measurement(
+        &self,
+        received: Instant,
+        megatag2: bool,
+    ) -> Option<VisionMeasurement> {
+        if self.botpose_tagcount < 1 {
+            return None;
+        }
+
+        let botpose = if megatag2 {
+            self.botpose_orb_wpiblue
+        } else {
+            self.botpose_wpiblue
+        };
+        let pose = Pose2d::new(
+            Translation2d::from_meters(botpose[0], botpose[1]),
+            Rotation2d::from_degrees(botpose[5]),
+        );
+
+        let latency = Duration::from_secs_f64((self.tl + self.cl).max(0.) / 1000.);
+        let std_dev = 0.5 * self.botpose_avgdist.max(1.).powi(2) / self.botpose_tagcount as f64;
+        let heading = if megatag2 { f64::INFINITY } else { std_dev };
+
+        Some(VisionMeasurement {
+            pose,
+            timestamp: received.checked_sub(latency).unwrap_or(received),
+            std_devs: [std_dev, std_dev, heading],
+        })
+    }
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -36,8 +89,12 @@ impl core::default::Default for LimelightResults {
             botpose_orb_wpiblue: [0.0; 6],
             botpose_wpiblue: [0.0; 6],
             botpose_tagcount: 0,
+            botpose_avgdist: 0.0,
 
             stdev_mt1: [0.0; 6],
+
+            tl: 0.0,
+            cl: 0.0,
         }
     }
 }
@@ -144,6 +201,7 @@ impl Limelight {
 mod tests {
     use crate::limelight::LimelightResults;
     use crate::limelight::LimelightStatus;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn parse_results() {
@@ -180,5 +238,19 @@ mod tests {
         );
 
         assert_eq!(status.finalYaw, -0.2972398258824448);
+
+        let received = Instant::now();
+        let measurement = results.vision_measurement(received, false).unwrap();
+        assert_eq!(
+            received - measurement.timestamp,
+            Duration::from_secs_f64((64.05245971679688 + 11.595222473144531) / 1000.)
+        );
+        assert!((measurement.pose.rotation.degrees() - 85.39996216809105).abs() < 1e-9);
+        let std_dev = 0.5 * 1.442501610463488f64.powi(2) / 2.;
+        assert!((measurement.std_devs[0] - std_dev).abs() < 1e-9);
+        assert!(results.vision_measurement(received, true).unwrap().std_devs[2].is_infinite());
+        assert!(LimelightResults::default()
+            .vision_measurement(received, false)
+            .is_none());
     }
 }
@@ -0,0 +1,77 @@
+/// Where a profile says the mechanism should be at some time
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProfileState {
+    pub position: f64,
+    pub velocity: f64,
+    pub acceleration: f64,
+}
+
+impl ProfileState {
+    pub fn new(position: f64, velocity: f64) -> Self {
+        Self {
+            position,
+            velocity,
+            acceleration: 0.,
+        }
+    }
+
+    /// Stopped at `position`
+    pub fn at_rest(position: f64) -> Self {
+        Self::new(position, 0.)
+    }
+}
+
+impl From<f64> for ProfileState {
+    /// At rest
+    fn from(position: f64) -> Self {
+        ProfileState::at_rest(position)
+    }
+}
+
+/// A planned move, sampled by time in seconds since the plan was made.
+///
+/// Units are up to the caller, as long as position, velocity and the limits agree.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::motion_profile::{MotionProfile, ProfileState};
+/// use frcrs::s_curve::SCurveProfile;
+/// use frcrs::trapezoidal::TrapezoidalProfile;
+///
+/// fn halfway(profile: &impl MotionProfile) -> ProfileState {
+///     profile.state_at(profile.total_time() / 2.)
+/// }
+///
+/// let trapezoid = TrapezoidalProfile::new(0., 1., 1., 2.);
+/// let s_curve = SCurveProfile::new(0., 1., 1., 2., 10.);
+///
+/// assert!((halfway(&trapezoid).position - 0.5).abs() < 1e-9);
+/// assert!((halfway(&s_curve).position - 0.5).abs() < 1e-9);
+/// assert!(s_curve.total_time() > trapezoid.total_time());
+/// ```
+pub trait MotionProfile {
+    fn state_at(&self, t: f64) -> ProfileState;
+
+    /// Plan again from `current` to `goal` with the same limits, starting the clock over
+    fn replan(&mut self, current: ProfileState, goal: ProfileState);
+
+    /// Seconds from the start of the plan to reaching the goal
+    fn total_time(&self) -> f64;
+
+    fn position_at(&self, t: f64) -> f64 {
+        self.state_at(t).position
+    }
+
+    fn velocity_at(&self, t: f64) -> f64 {
+        self.state_at(t).velocity
+    }
+
+    fn acceleration_at(&self, t: f64) -> f64 {
+        self.state_at(t).acceleration
+    }
+
+    fn is_finished(&self, t: f64) -> bool {
+        t >= self.total_time()
+    }
+}
@@ -4,9 +4,13 @@ use jni::{
 };
 
 use crate::{
-    call::{call, call_static},
-    java,
+    call::{call, try_call_static},
+    error::FrcError,
+    hardware::Gyro,
+    try_java,
 };
+use uom::si::angle::degree;
+use uom::si::f64::Angle;
 
 pub struct NavX {
     instance: GlobalRef,
@@ -14,19 +18,23 @@ pub struct NavX {
 
 impl NavX {
     pub fn new() -> Self {
-        let instance = call_static!(
+        Self::try_new().unwrap()
+    }
+
+    /// Like [`NavX::new`], but returns an error instead of panicking
+    pub fn try_new() -> Result<Self, FrcError> {
+        let instance = try_call_static!(
             "frc/robot/Wrapper",
             "createAHRS",
             "()Lcom/kauailabs/navx/frc/AHRS;",
             &Vec::new(),
             ReturnType::Object
-        )
-        .l()
-        .unwrap();
+        )?
+        .l()?;
 
-        let instance = java().new_global_ref(instance).unwrap();
+        let instance = try_java()?.new_global_ref(instance)?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
 
     pub fn get_angle(&self) -> f64 {
@@ -55,3 +63,14 @@ impl NavX {
         .unwrap()
     }
 }
+
+impl Gyro for NavX {
+    fn heading(&mut self) -> Angle {
+        // getAngle is clockwise positive
+        Angle::new::<degree>(-self.get_angle())
+    }
+
+    fn reset(&mut self) {
+        self.reset_angle();
+    }
+}
@@ -0,0 +1,438 @@
+use crate::motion_profile::{MotionProfile, ProfileState};
+
+/// A PID controller with the extras real mechanisms need.
+///
+/// Integral limits, an output range, a filtered derivative, continuous input for angles and a
+/// setpoint tolerance are all optional, and off until set. The derivative is measured from the
+/// second call on, so the first output doesn't kick.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::pid::PIDController;
+/// use std::f64::consts::PI;
+///
+/// let mut heading = PIDController::new(4., 0., 0.1);
+/// heading.enable_continuous_input(-PI, PI);
+/// heading.set_output_range(-1., 1.);
+/// heading.set_tolerance(0.02, f64::INFINITY);
+///
+/// // turning from just under PI to just over -PI goes the short way round
+/// let output = heading.update(-PI + 0.1, PI - 0.1, 0.02);
+/// assert!(output > 0. && output <= 1.);
+/// assert!(!heading.at_setpoint());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PIDController {
+    k_p: f64,
+    k_i: f64,
+    k_d: f64,
+    integrator_range: (f64, f64),
+    i_zone: f64,
+    output_range: (f64, f64),
+    derivative_filter: f64,
+    continuous: Option<(f64, f64)>,
+    position_tolerance: f64,
+    velocity_tolerance: f64,
+
+    setpoint: f64,
+    error: f64,
+    error_derivative: f64,
+    integral: f64,
+    has_measurement: bool,
+}
+
+impl PIDController {
+    pub fn new(k_p: f64, k_i: f64, k_d: f64) -> Self {
+        Self {
+            k_p,
+            k_i,
+            k_d,
+            integrator_range: (f64::NEG_INFINITY, f64::INFINITY),
+            i_zone: f64::INFINITY,
+            output_range: (f64::NEG_INFINITY, f64::INFINITY),
+            derivative_filter: 0.,
+            continuous: None,
+            position_tolerance: 0.05,
+            velocity_tolerance: f64::INFINITY,
+            setpoint: 0.,
+            error: 0.,
+            error_derivative: 0.,
+            integral: 0.,
+            has_measurement: false,
+        }
+    }
+
+    pub fn set_pid(&mut self, k_p: f64, k_i: f64, k_d: f64) {
+        self.k_p = k_p;
+        self.k_i = k_i;
+        self.k_d = k_d;
+    }
+
+    pub fn k_p(&self) -> f64 {
+        self.k_p
+    }
+
+    pub fn k_i(&self) -> f64 {
+        self.k_i
+    }
+
+    pub fn k_d(&self) -> f64 {
+        self.k_d
+    }
+
+    /// Limit how much the integral term can add to the output, the bounds can be given in
+    /// either order
+    pub fn set_integrator_range(&mut self, min: f64, max: f64) {
+        self.integrator_range = (min.min(max), min.max(max));
+    }
+
+    /// Clear the integral whenever the error is larger than `i_zone`, so it only builds up
+    /// near the setpoint
+    pub fn set_i_zone(&mut self, i_zone: f64) {
+        self.i_zone = i_zone;
+    }
+
+    /// Clamp the output, the bounds can be given in either order
+    pub fn set_output_range(&mut self, min: f64, max: f64) {
+        self.output_range = (min.min(max), min.max(max));
+    }
+
+    /// Low pass filter the derivative with a time constant in seconds, zero turns it off
+    pub fn set_derivative_filter(&mut self, time_constant: f64) {
+        self.derivative_filter = time_constant;
+    }
+
+    /// Treat `min` and `max` as the same point, like -180° and 180°, so the error is always the
+    /// short way round
+    pub fn enable_continuous_input(&mut self, min: f64, max: f64) {
+        self.continuous = Some((min, max));
+    }
+
+    pub fn disable_continuous_input(&mut self) {
+        self.continuous = None;
+    }
+
+    pub fn is_continuous_input_enabled(&self) -> bool {
+        self.continuous.is_some()
+    }
+
+    /// How close the error and its rate of change have to be to zero for
+    /// [`PIDController::at_setpoint`]
+    pub fn set_tolerance(&mut self, position: f64, velocity: f64) {
+        self.position_tolerance = position;
+        self.velocity_tolerance = velocity;
+    }
+
+    pub fn set_setpoint(&mut self, setpoint: f64) {
+        self.setpoint = setpoint;
+    }
+
+    pub fn setpoint(&self) -> f64 {
+        self.setpoint
+    }
+
+    /// Whether the last measurement was within tolerance of the setpoint
+    pub fn at_setpoint(&self) -> bool {
+        self.has_measurement
+            && self.error.abs() < self.position_tolerance
+            && self.error_derivative.abs() < self.velocity_tolerance
+    }
+
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// Rate of change of the error, after filtering
+    pub fn error_derivative(&self) -> f64 {
+        self.error_derivative
+    }
+
+    /// Clear the integral and derivative, like after the mechanism has been disabled
+    pub fn reset(&mut self) {
+        self.error = 0.;
+        self.error_derivative = 0.;
+        self.integral = 0.;
+        self.has_measurement = false;
+    }
+
+    /// Move the setpoint and calculate the output
+    pub fn update(&mut self, setpoint: f64, measured: f64, dt: f64) -> f64 {
+        self.setpoint = setpoint;
+        self.calculate(measured, dt)
+    }
+
+    /// Calculate the output for a measurement taken `dt` seconds after the last one
+    pub fn calculate(&mut self, measured: f64, dt: f64) -> f64 {
+        let error = match self.continuous {
+            Some((min, max)) => {
+                let half = (max - min) / 2.;
+                wrap(self.setpoint - measured, -half, half)
+            }
+            None => self.setpoint - measured,
+        };
+
+        if self.has_measurement && dt > 0. {
+            let derivative = (error - self.error) / dt;
+            let alpha = dt / (self.derivative_filter + dt);
+            self.error_derivative += alpha * (derivative - self.error_derivative);
+        }
+        self.error = error;
+        self.has_measurement = true;
+
+        if error.abs() > self.i_zone {
+            self.integral = 0.;
+        } else if self.k_i != 0. {
+            // a negative gain swaps which bound limits the integral
+            let (min, max) = self.integrator_range;
+            let (a, b) = (min / self.k_i, max / self.k_i);
+            self.integral = (self.integral + error * dt).clamp(a.min(b), a.max(b));
+        }
+
+        let (min, max) = self.output_range;
+        let output = self.k_p * error + self.k_i * self.integral + self.k_d * self.error_derivative;
+        output.clamp(min, max)
+    }
+}
+
+/// Wrap `value` into the range `min..max`
+fn wrap(value: f64, min: f64, max: f64) -> f64 {
+    let range = max - min;
+    min + (value - min).rem_euclid(range)
+}
+
+/// A [`PIDController`] that follows a motion profile to its goal instead of jumping straight
+/// there.
+///
+/// Each call advances the profile and runs the PID towards the profile's setpoint. Add a
+/// feedforward from [`ProfiledPIDController::setpoint`] for the best tracking.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::pid::{PIDController, ProfiledPIDController};
+/// use frcrs::trapezoidal::TrapezoidalProfile;
+///
+/// // the limits come from the profile, its start and goal are replaced
+/// let mut elevator = ProfiledPIDController::new(
+///     PIDController::new(5., 0., 0.),
+///     TrapezoidalProfile::new(0., 0., 1., 2.),
+/// );
+///
+/// elevator.reset(0.);
+/// elevator.set_goal(1.);
+///
+/// // every loop
+/// let output = elevator.calculate(0., 0.02);
+/// let velocity = elevator.setpoint().velocity;
+/// ```
+pub struct ProfiledPIDController<P> {
+    pid: PIDController,
+    profile: P,
+    goal: ProfileState,
+    setpoint: ProfileState,
+    elapsed: f64,
+}
+
+impl<P: MotionProfile> ProfiledPIDController<P> {
+    pub fn new(pid: PIDController, profile: P) -> Self {
+        let start = profile.state_at(0.);
+
+        Self {
+            pid,
+            profile,
+            goal: start,
+            setpoint: start,
+            elapsed: 0.,
+        }
+    }
+
+    pub fn pid(&self) -> &PIDController {
+        &self.pid
+    }
+
+    /// Change gains, tolerances and the like
+    pub fn pid_mut(&mut self) -> &mut PIDController {
+        &mut self.pid
+    }
+
+    pub fn goal(&self) -> ProfileState {
+        self.goal
+    }
+
+    /// The state the profile wants right now
+    pub fn setpoint(&self) -> ProfileState {
+        self.setpoint
+    }
+
+    /// Replan from the current setpoint if the goal changed
+    pub fn set_goal(&mut self, goal: impl Into<ProfileState>) {
+        let goal = goal.into();
+        if goal != self.goal {
+            self.goal = goal;
+            self.replan(self.setpoint);
+        }
+    }
+
+    /// Whether the profile has finished and the PID is within tolerance
+    pub fn at_goal(&self) -> bool {
+        self.profile.is_finished(self.elapsed) && self.pid.at_setpoint()
+    }
+
+    /// Start the profile over from where the mechanism actually is, like after it was disabled
+    pub fn reset(&mut self, measured: impl Into<ProfileState>) {
+        let measured = measured.into();
+        self.pid.reset();
+        self.setpoint = measured;
+        self.replan(measured);
+    }
+
+    /// Advance the profile by `dt` seconds and calculate the output for a measurement
+    pub fn calculate(&mut self, measured: f64, dt: f64) -> f64 {
+        if let Some((min, max)) = self.pid.continuous {
+            // aim for the goal the short way round from where the mechanism is
+            let half = (max - min) / 2.;
+            let goal = measured + wrap(self.goal.position - measured, -half, half);
+            if (goal - self.goal.position).abs() > 1e-9 {
+                self.goal.position = goal;
+                let setpoint = measured + wrap(self.setpoint.position - measured, -half, half);
+                self.replan(ProfileState {
+                    position: setpoint,
+                    ..self.setpoint
+                });
+            }
+        }
+
+        self.elapsed += dt;
+        self.setpoint = self.profile.state_at(self.elapsed);
+        self.pid.update(self.setpoint.position, measured, dt)
+    }
+
+    fn replan(&mut self, from: ProfileState) {
+        self.profile.replan(from, self.goal);
+        self.elapsed = 0.;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trapezoidal::TrapezoidalProfile;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn continuous_input_wraps() {
+        let mut pid = PIDController::new(1., 0., 0.);
+        pid.enable_continuous_input(-PI, PI);
+
+        let output = pid.update(PI - 0.1, -PI + 0.1, 0.02);
+
+        assert!((output - -0.2).abs() < 1e-9);
+        assert!((pid.error() - -0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn integral_is_limited() {
+        let mut pid = PIDController::new(0., 1., 0.);
+        pid.set_integrator_range(-0.5, 0.5);
+
+        for _ in 0..100 {
+            pid.update(1., 0., 0.02);
+        }
+        assert!((pid.calculate(0., 0.02) - 0.5).abs() < 1e-9);
+
+        // outside the i-zone the integral is thrown away
+        pid.set_i_zone(0.5);
+        assert_eq!(pid.calculate(0., 0.02), 0.);
+        assert!((pid.calculate(0.9, 0.02) - 0.1 * 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn negative_integral_gain() {
+        let mut pid = PIDController::new(0., -1., 0.);
+        assert!((pid.update(1., 0., 0.5) - -0.5).abs() < 1e-9);
+
+        // bounds given backwards still limit the integral term
+        pid.set_integrator_range(0.2, -0.2);
+        pid.set_output_range(1., -1.);
+        for _ in 0..100 {
+            pid.update(1., 0., 0.5);
+        }
+        assert!((pid.calculate(0., 0.5) - -0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn output_and_derivative() {
+        let mut pid = PIDController::new(10., 0., 1.);
+        pid.set_output_range(-1., 1.);
+
+        // no derivative kick on the first call
+        assert_eq!(pid.update(1., 0., 0.02), 1.);
+        assert_eq!(pid.error_derivative(), 0.);
+
+        pid.calculate(0.5, 0.02);
+        assert!((pid.error_derivative() - -25.).abs() < 1e-9);
+
+        let mut filtered = PIDController::new(0., 0., 1.);
+        filtered.set_derivative_filter(0.02);
+        filtered.update(1., 0., 0.02);
+        filtered.calculate(0.5, 0.02);
+        assert!((filtered.error_derivative() - -12.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tolerance_and_reset() {
+        let mut pid = PIDController::new(1., 1., 0.);
+        pid.set_tolerance(0.1, 1.);
+        assert!(!pid.at_setpoint());
+
+        pid.update(1., 0.95, 0.02);
+        assert!(pid.at_setpoint());
+
+        pid.update(1., 0.5, 0.02);
+        assert!(!pid.at_setpoint());
+
+        pid.reset();
+        assert!(!pid.at_setpoint());
+        assert_eq!(pid.calculate(1., 0.02), 0.);
+    }
+
+    #[test]
+    fn profiled_reaches_goal() {
+        let mut controller = ProfiledPIDController::new(
+            PIDController::new(5., 0., 0.),
+            TrapezoidalProfile::new(0., 0., 1., 2.),
+        );
+        controller.pid_mut().set_tolerance(0.01, 0.1);
+        controller.reset(0.);
+        controller.set_goal(1.);
+
+        // a mechanism that moves at the commanded velocity
+        let dt = 0.02;
+        let mut position = 0.;
+        for _ in 0..100 {
+            let output = controller.calculate(position, dt);
+            position += (controller.setpoint().velocity + output) * dt;
+
+            assert!(controller.setpoint().velocity <= 1. + 1e-9);
+        }
+
+        assert!((position - 1.).abs() < 0.01);
+        assert!(controller.at_goal());
+    }
+
+    #[test]
+    fn profiled_continuous_goes_short_way() {
+        let mut pid = PIDController::new(5., 0., 0.);
+        pid.enable_continuous_input(-PI, PI);
+        let mut controller =
+            ProfiledPIDController::new(pid, TrapezoidalProfile::new(0., 0., 1., 2.));
+        controller.reset(PI - 0.1);
+        controller.set_goal(-PI + 0.1);
+
+        controller.calculate(PI - 0.1, 0.02);
+
+        assert!(controller.setpoint().velocity > 0.);
+        assert!((controller.goal().position - (PI + 0.1)).abs() < 1e-9);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::call::{call, create};
-use crate::java;
+use crate::error::FrcError;
+use crate::hardware::Gyro;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
 use nalgebra::{Rotation3, Vector3};
+use uom::si::angle::revolution;
+use uom::si::f64::Angle;
 
 pub struct CanAndGyro {
     instance: GlobalRef,
@@ -10,15 +13,19 @@ pub struct CanAndGyro {
 
 impl CanAndGyro {
     pub fn new(id: i32) -> Self {
-        let instance = create!(
+        Self::try_new(id).unwrap()
+    }
+
+    /// Like [`CanAndGyro::new`], but returns an error instead of panicking
+    pub fn try_new(id: i32) -> Result<Self, FrcError> {
+        let instance = try_create!(
             "com/reduxrobotics/sensors/canandgyro/Canandgyro",
             "(I)V",
             &[JValue::Int(id).as_jni()]
-        );
+        )
+        .map_err(FrcError::device("Canandgyro", id))?;
 
-        Self {
-            instance: java().new_global_ref(instance).unwrap(),
-        }
+        Ok(Self { instance })
     }
 
     pub fn get_angle(&self) -> f64 {
@@ -33,4 +40,28 @@ impl CanAndGyro {
         .d()
         .unwrap()
     }
+
+    /// Set the current yaw, in rotations
+    pub fn set_yaw(&self, yaw: f64) {
+        call!(
+            self.instance.as_obj(),
+            "com/reduxrobotics/sensors/canandgyro/Canandgyro",
+            "setYaw",
+            "(D)Z",
+            &[JValue::Double(yaw).as_jni()],
+            ReturnType::Primitive(Primitive::Boolean)
+        )
+        .z()
+        .unwrap();
+    }
+}
+
+impl Gyro for CanAndGyro {
+    fn heading(&mut self) -> Angle {
+        Angle::new::<revolution>(self.get_angle())
+    }
+
+    fn reset(&mut self) {
+        self.set_yaw(0.);
+    }
 }
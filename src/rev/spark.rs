@@ -1,11 +1,15 @@
-use crate::call::{call, create, once};
+use crate::call::{call, once, try_call, try_create};
+use crate::error::{check, FrcError};
+use crate::hardware::{Encoder, MotorController};
 use crate::java;
 use crate::rev::{ControlType, IdleMode, MotorType};
+use crate::try_java;
 use jni::objects::{GlobalRef, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
 use once_cell::sync::OnceCell;
 use uom::si::angle;
 use uom::si::angle::revolution;
+use uom::si::angular_velocity::revolution_per_minute;
 use uom::si::f64::*;
 
 pub struct Spark {
@@ -60,16 +64,20 @@ impl Spark {
     }
 
     pub fn get_current(&self) -> f64 {
-        call!(
+        self.try_get_current().unwrap()
+    }
+
+    /// Like [`Spark::get_current`], but returns an error instead of panicking
+    pub fn try_get_current(&self) -> Result<f64, FrcError> {
+        Ok(try_call!(
             self.instance.as_obj(),
             "com/revrobotics/CANSparkBase",
             "getOutputCurrent",
             "()D",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        )?
+        .d()?)
     }
 
     pub(crate) fn get_controller(&mut self) -> &JObject {
@@ -97,59 +105,60 @@ impl Spark {
         &self.pid.as_ref().unwrap()
     }
 
-    fn get_encoder(&mut self) -> &JObject {
-        if self.encoder.is_some() {
-            return &self.encoder.as_ref().unwrap();
-        }
+    fn try_get_encoder(&mut self) -> Result<&JObject, FrcError> {
+        if self.encoder.is_none() {
+            let encoder = try_call!(
+                self.instance.as_obj(),
+                "com/revrobotics/CANSparkBase",
+                "getEncoder",
+                "()Lcom/revrobotics/RelativeEncoder;",
+                &Vec::new(),
+                ReturnType::Object
+            )?
+            .l()?;
 
-        self.encoder = Some(
-            java()
-                .new_global_ref(
-                    call!(
-                        self.instance.as_obj(),
-                        "com/revrobotics/CANSparkBase",
-                        "getEncoder",
-                        "()Lcom/revrobotics/RelativeEncoder;",
-                        &Vec::new(),
-                        ReturnType::Object
-                    )
-                    .l()
-                    .unwrap(),
-                )
-                .unwrap(),
-        );
+            self.encoder = Some(try_java()?.new_global_ref(encoder)?);
+        }
 
-        &self.encoder.as_ref().unwrap()
+        Ok(self.encoder.as_ref().unwrap().as_obj())
     }
 
     pub fn get_velocity(&mut self) -> f64 {
-        let encoder = self.get_encoder();
-        call!(
+        self.try_get_velocity().unwrap()
+    }
+
+    /// Like [`Spark::get_velocity`], but returns an error instead of panicking
+    pub fn try_get_velocity(&mut self) -> Result<f64, FrcError> {
+        let encoder = self.try_get_encoder()?;
+        Ok(try_call!(
             encoder,
             "com/revrobotics/RelativeEncoder",
             "getVelocity",
             "()D",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap()
+        )?
+        .d()?)
     }
 
     pub fn get_position(&mut self) -> Angle {
-        let encoder = self.get_encoder();
-        let rots: f64 = call!(
+        self.try_get_position().unwrap()
+    }
+
+    /// Like [`Spark::get_position`], but returns an error instead of panicking
+    pub fn try_get_position(&mut self) -> Result<Angle, FrcError> {
+        let encoder = self.try_get_encoder()?;
+        let rots: f64 = try_call!(
             encoder,
             "com/revrobotics/RelativeEncoder",
             "getPosition",
             "()D",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Double)
-        )
-        .d()
-        .unwrap();
+        )?
+        .d()?;
 
-        Angle::new::<revolution>(rots)
+        Ok(Angle::new::<revolution>(rots))
     }
 
     pub(crate) fn instance(&self) -> &JObject {
@@ -157,34 +166,37 @@ impl Spark {
     }
 
     pub fn new(can_id: i32, motor_type: MotorType) -> Self {
-        let mut jvm = java();
+        Self::try_new(can_id, motor_type).unwrap()
+    }
 
-        let motortype = jvm
-            .call_static_method(
-                "frc/robot/Wrapper",
-                motor_type.as_str(),
-                "()Lcom/revrobotics/CANSparkLowLevel$MotorType;",
-                &Vec::new(),
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+    /// Like [`Spark::new`], but returns an error instead of panicking
+    pub fn try_new(can_id: i32, motor_type: MotorType) -> Result<Self, FrcError> {
+        let mut jvm = try_java()?;
+
+        let motortype = jvm.call_static_method(
+            "frc/robot/Wrapper",
+            motor_type.as_str(),
+            "()Lcom/revrobotics/CANSparkLowLevel$MotorType;",
+            &Vec::new(),
+        );
+        let motortype = check(&mut jvm, motortype)?.l()?;
 
-        let instance = create!(
+        let instance = try_create!(
             "com/revrobotics/CANSparkMax",
             "(ILcom/revrobotics/CANSparkMaxLowLevel$MotorType;)V",
             &[
                 JValue::Int(can_id).as_jni(),
-                JValue::Object(&JObject::from_raw(motortype.into_raw())).as_jni()
+                JValue::Object(&motortype).as_jni()
             ]
-        );
+        )
+        .map_err(FrcError::device("SparkMax", can_id))?;
 
-        Self {
+        Ok(Self {
             can_id,
             instance,
             encoder: None,
             pid: None,
-        }
+        })
     }
 
     pub fn flex(can_id: i32) -> Self {
@@ -195,16 +207,20 @@ impl Spark {
     ///
     /// `amount` is from -1, 1
     pub fn set(&self, amount: f64) {
-        call!(
+        self.try_set(amount).unwrap()
+    }
+
+    /// Like [`Spark::set`], but returns an error instead of panicking
+    pub fn try_set(&self, amount: f64) -> Result<(), FrcError> {
+        Ok(try_call!(
             self.instance.as_obj(),
             "com/revrobotics/CANSparkBase",
             "set",
             "(D)V",
             &[JValue::Double(amount).as_jni()],
             ReturnType::Primitive(Primitive::Void)
-        )
-        .v()
-        .unwrap();
+        )?
+        .v()?)
     }
 
     pub fn set_idle_mode(&self, idle_mode: IdleMode) {
@@ -249,16 +265,20 @@ impl Spark {
 
     /// Stop the motor
     pub fn stop(&self) {
-        call!(
+        self.try_stop().unwrap()
+    }
+
+    /// Like [`Spark::stop`], but returns an error instead of panicking
+    pub fn try_stop(&self) -> Result<(), FrcError> {
+        Ok(try_call!(
             self.instance.as_obj(),
             "com/revrobotics/CANSparkBase",
             "stopMotor",
             "()V",
             &Vec::new(),
             ReturnType::Primitive(Primitive::Void)
-        )
-        .v()
-        .unwrap();
+        )?
+        .v()?)
     }
 
     pub fn set_position(&mut self, position: Angle) {
@@ -322,3 +342,24 @@ impl Spark {
         .unwrap();
     }
 }
+
+impl MotorController for Spark {
+    fn set_percent(&mut self, percent: f64) {
+        self.set(percent);
+    }
+
+    fn stop(&mut self) {
+        Spark::stop(self);
+    }
+}
+
+impl Encoder for Spark {
+    fn position(&mut self) -> Angle {
+        self.get_position()
+    }
+
+    fn velocity(&mut self) -> AngularVelocity {
+        // the encoder reports RPM unless a conversion factor is configured
+        AngularVelocity::new::<revolution_per_minute>(self.get_velocity())
+    }
+}
@@ -0,0 +1,491 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::{pending, poll_fn, Future};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::task::yield_now;
+use tokio::time::sleep;
+
+use super::{Command, SubsystemId};
+
+/// How often conditions are checked, matching the robot loop
+const POLL_PERIOD: Duration = Duration::from_millis(4);
+
+type ActionFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ComposeError {
+    #[error("{0:?} is required by actions that would run at the same time")]
+    SharedRequirement(SubsystemId),
+}
+
+/// A restartable async routine, along with the subsystems it uses.
+///
+/// Actions can be awaited directly with [`Action::run`], or scheduled on a
+/// [`super::CommandScheduler`], which keeps actions with shared requirements from running at the
+/// same time. Ending an action early drops its future, so cleanup belongs in a `Drop` impl.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use frcrs::robot::{deadline, sequence, Action};
+///
+/// # fn example(drive: Action, intake: Action, shoot: Action, empty: fn() -> bool)
+/// #     -> Result<Action, frcrs::robot::ComposeError> {
+/// // drive a path while intaking, then shoot until empty or 2 seconds pass
+/// let auto = sequence([
+///     deadline(drive, [intake])?,
+///     shoot.until(empty).with_timeout(Duration::from_secs(2)),
+/// ]);
+/// # Ok(auto)
+/// # }
+/// ```
+pub struct Action {
+    requirements: Vec<SubsystemId>,
+    start: Box<dyn FnMut() -> ActionFuture>,
+    running: Option<ActionFuture>,
+}
+
+impl Action {
+    /// An action that runs the future returned by `start` each time it's started
+    pub fn new<F, Fut>(requirements: impl IntoIterator<Item = SubsystemId>, mut start: F) -> Self
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        Self {
+            requirements: merge(requirements),
+            start: Box::new(move || Box::pin(start())),
+            running: None,
+        }
+    }
+
+    /// Finishes immediately
+    pub fn none() -> Self {
+        Self::new([], || async {})
+    }
+
+    pub fn wait(duration: Duration) -> Self {
+        Self::new([], move || async move { sleep(duration).await })
+    }
+
+    /// Finishes once `condition` returns true
+    pub fn wait_until(condition: impl FnMut() -> bool + 'static) -> Self {
+        let condition = Rc::new(RefCell::new(condition));
+
+        Self::new([], move || {
+            let condition = condition.clone();
+            async move {
+                while !(condition.borrow_mut())() {
+                    sleep(POLL_PERIOD).await;
+                }
+            }
+        })
+    }
+
+    pub fn requirements(&self) -> &[SubsystemId] {
+        &self.requirements
+    }
+
+    /// Start the action
+    pub fn run(&mut self) -> impl Future<Output = ()> {
+        (self.start)()
+    }
+
+    /// End the action if it takes longer than `timeout`
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.until_action(Action::wait(timeout))
+    }
+
+    /// End the action once `condition` returns true
+    pub fn until(self, condition: impl FnMut() -> bool + 'static) -> Self {
+        self.until_action(Action::wait_until(condition))
+    }
+
+    /// Only run the action if `condition` is true when it starts
+    pub fn only_if(mut self, mut condition: impl FnMut() -> bool + 'static) -> Self {
+        let requirements = std::mem::take(&mut self.requirements);
+
+        Self {
+            requirements,
+            start: Box::new(move || {
+                if condition() {
+                    (self.start)()
+                } else {
+                    Box::pin(async {})
+                }
+            }),
+            running: None,
+        }
+    }
+
+    /// Restart the action every time it finishes, forever
+    pub fn repeat(self) -> Self {
+        let requirements = self.requirements.clone();
+        let action = Rc::new(RefCell::new(self));
+
+        Self::new(requirements, move || {
+            let action = action.clone();
+            async move {
+                loop {
+                    let run = action.borrow_mut().run();
+                    run.await;
+                    // don't spin if the action finishes immediately
+                    yield_now().await;
+                }
+            }
+        })
+    }
+
+    /// Run `next` after this action finishes
+    pub fn and_then(self, next: Action) -> Self {
+        sequence([self, next])
+    }
+
+    fn until_action(self, condition: Action) -> Self {
+        race([self, condition]).expect("conditions don't have requirements")
+    }
+}
+
+/// Run actions one after another
+pub fn sequence(actions: impl IntoIterator<Item = Action>) -> Action {
+    let actions: Vec<Action> = actions.into_iter().collect();
+    let requirements = merge(actions.iter().flat_map(|a| a.requirements.clone()));
+    let actions = Rc::new(RefCell::new(actions));
+
+    Action::new(requirements, move || {
+        let actions = actions.clone();
+        async move {
+            let count = actions.borrow().len();
+            for index in 0..count {
+                let run = actions.borrow_mut()[index].run();
+                run.await;
+            }
+        }
+    })
+}
+
+/// Run actions at the same time, finishing when all of them have
+pub fn parallel(actions: impl IntoIterator<Item = Action>) -> Result<Action, ComposeError> {
+    let mut actions = disjoint(actions)?;
+    let requirements = merge(actions.iter().flat_map(|a| a.requirements.clone()));
+
+    Ok(Action::new(requirements, move || {
+        join_all(actions.iter_mut().map(|a| (a.start)()).collect())
+    }))
+}
+
+/// Run actions at the same time, finishing when any of them does
+pub fn race(actions: impl IntoIterator<Item = Action>) -> Result<Action, ComposeError> {
+    let mut actions = disjoint(actions)?;
+    let requirements = merge(actions.iter().flat_map(|a| a.requirements.clone()));
+
+    Ok(Action::new(requirements, move || {
+        race_all(actions.iter_mut().map(|a| (a.start)()).collect())
+    }))
+}
+
+/// Run actions alongside `deadline`, finishing when `deadline` does
+pub fn deadline(
+    deadline: Action,
+    others: impl IntoIterator<Item = Action>,
+) -> Result<Action, ComposeError> {
+    let mut actions = disjoint(std::iter::once(deadline).chain(others))?;
+    let requirements = merge(actions.iter().flat_map(|a| a.requirements.clone()));
+
+    Ok(Action::new(requirements, move || {
+        let mut runs = actions.iter_mut().map(|a| (a.start)());
+        let deadline = runs.next().unwrap();
+        let others = join_all(runs.collect());
+
+        race_all(vec![
+            deadline,
+            Box::pin(async move {
+                others.await;
+                pending::<()>().await;
+            }),
+        ])
+    }))
+}
+
+/// Pick an action when started based on the value of `selector`.
+///
+/// Finishes immediately if there's no action for the value.
+pub fn select<K: Eq + Hash + 'static>(
+    mut selector: impl FnMut() -> K + 'static,
+    options: impl IntoIterator<Item = (K, Action)>,
+) -> Action {
+    let mut options: HashMap<K, Action> = options.into_iter().collect();
+    let requirements = merge(options.values().flat_map(|a| a.requirements.clone()));
+
+    Action::new(requirements, move || {
+        let run = options.get_mut(&selector()).map(|a| (a.start)());
+        async move {
+            if let Some(run) = run {
+                run.await;
+            }
+        }
+    })
+}
+
+impl Command for Action {
+    fn initialize(&mut self) {
+        self.running = Some((self.start)());
+    }
+
+    fn execute(&mut self) {
+        // the scheduler polls every loop, so wakeups aren't needed
+        let mut context = Context::from_waker(Waker::noop());
+
+        if let Some(running) = &mut self.running {
+            if running.as_mut().poll(&mut context).is_ready() {
+                self.running = None;
+            }
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.running.is_none()
+    }
+
+    fn end(&mut self, _interrupted: bool) {
+        self.running = None;
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        self.requirements.clone()
+    }
+}
+
+fn merge(requirements: impl IntoIterator<Item = SubsystemId>) -> Vec<SubsystemId> {
+    let mut merged = Vec::new();
+    for requirement in requirements {
+        if !merged.contains(&requirement) {
+            merged.push(requirement);
+        }
+    }
+    merged
+}
+
+fn disjoint(actions: impl IntoIterator<Item = Action>) -> Result<Vec<Action>, ComposeError> {
+    let actions: Vec<Action> = actions.into_iter().collect();
+
+    let mut seen = Vec::new();
+    for requirement in actions.iter().flat_map(|a| a.requirements.iter()) {
+        if seen.contains(requirement) {
+            return Err(ComposeError::SharedRequirement(*requirement));
+        }
+        seen.push(*requirement);
+    }
+
+    Ok(actions)
+}
+
+fn join_all(runs: Vec<ActionFuture>) -> ActionFuture {
+    let mut runs: Vec<Option<ActionFuture>> = runs.into_iter().map(Some).collect();
+
+    Box::pin(poll_fn(move |cx| {
+        for slot in runs.iter_mut() {
+            if let Some(run) = slot {
+                if run.as_mut().poll(cx).is_ready() {
+                    *slot = None;
+                }
+            }
+        }
+
+        if runs.iter().all(Option::is_none) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }))
+}
+
+fn race_all(mut runs: Vec<ActionFuture>) -> ActionFuture {
+    Box::pin(poll_fn(move |cx| {
+        if runs.is_empty() {
+            return Poll::Ready(());
+        }
+
+        for run in runs.iter_mut() {
+            if run.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(());
+            }
+        }
+
+        Poll::Pending
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver_station::{set_source, SimDriverStation};
+    use crate::input::RobotMode;
+    use crate::robot::CommandScheduler;
+    use std::cell::Cell;
+    use std::time::Instant;
+
+    type Log = Rc<RefCell<Vec<&'static str>>>;
+
+    fn id(n: usize) -> SubsystemId {
+        SubsystemId(n)
+    }
+
+    fn step(log: &Log, name: &'static str, duration: Duration) -> Action {
+        let log = log.clone();
+        Action::new([], move || {
+            let log = log.clone();
+            async move {
+                log.borrow_mut().push(name);
+                sleep(duration).await;
+                log.borrow_mut().push("end");
+            }
+        })
+    }
+
+    fn block_on(future: impl Future<Output = ()>) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future);
+    }
+
+    #[test]
+    fn sequence_runs_in_order() {
+        let log = Log::default();
+        let mut auto = sequence([
+            step(&log, "a", Duration::from_millis(5)),
+            step(&log, "b", Duration::ZERO),
+        ]);
+
+        block_on(auto.run());
+        assert_eq!(*log.borrow(), vec!["a", "end", "b", "end"]);
+    }
+
+    #[test]
+    fn parallel_waits_for_all() {
+        let start = Instant::now();
+        let mut both = parallel([
+            Action::wait(Duration::from_millis(10)),
+            Action::wait(Duration::from_millis(30)),
+        ])
+        .unwrap();
+
+        block_on(both.run());
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn race_and_timeout_stop_early() {
+        let log = Log::default();
+        let mut raced = race([
+            step(&log, "slow", Duration::from_secs(10)),
+            Action::wait(Duration::from_millis(10)),
+        ])
+        .unwrap()
+        .and_then(step(&log, "next", Duration::ZERO).with_timeout(Duration::from_secs(10)));
+
+        block_on(raced.run());
+        assert_eq!(*log.borrow(), vec!["slow", "next", "end"]);
+    }
+
+    #[test]
+    fn deadline_cancels_others() {
+        let log = Log::default();
+        let mut group = deadline(
+            Action::wait(Duration::from_millis(10)),
+            [step(&log, "intake", Duration::from_secs(10))],
+        )
+        .unwrap();
+
+        let start = Instant::now();
+        block_on(group.run());
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(*log.borrow(), vec!["intake"]);
+    }
+
+    #[test]
+    fn until_and_repeat() {
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        let checked = count.clone();
+
+        let mut repeated = Action::new([], move || {
+            counter.set(counter.get() + 1);
+            async {}
+        })
+        .repeat()
+        .until(move || checked.get() >= 3);
+
+        block_on(repeated.run());
+        assert!(count.get() >= 3);
+    }
+
+    #[test]
+    fn only_if_and_select() {
+        let log = Log::default();
+        let mut skipped = step(&log, "skipped", Duration::ZERO).only_if(|| false);
+        let mut chosen = select(
+            || 2,
+            [
+                (1, step(&log, "one", Duration::ZERO)),
+                (2, step(&log, "two", Duration::ZERO)),
+            ],
+        );
+
+        block_on(async {
+            skipped.run().await;
+            chosen.run().await;
+        });
+        assert_eq!(*log.borrow(), vec!["two", "end"]);
+    }
+
+    #[test]
+    fn rejects_shared_requirements() {
+        let drive = || Action::new([id(1)], || async {});
+        let intake = Action::new([id(2)], || async {});
+
+        assert!(parallel([drive(), intake]).is_ok());
+        assert_eq!(
+            race([drive(), drive()]).err(),
+            Some(ComposeError::SharedRequirement(id(1)))
+        );
+
+        let both = sequence([drive(), drive()]);
+        assert_eq!(both.requirements(), &[id(1)]);
+    }
+
+    #[test]
+    fn scheduled_as_command() {
+        let ds = SimDriverStation::new();
+        ds.set_mode(RobotMode::Auto);
+        set_source(ds);
+
+        let log = Log::default();
+        let mut scheduler = CommandScheduler::new();
+
+        block_on(async {
+            let auto = sequence([
+                step(&log, "a", Duration::from_millis(5)),
+                step(&log, "b", Duration::ZERO),
+            ]);
+            let command = scheduler.schedule(auto).unwrap();
+
+            let start = Instant::now();
+            while scheduler.is_scheduled(command) && start.elapsed() < Duration::from_secs(1) {
+                scheduler.run();
+                sleep(POLL_PERIOD).await;
+            }
+        });
+
+        assert_eq!(*log.borrow(), vec!["a", "end", "b", "end"]);
+    }
+}
@@ -0,0 +1,78 @@
+//! Command based robot structure.
+//!
+//! Mechanisms are [`Subsystem`]s, shared as `Rc<RefCell<_>>` between the robot and the commands
+//! that use them. A [`Command`] lists the subsystems it requires, and the [`CommandScheduler`]
+//! makes sure only one command uses a subsystem at a time. Return the scheduler from
+//! [`crate::Robot::scheduler`] to have it run every loop.
+//!
+//! Routines can also be written as async [`Action`]s and composed with [`sequence`],
+//! [`parallel`], [`race`], [`deadline`] and [`select`].
+
+mod action;
+mod scheduler;
+
+pub use action::*;
+pub use scheduler::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A mechanism that commands can require.
+pub trait Subsystem {
+    /// Called every loop while the scheduler runs, before any commands
+    fn periodic(&mut self) {}
+}
+
+/// Identifies a subsystem in a command's requirements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubsystemId(usize);
+
+impl SubsystemId {
+    pub fn of<S: ?Sized>(subsystem: &Rc<RefCell<S>>) -> Self {
+        Self(Rc::as_ptr(subsystem) as *const () as usize)
+    }
+}
+
+/// What happens when a command is scheduled that needs a subsystem this command is using.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum InterruptionBehavior {
+    /// End this command and run the new one
+    #[default]
+    CancelSelf,
+    /// Keep running and refuse the new command
+    CancelIncoming,
+}
+
+/// An action the robot performs over one or more loops.
+pub trait Command {
+    /// Called once when the command is scheduled
+    fn initialize(&mut self) {}
+
+    /// Called every loop while the command is scheduled
+    fn execute(&mut self) {}
+
+    /// Checked after every execute, the command ends once this returns true
+    fn is_finished(&mut self) -> bool {
+        false
+    }
+
+    /// Called once when the command ends, `interrupted` is true if it was cancelled
+    fn end(&mut self, _interrupted: bool) {}
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        Vec::new()
+    }
+
+    fn interruption_behavior(&self) -> InterruptionBehavior {
+        InterruptionBehavior::CancelSelf
+    }
+
+    /// Whether to keep running while the robot is disabled
+    fn runs_when_disabled(&self) -> bool {
+        false
+    }
+
+    fn name(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+}
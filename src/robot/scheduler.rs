@@ -0,0 +1,509 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use thiserror::Error;
+
+use crate::input::RobotState;
+
+use super::{Command, InterruptionBehavior, Subsystem, SubsystemId};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ScheduleError {
+    #[error("{incoming} can't interrupt {running}")]
+    Conflict { incoming: String, running: String },
+
+    #[error("default command {0} doesn't require its subsystem")]
+    MissingRequirement(String),
+}
+
+/// Handle to a scheduled command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CommandId(u64);
+
+struct Scheduled {
+    id: CommandId,
+    command: Box<dyn Command>,
+    requirements: Vec<SubsystemId>,
+    /// The subsystem this is the default command of
+    default_of: Option<SubsystemId>,
+}
+
+/// Runs commands, resolving conflicts between commands that require the same subsystem.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use frcrs::robot::{Command, CommandScheduler, Subsystem, SubsystemId};
+///
+/// struct Intake;
+/// impl Subsystem for Intake {}
+///
+/// struct RunIntake(Rc<RefCell<Intake>>);
+/// impl Command for RunIntake {
+///     fn requirements(&self) -> Vec<SubsystemId> {
+///         vec![SubsystemId::of(&self.0)]
+///     }
+/// }
+///
+/// let intake = Rc::new(RefCell::new(Intake));
+/// let mut scheduler = CommandScheduler::new();
+/// scheduler.register(intake.clone());
+/// scheduler.schedule(RunIntake(intake)).unwrap();
+/// scheduler.run();
+/// ```
+#[derive(Default)]
+pub struct CommandScheduler {
+    subsystems: Vec<(SubsystemId, Rc<RefCell<dyn Subsystem>>)>,
+    default_commands: HashMap<SubsystemId, Box<dyn Command>>,
+    scheduled: Vec<Scheduled>,
+    next_id: u64,
+}
+
+impl CommandScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call the subsystem's periodic every loop
+    pub fn register<S: Subsystem + 'static>(&mut self, subsystem: Rc<RefCell<S>>) -> SubsystemId {
+        let id = SubsystemId::of(&subsystem);
+        if !self
+            .subsystems
+            .iter()
+            .any(|(registered, _)| *registered == id)
+        {
+            self.subsystems.push((id, subsystem));
+        }
+        id
+    }
+
+    /// Run `command` whenever nothing else requires `subsystem`.
+    ///
+    /// Replaces the previous default command, cancelling it if it is running.
+    pub fn set_default_command(
+        &mut self,
+        subsystem: SubsystemId,
+        command: impl Command + 'static,
+    ) -> Result<(), ScheduleError> {
+        if !command.requirements().contains(&subsystem) {
+            return Err(ScheduleError::MissingRequirement(command.name()));
+        }
+
+        if let Some(index) = self
+            .scheduled
+            .iter()
+            .position(|s| s.default_of == Some(subsystem))
+        {
+            let mut previous = self.scheduled.remove(index);
+            previous.command.end(true);
+        }
+
+        self.default_commands.insert(subsystem, Box::new(command));
+        Ok(())
+    }
+
+    pub fn remove_default_command(&mut self, subsystem: SubsystemId) {
+        self.default_commands.remove(&subsystem);
+        if let Some(scheduled) = self
+            .scheduled
+            .iter_mut()
+            .find(|s| s.default_of == Some(subsystem))
+        {
+            scheduled.default_of = None;
+        }
+    }
+
+    /// Start a command, cancelling any commands using the same subsystems.
+    ///
+    /// Fails if one of those commands can't be interrupted.
+    pub fn schedule(
+        &mut self,
+        command: impl Command + 'static,
+    ) -> Result<CommandId, ScheduleError> {
+        self.start(Box::new(command), None)
+            .map_err(|(_, error)| error)
+    }
+
+    /// End a command early, does nothing if it already ended
+    pub fn cancel(&mut self, id: CommandId) {
+        if let Some(index) = self.scheduled.iter().position(|s| s.id == id) {
+            let scheduled = self.scheduled.remove(index);
+            self.finish(scheduled, true);
+        }
+    }
+
+    pub fn cancel_all(&mut self) {
+        for scheduled in std::mem::take(&mut self.scheduled) {
+            self.finish(scheduled, true);
+        }
+    }
+
+    pub fn is_scheduled(&self, id: CommandId) -> bool {
+        self.scheduled.iter().any(|s| s.id == id)
+    }
+
+    /// The command currently using a subsystem
+    pub fn requiring(&self, subsystem: SubsystemId) -> Option<CommandId> {
+        self.scheduled
+            .iter()
+            .find(|s| s.requirements.contains(&subsystem))
+            .map(|s| s.id)
+    }
+
+    /// Run one loop: subsystem periodics, then every scheduled command, then default commands
+    /// for subsystems left idle.
+    ///
+    /// Commands that don't run while disabled are cancelled when the robot is disabled.
+    pub fn run(&mut self) {
+        for (_, subsystem) in &self.subsystems {
+            subsystem.borrow_mut().periodic();
+        }
+
+        let enabled = RobotState::get().enabled();
+
+        let mut index = 0;
+        while index < self.scheduled.len() {
+            let command = &mut self.scheduled[index].command;
+
+            if !enabled && !command.runs_when_disabled() {
+                let scheduled = self.scheduled.remove(index);
+                self.finish(scheduled, true);
+                continue;
+            }
+
+            command.execute();
+            if command.is_finished() {
+                let scheduled = self.scheduled.remove(index);
+                self.finish(scheduled, false);
+                continue;
+            }
+
+            index += 1;
+        }
+
+        self.start_default_commands(enabled);
+    }
+
+    fn start_default_commands(&mut self, enabled: bool) {
+        let subsystems: Vec<SubsystemId> = self.default_commands.keys().copied().collect();
+
+        for subsystem in subsystems {
+            let idle = self.default_commands[&subsystem]
+                .requirements()
+                .iter()
+                .all(|&requirement| self.requiring(requirement).is_none());
+            let allowed = enabled || self.default_commands[&subsystem].runs_when_disabled();
+
+            if idle && allowed {
+                let command = self.default_commands.remove(&subsystem).unwrap();
+                if let Err((command, _)) = self.start(command, Some(subsystem)) {
+                    self.default_commands.insert(subsystem, command);
+                }
+            }
+        }
+    }
+
+    fn start(
+        &mut self,
+        mut command: Box<dyn Command>,
+        default_of: Option<SubsystemId>,
+    ) -> Result<CommandId, (Box<dyn Command>, ScheduleError)> {
+        let requirements = command.requirements();
+
+        let conflicts: Vec<usize> = self
+            .scheduled
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.requirements.iter().any(|r| requirements.contains(r)))
+            .map(|(index, _)| index)
+            .collect();
+
+        if let Some(&blocking) = conflicts.iter().find(|&&index| {
+            self.scheduled[index].command.interruption_behavior()
+                == InterruptionBehavior::CancelIncoming
+        }) {
+            let error = ScheduleError::Conflict {
+                incoming: command.name(),
+                running: self.scheduled[blocking].command.name(),
+            };
+            return Err((command, error));
+        }
+
+        for index in conflicts.into_iter().rev() {
+            let scheduled = self.scheduled.remove(index);
+            self.finish(scheduled, true);
+        }
+
+        command.initialize();
+
+        let id = CommandId(self.next_id);
+        self.next_id += 1;
+        self.scheduled.push(Scheduled {
+            id,
+            command,
+            requirements,
+            default_of,
+        });
+
+        Ok(id)
+    }
+
+    fn finish(&mut self, mut scheduled: Scheduled, interrupted: bool) {
+        scheduled.command.end(interrupted);
+
+        // default commands go back to waiting for their subsystem to be free
+        if let Some(subsystem) = scheduled.default_of {
+            self.default_commands
+                .entry(subsystem)
+                .or_insert(scheduled.command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver_station::{set_source, SimDriverStation};
+    use crate::input::RobotMode;
+
+    #[derive(Default)]
+    struct Elevator {
+        periodic_calls: usize,
+    }
+
+    impl Subsystem for Elevator {
+        fn periodic(&mut self) {
+            self.periodic_calls += 1;
+        }
+    }
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    struct Record {
+        name: &'static str,
+        requirements: Vec<SubsystemId>,
+        loops: usize,
+        duration: Option<usize>,
+        behavior: InterruptionBehavior,
+        log: Log,
+    }
+
+    impl Record {
+        fn new(name: &'static str, requirements: Vec<SubsystemId>, log: &Log) -> Self {
+            Self {
+                name,
+                requirements,
+                loops: 0,
+                duration: None,
+                behavior: InterruptionBehavior::CancelSelf,
+                log: log.clone(),
+            }
+        }
+
+        fn lasting(mut self, loops: usize) -> Self {
+            self.duration = Some(loops);
+            self
+        }
+
+        fn uninterruptible(mut self) -> Self {
+            self.behavior = InterruptionBehavior::CancelIncoming;
+            self
+        }
+    }
+
+    impl Command for Record {
+        fn initialize(&mut self) {
+            self.log.borrow_mut().push(format!("{} init", self.name));
+        }
+
+        fn execute(&mut self) {
+            self.loops += 1;
+        }
+
+        fn is_finished(&mut self) -> bool {
+            self.duration.is_some_and(|duration| self.loops >= duration)
+        }
+
+        fn end(&mut self, interrupted: bool) {
+            let how = if interrupted { "interrupted" } else { "done" };
+            self.log.borrow_mut().push(format!("{} {}", self.name, how));
+        }
+
+        fn requirements(&self) -> Vec<SubsystemId> {
+            self.requirements.clone()
+        }
+
+        fn interruption_behavior(&self) -> InterruptionBehavior {
+            self.behavior
+        }
+
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+    }
+
+    fn setup() -> (CommandScheduler, Rc<RefCell<Elevator>>, SubsystemId, Log) {
+        let ds = SimDriverStation::new();
+        ds.set_mode(RobotMode::Teleop);
+        set_source(ds);
+
+        let elevator = Rc::new(RefCell::new(Elevator::default()));
+        let mut scheduler = CommandScheduler::new();
+        let id = scheduler.register(elevator.clone());
+
+        (scheduler, elevator, id, Log::default())
+    }
+
+    #[test]
+    fn runs_until_finished() {
+        let (mut scheduler, elevator, id, log) = setup();
+
+        let command = scheduler
+            .schedule(Record::new("raise", vec![id], &log).lasting(3))
+            .unwrap();
+
+        for _ in 0..3 {
+            assert!(scheduler.is_scheduled(command));
+            scheduler.run();
+        }
+
+        assert!(!scheduler.is_scheduled(command));
+        assert_eq!(elevator.borrow().periodic_calls, 3);
+        assert_eq!(*log.borrow(), vec!["raise init", "raise done"]);
+    }
+
+    #[test]
+    fn conflicting_command_interrupts() {
+        let (mut scheduler, _, id, log) = setup();
+
+        let first = scheduler
+            .schedule(Record::new("raise", vec![id], &log))
+            .unwrap();
+        let second = scheduler
+            .schedule(Record::new("lower", vec![id], &log))
+            .unwrap();
+
+        assert!(!scheduler.is_scheduled(first));
+        assert_eq!(scheduler.requiring(id), Some(second));
+        assert_eq!(
+            *log.borrow(),
+            vec!["raise init", "raise interrupted", "lower init"]
+        );
+    }
+
+    #[test]
+    fn uninterruptible_command_rejects_incoming() {
+        let (mut scheduler, _, id, log) = setup();
+
+        let climb = scheduler
+            .schedule(Record::new("climb", vec![id], &log).uninterruptible())
+            .unwrap();
+        let result = scheduler.schedule(Record::new("lower", vec![id], &log));
+
+        assert_eq!(
+            result,
+            Err(ScheduleError::Conflict {
+                incoming: "lower".to_string(),
+                running: "climb".to_string(),
+            })
+        );
+        assert!(scheduler.is_scheduled(climb));
+    }
+
+    #[test]
+    fn default_command_resumes() {
+        let (mut scheduler, _, id, log) = setup();
+
+        scheduler
+            .set_default_command(id, Record::new("hold", vec![id], &log))
+            .unwrap();
+        scheduler.run();
+
+        scheduler
+            .schedule(Record::new("raise", vec![id], &log).lasting(1))
+            .unwrap();
+        scheduler.run();
+        scheduler.run();
+
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "hold init",
+                "hold interrupted",
+                "raise init",
+                "raise done",
+                "hold init"
+            ]
+        );
+    }
+
+    #[test]
+    fn default_command_must_require_subsystem() {
+        let (mut scheduler, _, id, log) = setup();
+
+        assert_eq!(
+            scheduler.set_default_command(id, Record::new("hold", vec![], &log)),
+            Err(ScheduleError::MissingRequirement("hold".to_string()))
+        );
+    }
+
+    #[test]
+    fn cancelled_when_disabled() {
+        let (mut scheduler, _, id, log) = setup();
+        let ds = SimDriverStation::new();
+        set_source(ds);
+
+        scheduler
+            .schedule(Record::new("raise", vec![id], &log))
+            .unwrap();
+        scheduler
+            .set_default_command(id, Record::new("hold", vec![id], &log))
+            .unwrap();
+        scheduler.run();
+        scheduler.run();
+
+        assert_eq!(scheduler.requiring(id), None);
+        assert_eq!(*log.borrow(), vec!["raise init", "raise interrupted"]);
+    }
+
+    struct CommandRobot {
+        scheduler: CommandScheduler,
+    }
+
+    impl crate::Robot for CommandRobot {
+        async fn robot_init(&mut self) {}
+
+        fn scheduler(&mut self) -> Option<&mut CommandScheduler> {
+            Some(&mut self.scheduler)
+        }
+    }
+
+    #[test]
+    fn runs_from_robot_loop() {
+        let (mut scheduler, elevator, id, log) = setup();
+        let ds = SimDriverStation::new();
+        ds.schedule_match(
+            std::time::Duration::from_millis(40),
+            std::time::Duration::ZERO,
+            std::time::Duration::ZERO,
+        );
+        set_source(ds);
+
+        scheduler
+            .schedule(Record::new("raise", vec![id], &log).lasting(5))
+            .unwrap();
+
+        let mut robot = CommandRobot { scheduler };
+        crate::Robot::start_competition(
+            &mut robot,
+            tokio::runtime::Runtime::new().unwrap(),
+            tokio::task::LocalSet::new(),
+        );
+
+        assert_eq!(*log.borrow(), vec!["raise init", "raise done"]);
+        assert_eq!(elevator.borrow().periodic_calls, 10);
+    }
+}
@@ -0,0 +1,514 @@
+use crate::motion_profile::{MotionProfile, ProfileState};
+
+/// A jerk limited ("S-curve") motion profile.
+///
+/// Acceleration ramps up and down at no more than `max_jerk` instead of switching instantly, so
+/// mechanisms start and stop smoothly. Like [`crate::trapezoidal::TrapezoidalProfile`] it can
+/// start and end moving. If the mechanism is accelerating when the plan is made, the acceleration
+/// is first ramped to zero at `max_jerk`. If the goal can't be reached without overshooting, the
+/// profile stops first and then comes back.
+///
+/// Plans with the double S algorithm from Biagiotti and Melchiorri, *Trajectory Planning for
+/// Automatic Machines and Robots*.
+///
+/// # Example
+///
+/// ```rust
+/// use frcrs::motion_profile::MotionProfile;
+/// use frcrs::s_curve::SCurveProfile;
+///
+/// let mut profile = SCurveProfile::new(0., 1.5, 2., 4., 20.);
+///
+/// // every loop
+/// let setpoint = profile.update(0.02);
+///
+/// // the goal moved, start over from the current setpoint
+/// profile.replan(setpoint, 0.5.into());
+/// ```
+pub struct SCurveProfile {
+    /// Brings the starting acceleration to zero before the rest of the plan
+    ease: Option<Ease>,
+    /// Used to come to a stop first when the goal can't be reached directly
+    stop: Option<DoubleS>,
+    main: DoubleS,
+    max_vel: f64,
+    max_acc: f64,
+    max_jerk: f64,
+    elapsed: f64,
+}
+
+impl SCurveProfile {
+    /// Start and end at rest
+    pub fn new(start: f64, goal: f64, max_vel: f64, max_acc: f64, max_jerk: f64) -> Self {
+        Self::from_states(
+            ProfileState::at_rest(start),
+            ProfileState::at_rest(goal),
+            max_vel,
+            max_acc,
+            max_jerk,
+        )
+    }
+
+    /// Plan from `current` to arrive at `goal` with its velocity. Any acceleration in `current`
+    /// is ramped to zero first, and velocities over `max_vel` are clamped.
+    pub fn from_states(
+        current: ProfileState,
+        goal: ProfileState,
+        max_vel: f64,
+        max_acc: f64,
+        max_jerk: f64,
+    ) -> Self {
+        let mut profile = Self {
+            ease: None,
+            stop: None,
+            main: DoubleS::default(),
+            max_vel,
+            max_acc,
+            max_jerk,
+            elapsed: 0.,
+        };
+        profile.replan(current, goal);
+        profile
+    }
+
+    /// Update the profile by the elapsed time `dt` in seconds, and return the setpoint
+    pub fn update(&mut self, dt: f64) -> ProfileState {
+        self.elapsed += dt;
+        self.state_at(self.elapsed)
+    }
+
+    /// Seconds since the profile was planned
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+}
+
+impl MotionProfile for SCurveProfile {
+    fn replan(&mut self, current: ProfileState, goal: ProfileState) {
+        let limits = Limits {
+            vel: self.max_vel,
+            acc: self.max_acc,
+            jerk: self.max_jerk,
+        };
+        // keep acceleration continuous when interrupted mid ramp, then plan from where it settles
+        self.ease = (current.acceleration != 0.).then(|| Ease::new(current, limits.jerk));
+        let current = self
+            .ease
+            .map_or(current, |ease| ease.state_at(ease.duration));
+
+        let v0 = current.velocity.clamp(-limits.vel, limits.vel);
+        let v1 = goal.velocity.clamp(-limits.vel, limits.vel);
+
+        self.elapsed = 0.;
+        self.stop = None;
+        self.main = match DoubleS::plan(current.position, goal.position, v0, v1, limits) {
+            Some(main) => main,
+            None => {
+                let stop = DoubleS::stop(current.position, v0, limits);
+                let from = stop.q1;
+                self.stop = Some(stop);
+
+                // the goal velocity may still be too fast to reach from rest, so ease off it
+                let mut v1 = v1;
+                loop {
+                    if let Some(main) = DoubleS::plan(from, goal.position, 0., v1, limits) {
+                        break main;
+                    }
+                    v1 = if v1.abs() < 1e-6 { 0. } else { v1 * 0.9 };
+                }
+            }
+        };
+    }
+
+    fn state_at(&self, mut t: f64) -> ProfileState {
+        if let Some(ease) = &self.ease {
+            if t < ease.duration {
+                return ease.state_at(t);
+            }
+            t -= ease.duration;
+        }
+
+        match &self.stop {
+            Some(stop) if t < stop.total() => stop.state_at(t),
+            Some(stop) => self.main.state_at(t - stop.total()),
+            None => self.main.state_at(t),
+        }
+    }
+
+    fn total_time(&self) -> f64 {
+        self.ease.as_ref().map_or(0., |ease| ease.duration)
+            + self.stop.as_ref().map_or(0., DoubleS::total)
+            + self.main.total()
+    }
+}
+
+/// Constant jerk from the starting acceleration down to zero
+#[derive(Clone, Copy)]
+struct Ease {
+    q0: f64,
+    v0: f64,
+    a0: f64,
+    jerk: f64,
+    duration: f64,
+}
+
+impl Ease {
+    fn new(current: ProfileState, jerk: f64) -> Self {
+        let a0 = current.acceleration;
+
+        Self {
+            q0: current.position,
+            v0: current.velocity,
+            a0,
+            jerk: -jerk.copysign(a0),
+            duration: a0.abs() / jerk,
+        }
+    }
+
+    fn state_at(&self, t: f64) -> ProfileState {
+        let t = t.clamp(0., self.duration);
+
+        ProfileState {
+            position: self.q0 + self.v0 * t + self.a0 * t * t / 2. + self.jerk * t.powi(3) / 6.,
+            velocity: self.v0 + self.a0 * t + self.jerk * t * t / 2.,
+            acceleration: if t < self.duration {
+                self.a0 + self.jerk * t
+            } else {
+                0.
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Limits {
+    vel: f64,
+    acc: f64,
+    jerk: f64,
+}
+
+/// One double S segment, planned in the direction of travel
+#[derive(Clone, Copy, Default)]
+struct DoubleS {
+    q0: f64,
+    q1: f64,
+    v0: f64,
+    v1: f64,
+    sign: f64,
+    jerk: f64,
+    /// Jerk phase and total time while speeding up
+    tj1: f64,
+    ta: f64,
+    /// Time at constant speed
+    tv: f64,
+    /// Jerk phase and total time while slowing down
+    tj2: f64,
+    td: f64,
+    acc_a: f64,
+    acc_d: f64,
+    vlim: f64,
+}
+
+impl DoubleS {
+    /// `None` if the goal would be overshot
+    fn plan(q0: f64, q1: f64, v0: f64, v1: f64, limits: Limits) -> Option<Self> {
+        let sign = if q1 >= q0 { 1. } else { -1. };
+        let (q0, q1, v0, v1) = (q0 * sign, q1 * sign, v0 * sign, v1 * sign);
+        let h = q1 - q0;
+        let Limits { vel, jerk, .. } = limits;
+        let mut acc = limits.acc;
+
+        if v0 < 0. || v1 < 0. {
+            return None;
+        }
+
+        // shortest distance to change speed from v0 to v1
+        let dv = (v1 - v0).abs();
+        let tj = (dv / jerk).sqrt().min(acc / jerk);
+        let min_distance = if tj < acc / jerk {
+            tj * (v0 + v1)
+        } else {
+            0.5 * (v0 + v1) * (tj + dv / acc)
+        };
+        if h < min_distance - 1e-9 {
+            return None;
+        }
+
+        let mut plan = Self {
+            q0,
+            q1,
+            v0,
+            v1,
+            sign,
+            jerk,
+            ..Self::default()
+        };
+
+        if h < 1e-12 && v0 < 1e-12 && v1 < 1e-12 {
+            return Some(plan);
+        }
+
+        // assume full speed is reached
+        let ramp = |v: f64, acc: f64| {
+            if (vel - v) * jerk < acc * acc {
+                let tj = ((vel - v) / jerk).sqrt();
+                (tj, 2. * tj)
+            } else {
+                (acc / jerk, acc / jerk + (vel - v) / acc)
+            }
+        };
+        let (tj1, ta) = ramp(v0, acc);
+        let (tj2, td) = ramp(v1, acc);
+        let tv = h / vel - ta / 2. * (1. + v0 / vel) - td / 2. * (1. + v1 / vel);
+
+        if tv > 0. {
+            (plan.tj1, plan.ta, plan.tv, plan.tj2, plan.td) = (tj1, ta, tv, tj2, td);
+        } else {
+            // full speed isn't reached, lower the acceleration until the phases fit
+            loop {
+                let tj = acc / jerk;
+                let delta = acc.powi(4) / jerk.powi(2)
+                    + 2. * (v0 * v0 + v1 * v1)
+                    + acc * (4. * h - 2. * acc / jerk * (v0 + v1));
+                let ta = (acc * acc / jerk - 2. * v0 + delta.sqrt()) / (2. * acc);
+                let td = (acc * acc / jerk - 2. * v1 + delta.sqrt()) / (2. * acc);
+
+                if ta < 0. {
+                    plan.ta = 0.;
+                    plan.tj1 = 0.;
+                    plan.td = 2. * h / (v1 + v0);
+                    plan.tj2 = (jerk * h
+                        - (jerk * (jerk * h * h + (v1 + v0).powi(2) * (v1 - v0))).sqrt())
+                        / (jerk * (v1 + v0));
+                    break;
+                }
+                if td < 0. {
+                    plan.td = 0.;
+                    plan.tj2 = 0.;
+                    plan.ta = 2. * h / (v1 + v0);
+                    plan.tj1 = (jerk * h
+                        - (jerk * (jerk * h * h - (v1 + v0).powi(2) * (v1 - v0))).sqrt())
+                        / (jerk * (v1 + v0));
+                    break;
+                }
+                if (ta >= 2. * tj && td >= 2. * tj) || acc < 1e-9 {
+                    (plan.tj1, plan.ta, plan.tj2, plan.td) = (tj, ta, tj, td);
+                    break;
+                }
+
+                acc *= 0.99;
+            }
+        }
+
+        plan.acc_a = jerk * plan.tj1;
+        plan.acc_d = -jerk * plan.tj2;
+        plan.vlim = v0 + (plan.ta - plan.tj1) * plan.acc_a;
+        if plan.ta == 0. {
+            plan.vlim = v0;
+        }
+
+        Some(plan)
+    }
+
+    /// Slow from `v0` to rest as quickly as the limits allow
+    fn stop(q0: f64, v0: f64, limits: Limits) -> Self {
+        let sign = if v0 >= 0. { 1. } else { -1. };
+        let v0 = v0.abs();
+        let Limits { acc, jerk, .. } = limits;
+
+        let (tj, td) = if v0 * jerk < acc * acc {
+            let tj = (v0 / jerk).sqrt();
+            (tj, 2. * tj)
+        } else {
+            (acc / jerk, acc / jerk + v0 / acc)
+        };
+
+        Self {
+            q0: q0 * sign,
+            q1: q0 * sign + v0 * td / 2.,
+            v0,
+            v1: 0.,
+            sign,
+            jerk,
+            tj2: tj,
+            td,
+            acc_d: -jerk * tj,
+            vlim: v0,
+            ..Self::default()
+        }
+    }
+
+    fn total(&self) -> f64 {
+        self.ta + self.tv + self.td
+    }
+
+    fn state_at(&self, t: f64) -> ProfileState {
+        let Self {
+            q0,
+            q1,
+            v0,
+            v1,
+            jerk,
+            tj1,
+            ta,
+            tj2,
+            td,
+            acc_a,
+            acc_d,
+            vlim,
+            ..
+        } = *self;
+        let total = self.total();
+        let t = t.clamp(0., total);
+
+        let (position, velocity, acceleration) = if t < tj1 {
+            (
+                q0 + v0 * t + jerk * t.powi(3) / 6.,
+                v0 + jerk * t * t / 2.,
+                jerk * t,
+            )
+        } else if t < ta - tj1 {
+            (
+                q0 + v0 * t + acc_a / 6. * (3. * t * t - 3. * tj1 * t + tj1 * tj1),
+                v0 + acc_a * (t - tj1 / 2.),
+                acc_a,
+            )
+        } else if t < ta {
+            let left = ta - t;
+            (
+                q0 + (vlim + v0) * ta / 2. - vlim * left + jerk * left.powi(3) / 6.,
+                vlim - jerk * left * left / 2.,
+                jerk * left,
+            )
+        } else if t < total - td {
+            (q0 + (vlim + v0) * ta / 2. + vlim * (t - ta), vlim, 0.)
+        } else if t < total - td + tj2 {
+            let into = t - total + td;
+            (
+                q1 - (vlim + v1) * td / 2. + vlim * into - jerk * into.powi(3) / 6.,
+                vlim - jerk * into * into / 2.,
+                -jerk * into,
+            )
+        } else if t < total - tj2 {
+            let into = t - total + td;
+            (
+                q1 - (vlim + v1) * td / 2.
+                    + vlim * into
+                    + acc_d / 6. * (3. * into * into - 3. * tj2 * into + tj2 * tj2),
+                vlim + acc_d * (into - tj2 / 2.),
+                acc_d,
+            )
+        } else {
+            let left = total - t;
+            (
+                q1 - v1 * left - jerk * left.powi(3) / 6.,
+                v1 + jerk * left * left / 2.,
+                -jerk * left,
+            )
+        };
+
+        ProfileState {
+            position: position * self.sign,
+            velocity: velocity * self.sign,
+            acceleration: acceleration * self.sign,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Step through a profile checking it is continuous and within its limits
+    fn check(profile: &SCurveProfile, vel: f64, acc: f64, jerk: f64) {
+        let dt = 1e-3;
+        let mut previous = profile.state_at(0.);
+        let mut t = dt;
+
+        while t <= profile.total_time() + dt {
+            let state = profile.state_at(t);
+
+            assert!(state.velocity.abs() <= vel + 1e-6, "{:?} at {}", state, t);
+            assert!(
+                state.acceleration.abs() <= acc + 1e-6,
+                "{:?} at {}",
+                state,
+                t
+            );
+            assert!(
+                (state.acceleration - previous.acceleration).abs() <= jerk * dt + 1e-6,
+                "{:?} after {:?} at {}",
+                state,
+                previous,
+                t
+            );
+            assert!((state.position - previous.position).abs() <= vel * dt + 1e-6);
+
+            previous = state;
+            t += dt;
+        }
+    }
+
+    #[test]
+    fn reaches_full_speed() {
+        let profile = SCurveProfile::new(0., 10., 2., 4., 20.);
+        check(&profile, 2., 4., 20.);
+
+        // 0.7s and 0.7m to reach full speed each way, 4.3s cruising
+        assert!((profile.total_time() - 5.7).abs() < 1e-9);
+        assert!((profile.velocity_at(2.) - 2.).abs() < 1e-9);
+        assert_eq!(
+            profile.state_at(profile.total_time()),
+            ProfileState::at_rest(10.)
+        );
+    }
+
+    #[test]
+    fn short_move_backwards() {
+        let profile = SCurveProfile::new(1., 0.9, 2., 4., 20.);
+        check(&profile, 2., 4., 20.);
+
+        let halfway = profile.state_at(profile.total_time() / 2.);
+        assert!((halfway.position - 0.95).abs() < 1e-9);
+        assert!(halfway.velocity < 0.);
+        assert!((profile.position_at(profile.total_time()) - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn interrupted_mid_move() {
+        let mut profile = SCurveProfile::new(0., 10., 2., 4., 20.);
+        let mut state = ProfileState::default();
+        for _ in 0..5 {
+            state = profile.update(0.02);
+        }
+        assert!(state.acceleration > 1.);
+
+        // the goal moved behind us while speeding up, so ease off, stop, then come back
+        profile.replan(state, (-1.0).into());
+        check(&profile, 2., 4., 20.);
+
+        let next = profile.state_at(1e-3);
+        assert!((next.position - state.position).abs() < 0.01);
+        assert!((next.acceleration - state.acceleration).abs() <= 20. * 1e-3 + 1e-6);
+        assert!(next.velocity > 0.);
+        assert!((profile.position_at(profile.total_time()) + 1.).abs() < 1e-9);
+        assert!(profile.is_finished(profile.total_time()));
+    }
+
+    #[test]
+    fn ends_moving() {
+        let profile = SCurveProfile::from_states(
+            ProfileState::new(0., 1.),
+            ProfileState::new(3., 0.5),
+            2.,
+            4.,
+            20.,
+        );
+        check(&profile, 2., 4., 20.);
+
+        assert_eq!(profile.velocity_at(0.), 1.);
+        let end = profile.state_at(profile.total_time());
+        assert!((end.position - 3.).abs() < 1e-9);
+        assert!((end.velocity - 0.5).abs() < 1e-9);
+    }
+}
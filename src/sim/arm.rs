@@ -0,0 +1,164 @@
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{Angle, AngularVelocity, Length, Mass, Time};
+use uom::si::length::meter;
+use uom::si::mass::kilogram;
+use uom::si::time::second;
+
+use super::{integrate, Gearbox, Mechanism, SimMotor, GRAVITY};
+
+/// Gravity changes with the arm angle, so the arm is integrated in steps no longer than this.
+const MAX_STEP: f64 = 0.001;
+
+/// A single jointed arm rotating in a vertical plane.
+///
+/// The arm is modelled as a uniform rod pivoting at one end. An angle of zero is horizontal, and
+/// the arm stops dead at its hard stops.
+pub struct ArmSim {
+    gearbox: Gearbox,
+    length: f64,
+    mass: f64,
+    moi: f64,
+    min_angle: f64,
+    max_angle: f64,
+    angle: f64,
+    velocity: f64,
+    motor: SimMotor,
+}
+
+impl ArmSim {
+    pub fn new(
+        gearbox: Gearbox,
+        length: Length,
+        mass: Mass,
+        min_angle: Angle,
+        max_angle: Angle,
+        starting_angle: Angle,
+    ) -> Self {
+        let length = length.get::<meter>();
+        let mass = mass.get::<kilogram>();
+
+        let arm = Self {
+            gearbox,
+            length,
+            mass,
+            moi: mass * length * length / 3.,
+            min_angle: min_angle.get::<radian>(),
+            max_angle: max_angle.get::<radian>(),
+            angle: starting_angle.get::<radian>(),
+            velocity: 0.,
+            motor: SimMotor::new(),
+        };
+        arm.sync_motor(0.);
+        arm
+    }
+
+    /// Handle to the motor driving the arm
+    pub fn motor(&self) -> SimMotor {
+        self.motor.clone()
+    }
+
+    pub fn angle(&self) -> Angle {
+        Angle::new::<radian>(self.angle)
+    }
+
+    pub fn velocity(&self) -> AngularVelocity {
+        AngularVelocity::new::<radian_per_second>(self.velocity)
+    }
+
+    pub fn at_lower_limit(&self) -> bool {
+        self.angle <= self.min_angle
+    }
+
+    pub fn at_upper_limit(&self) -> bool {
+        self.angle >= self.max_angle
+    }
+
+    fn sync_motor(&self, voltage: f64) {
+        let reduction = self.gearbox.reduction;
+        self.motor.update_rotor(
+            self.angle * reduction,
+            self.velocity * reduction,
+            self.gearbox.current(self.velocity, voltage),
+        );
+    }
+}
+
+impl Mechanism for ArmSim {
+    fn update(&mut self, dt: Time) {
+        let voltage = self.motor.voltage_si();
+        let (torque_per_volt, torque_per_speed) = self.gearbox.torque_coefficients();
+
+        let mut remaining = dt.get::<second>();
+        while remaining > 0. {
+            let step = remaining.min(MAX_STEP);
+            remaining -= step;
+
+            let gravity = self.mass * GRAVITY * self.length / 2. * self.angle.cos();
+
+            (self.angle, self.velocity) = integrate(
+                self.angle,
+                self.velocity,
+                (torque_per_volt * voltage - gravity) / self.moi,
+                -torque_per_speed / self.moi,
+                step,
+            );
+
+            if self.angle < self.min_angle {
+                self.angle = self.min_angle;
+                self.velocity = 0.;
+            } else if self.angle > self.max_angle {
+                self.angle = self.max_angle;
+                self.velocity = 0.;
+            }
+        }
+
+        self.sync_motor(voltage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::MotorController;
+    use crate::sim::DCMotor;
+    use uom::si::angle::degree;
+    use uom::si::time::millisecond;
+
+    fn arm() -> ArmSim {
+        ArmSim::new(
+            Gearbox::new(DCMotor::neo(1), 100.),
+            Length::new::<meter>(0.5),
+            Mass::new::<kilogram>(3.),
+            Angle::new::<degree>(-45.),
+            Angle::new::<degree>(90.),
+            Angle::new::<degree>(0.),
+        )
+    }
+
+    #[test]
+    fn falls_to_hard_stop() {
+        let mut arm = arm();
+
+        // the motor's back EMF brakes the fall through the reduction
+        for _ in 0..500 {
+            arm.update(Time::new::<millisecond>(20.));
+        }
+
+        assert!(arm.at_lower_limit());
+        assert_eq!(arm.velocity().get::<radian_per_second>(), 0.);
+    }
+
+    #[test]
+    fn lifts_under_power() {
+        let mut arm = arm();
+        let mut motor = arm.motor();
+
+        motor.set_percent(1.);
+        for _ in 0..100 {
+            arm.update(Time::new::<millisecond>(20.));
+        }
+
+        assert!(arm.at_upper_limit());
+    }
+}
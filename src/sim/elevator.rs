@@ -0,0 +1,162 @@
+use uom::si::f64::{Length, Mass, Time, Velocity};
+use uom::si::length::meter;
+use uom::si::mass::kilogram;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+use super::{integrate, Gearbox, Mechanism, SimMotor, GRAVITY};
+
+/// A carriage lifted by a drum or sprocket, with hard stops at either end.
+pub struct ElevatorSim {
+    gearbox: Gearbox,
+    mass: f64,
+    drum_radius: f64,
+    min_height: f64,
+    max_height: f64,
+    height: f64,
+    velocity: f64,
+    motor: SimMotor,
+}
+
+impl ElevatorSim {
+    pub fn new(
+        gearbox: Gearbox,
+        carriage_mass: Mass,
+        drum_radius: Length,
+        min_height: Length,
+        max_height: Length,
+        starting_height: Length,
+    ) -> Self {
+        let elevator = Self {
+            gearbox,
+            mass: carriage_mass.get::<kilogram>(),
+            drum_radius: drum_radius.get::<meter>(),
+            min_height: min_height.get::<meter>(),
+            max_height: max_height.get::<meter>(),
+            height: starting_height.get::<meter>(),
+            velocity: 0.,
+            motor: SimMotor::new(),
+        };
+        elevator.sync_motor(0.);
+        elevator
+    }
+
+    /// Handle to the motor driving the elevator
+    pub fn motor(&self) -> SimMotor {
+        self.motor.clone()
+    }
+
+    pub fn height(&self) -> Length {
+        Length::new::<meter>(self.height)
+    }
+
+    pub fn velocity(&self) -> Velocity {
+        Velocity::new::<meter_per_second>(self.velocity)
+    }
+
+    pub fn at_lower_limit(&self) -> bool {
+        self.height <= self.min_height
+    }
+
+    pub fn at_upper_limit(&self) -> bool {
+        self.height >= self.max_height
+    }
+
+    fn sync_motor(&self, voltage: f64) {
+        // drum rotations, scaled up through the gearbox
+        let to_rotor = self.gearbox.reduction / self.drum_radius;
+        self.motor.update_rotor(
+            self.height * to_rotor,
+            self.velocity * to_rotor,
+            self.gearbox
+                .current(self.velocity / self.drum_radius, voltage),
+        );
+    }
+}
+
+impl Mechanism for ElevatorSim {
+    fn update(&mut self, dt: Time) {
+        let voltage = self.motor.voltage_si();
+        let (torque_per_volt, torque_per_speed) = self.gearbox.torque_coefficients();
+        let r = self.drum_radius;
+
+        (self.height, self.velocity) = integrate(
+            self.height,
+            self.velocity,
+            torque_per_volt * voltage / (r * self.mass) - GRAVITY,
+            -torque_per_speed / (r * r * self.mass),
+            dt.get::<second>(),
+        );
+
+        if self.height < self.min_height {
+            self.height = self.min_height;
+            self.velocity = 0.;
+        } else if self.height > self.max_height {
+            self.height = self.max_height;
+            self.velocity = 0.;
+        }
+
+        self.sync_motor(voltage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{Encoder, MotorController};
+    use crate::sim::DCMotor;
+    use crate::trapezoidal::{TrapezoidalProfile, PID};
+    use std::f64::consts::PI;
+    use uom::si::angle::revolution;
+    use uom::si::time::millisecond;
+
+    const REDUCTION: f64 = 10.;
+    const DRUM_RADIUS: f64 = 0.02;
+
+    fn elevator() -> ElevatorSim {
+        ElevatorSim::new(
+            Gearbox::new(DCMotor::kraken_x60(1), REDUCTION),
+            Mass::new::<kilogram>(5.),
+            Length::new::<meter>(DRUM_RADIUS),
+            Length::new::<meter>(0.),
+            Length::new::<meter>(1.5),
+            Length::new::<meter>(0.),
+        )
+    }
+
+    #[test]
+    fn rests_on_hard_stop() {
+        let mut elevator = elevator();
+
+        elevator.update(Time::new::<millisecond>(20.));
+
+        assert!(elevator.at_lower_limit());
+        assert_eq!(elevator.height().get::<meter>(), 0.);
+    }
+
+    #[test]
+    fn follows_trapezoidal_profile() {
+        let mut elevator = elevator();
+        let mut motor = elevator.motor();
+
+        let dt = 0.02;
+        let mut profile = TrapezoidalProfile::new(0., 1., 1., 2.);
+        let mut pid = PID::new(5., 0., 0.);
+
+        // encoder reads rotor rotations, convert back to carriage height
+        let height = |motor: &mut SimMotor| {
+            motor.position().get::<revolution>() / REDUCTION * 2. * PI * DRUM_RADIUS
+        };
+
+        for _ in 0..150 {
+            let setpoint = profile.update(dt);
+            let output = pid.update(setpoint.position, height(&mut motor), dt);
+            motor.set_percent(output);
+
+            elevator.update(Time::new::<second>(dt));
+        }
+
+        assert!((height(&mut motor) - 1.).abs() < 0.02);
+        assert!((elevator.height().get::<meter>() - 1.).abs() < 0.02);
+    }
+}
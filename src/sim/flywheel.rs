@@ -0,0 +1,111 @@
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{Angle, AngularVelocity, MomentOfInertia, Time};
+use uom::si::moment_of_inertia::kilogram_square_meter;
+use uom::si::time::second;
+
+use super::{integrate, Gearbox, Mechanism, SimMotor};
+
+/// A spinning mass with no external load, such as a shooter wheel.
+pub struct FlywheelSim {
+    gearbox: Gearbox,
+    moi: f64,
+    position: f64,
+    velocity: f64,
+    motor: SimMotor,
+}
+
+impl FlywheelSim {
+    pub fn new(gearbox: Gearbox, moi: MomentOfInertia) -> Self {
+        Self {
+            gearbox,
+            moi: moi.get::<kilogram_square_meter>(),
+            position: 0.,
+            velocity: 0.,
+            motor: SimMotor::new(),
+        }
+    }
+
+    /// Handle to the motor driving the flywheel
+    pub fn motor(&self) -> SimMotor {
+        self.motor.clone()
+    }
+
+    /// Position of the output shaft
+    pub fn position(&self) -> Angle {
+        Angle::new::<radian>(self.position)
+    }
+
+    /// Velocity of the output shaft
+    pub fn velocity(&self) -> AngularVelocity {
+        AngularVelocity::new::<radian_per_second>(self.velocity)
+    }
+
+    pub fn set_velocity(&mut self, velocity: AngularVelocity) {
+        self.velocity = velocity.get::<radian_per_second>();
+    }
+}
+
+impl Mechanism for FlywheelSim {
+    fn update(&mut self, dt: Time) {
+        let voltage = self.motor.voltage_si();
+        let (torque_per_volt, torque_per_speed) = self.gearbox.torque_coefficients();
+
+        (self.position, self.velocity) = integrate(
+            self.position,
+            self.velocity,
+            torque_per_volt * voltage / self.moi,
+            -torque_per_speed / self.moi,
+            dt.get::<second>(),
+        );
+
+        let reduction = self.gearbox.reduction;
+        self.motor.update_rotor(
+            self.position * reduction,
+            self.velocity * reduction,
+            self.gearbox.current(self.velocity, voltage),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{Encoder, MotorController};
+    use crate::sim::DCMotor;
+    use uom::si::time::millisecond;
+
+    #[test]
+    fn reaches_free_speed() {
+        let motor = DCMotor::falcon_500(1);
+        let mut flywheel = FlywheelSim::new(
+            Gearbox::new(motor, 2.),
+            MomentOfInertia::new::<kilogram_square_meter>(0.001),
+        );
+        let mut output = flywheel.motor();
+
+        output.set_percent(1.);
+        for _ in 0..250 {
+            flywheel.update(Time::new::<millisecond>(20.));
+        }
+
+        let free_speed = motor.free_speed().get::<radian_per_second>();
+        let rotor_speed = output.velocity().get::<radian_per_second>();
+        assert!((rotor_speed - free_speed).abs() / free_speed < 0.01);
+        assert!((flywheel.velocity().get::<radian_per_second>() - rotor_speed / 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn coasts_without_output() {
+        let mut flywheel = FlywheelSim::new(
+            Gearbox::new(DCMotor::neo(1), 1.),
+            MomentOfInertia::new::<kilogram_square_meter>(0.01),
+        );
+        flywheel.set_velocity(AngularVelocity::new::<radian_per_second>(100.));
+
+        flywheel.update(Time::new::<millisecond>(20.));
+
+        let velocity = flywheel.velocity().get::<radian_per_second>();
+        assert!(velocity > 0. && velocity < 100.);
+    }
+}
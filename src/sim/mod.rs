@@ -0,0 +1,236 @@
+//! Deterministic physics simulation of motors and mechanisms.
+//!
+//! Each mechanism hands out [`SimMotor`]s, which implement the [`crate::hardware`] traits and
+//! can be given to a subsystem in place of a `Talon` or `Spark`. Every loop, [`Simulation::step`]
+//! reads the output the subsystem commanded, integrates the mechanism, and writes the new rotor
+//! position and velocity back to the motor's encoder.
+//!
+//! # Example
+//!
+//! ```rust
+//! use frcrs::hardware::{Encoder, MotorController};
+//! use frcrs::sim::{DCMotor, FlywheelSim, Gearbox, Simulation};
+//! use uom::si::angular_velocity::revolution_per_minute;
+//! use uom::si::f64::{MomentOfInertia, Time};
+//! use uom::si::moment_of_inertia::kilogram_square_meter;
+//! use uom::si::time::millisecond;
+//!
+//! let flywheel = FlywheelSim::new(
+//!     Gearbox::new(DCMotor::kraken_x60(2), 1.),
+//!     MomentOfInertia::new::<kilogram_square_meter>(0.002),
+//! );
+//! let mut motor = flywheel.motor();
+//!
+//! let mut sim = Simulation::new();
+//! sim.add(flywheel);
+//!
+//! motor.set_percent(1.);
+//! for _ in 0..100 {
+//!     sim.step(Time::new::<millisecond>(20.));
+//! }
+//!
+//! assert!(motor.velocity().get::<revolution_per_minute>() > 5000.);
+//! ```
+
+mod arm;
+mod elevator;
+mod flywheel;
+mod motor;
+
+pub use arm::*;
+pub use elevator::*;
+pub use flywheel::*;
+pub use motor::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::electric_current::ampere;
+use uom::si::electric_potential::volt;
+use uom::si::f64::{Angle, AngularVelocity, ElectricCurrent, ElectricPotential, Time};
+use uom::si::time::second;
+
+use crate::hardware::{Encoder, MotorController};
+
+/// Acceleration due to gravity in m/s^2
+const GRAVITY: f64 = 9.81;
+
+/// A simulated mechanism that can be advanced in time.
+pub trait Mechanism {
+    fn update(&mut self, dt: Time);
+}
+
+impl<M: Mechanism> Mechanism for Rc<RefCell<M>> {
+    fn update(&mut self, dt: Time) {
+        self.borrow_mut().update(dt);
+    }
+}
+
+/// A set of mechanisms that are stepped together.
+///
+/// Mechanisms are moved into the simulation. To keep inspecting one after adding it, wrap it in
+/// an `Rc<RefCell<_>>` and add a clone.
+pub struct Simulation {
+    mechanisms: Vec<Box<dyn Mechanism>>,
+    time: f64,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation {
+    pub fn new() -> Self {
+        Self {
+            mechanisms: Vec::new(),
+            time: 0.,
+        }
+    }
+
+    pub fn add<M: Mechanism + 'static>(&mut self, mechanism: M) {
+        self.mechanisms.push(Box::new(mechanism));
+    }
+
+    /// Advance every mechanism by `dt`
+    pub fn step(&mut self, dt: Time) {
+        for mechanism in &mut self.mechanisms {
+            mechanism.update(dt);
+        }
+
+        self.time += dt.get::<second>();
+    }
+
+    /// Time simulated since the simulation was created
+    pub fn time(&self) -> Time {
+        Time::new::<second>(self.time)
+    }
+}
+
+struct SimMotorState {
+    output: f64,
+    supply_voltage: f64,
+    // rotor position and velocity, in radians and rad/s
+    position: f64,
+    velocity: f64,
+    current: f64,
+}
+
+/// A motor controller driving a simulated mechanism.
+///
+/// Like the integrated encoder of a `Talon` or `Spark`, position and velocity are reported at the
+/// rotor, before the gearbox.
+#[derive(Clone)]
+pub struct SimMotor {
+    state: Rc<RefCell<SimMotorState>>,
+}
+
+impl SimMotor {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(SimMotorState {
+                output: 0.,
+                supply_voltage: 12.,
+                position: 0.,
+                velocity: 0.,
+                current: 0.,
+            })),
+        }
+    }
+
+    /// Last commanded output, from -1 to 1
+    pub fn output(&self) -> f64 {
+        self.state.borrow().output
+    }
+
+    /// Voltage currently applied to the motor
+    pub fn voltage(&self) -> ElectricPotential {
+        ElectricPotential::new::<volt>(self.voltage_si())
+    }
+
+    /// Current drawn by the motor during the last step
+    pub fn current(&self) -> ElectricCurrent {
+        ElectricCurrent::new::<ampere>(self.state.borrow().current)
+    }
+
+    /// Change the battery voltage the output is scaled by, defaults to 12V
+    pub fn set_supply_voltage(&self, voltage: ElectricPotential) {
+        self.state.borrow_mut().supply_voltage = voltage.get::<volt>();
+    }
+
+    pub(crate) fn voltage_si(&self) -> f64 {
+        let state = self.state.borrow();
+        state.output * state.supply_voltage
+    }
+
+    pub(crate) fn update_rotor(&self, position: f64, velocity: f64, current: f64) {
+        let mut state = self.state.borrow_mut();
+        state.position = position;
+        state.velocity = velocity;
+        state.current = current;
+    }
+}
+
+impl MotorController for SimMotor {
+    fn set_percent(&mut self, percent: f64) {
+        self.state.borrow_mut().output = percent.clamp(-1., 1.);
+    }
+
+    fn stop(&mut self) {
+        self.state.borrow_mut().output = 0.;
+    }
+}
+
+impl Encoder for SimMotor {
+    fn position(&mut self) -> Angle {
+        Angle::new::<radian>(self.state.borrow().position)
+    }
+
+    fn velocity(&mut self) -> AngularVelocity {
+        AngularVelocity::new::<radian_per_second>(self.state.borrow().velocity)
+    }
+}
+
+/// Integrate `x'' = a + b * x'` exactly over `dt`, holding `a` and `b` constant.
+///
+/// Motors behind a large reduction have a time constant of a few milliseconds, so explicit
+/// integrators blow up at robot loop rates. Solving the linear part in closed form keeps the
+/// simulation stable at any step size.
+fn integrate(position: f64, velocity: f64, a: f64, b: f64, dt: f64) -> (f64, f64) {
+    if b.abs() < 1e-12 {
+        return (
+            position + velocity * dt + 0.5 * a * dt * dt,
+            velocity + a * dt,
+        );
+    }
+
+    let steady_state = -a / b;
+    let decay = (b * dt).exp();
+
+    let velocity_next = steady_state + (velocity - steady_state) * decay;
+    let position_next = position + steady_state * dt + (velocity - steady_state) * (decay - 1.) / b;
+
+    (position_next, velocity_next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::integrate;
+
+    #[test]
+    fn integrate_constant_acceleration() {
+        let (position, velocity) = integrate(0., 0., 2., 0., 1.);
+
+        assert_eq!((position, velocity), (1., 2.));
+    }
+
+    #[test]
+    fn integrate_settles_at_steady_state() {
+        let (_, velocity) = integrate(0., 0., 10., -100., 1.);
+
+        assert!((velocity - 0.1).abs() < 1e-9);
+    }
+}
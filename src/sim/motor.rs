@@ -0,0 +1,196 @@
+use uom::si::angular_velocity::{radian_per_second, revolution_per_minute};
+use uom::si::electric_current::ampere;
+use uom::si::electric_potential::volt;
+use uom::si::f64::{AngularVelocity, ElectricCurrent, ElectricPotential, Torque};
+use uom::si::torque::newton_meter;
+
+/// Model of one or more identical brushed or brushless DC motors driving the same shaft.
+///
+/// The constants are taken from the vendor motor curves, everything else is derived from them.
+#[derive(Clone, Copy, Debug)]
+pub struct DCMotor {
+    nominal_voltage: f64,
+    stall_torque: f64,
+    stall_current: f64,
+    free_current: f64,
+    free_speed: f64,
+
+    resistance: f64,
+    // rad/s per volt
+    kv: f64,
+    // Nm per amp
+    kt: f64,
+}
+
+impl DCMotor {
+    pub fn new(
+        nominal_voltage: ElectricPotential,
+        stall_torque: Torque,
+        stall_current: ElectricCurrent,
+        free_current: ElectricCurrent,
+        free_speed: AngularVelocity,
+        motor_count: usize,
+    ) -> Self {
+        let count = motor_count as f64;
+
+        let nominal_voltage = nominal_voltage.get::<volt>();
+        let stall_torque = stall_torque.get::<newton_meter>() * count;
+        let stall_current = stall_current.get::<ampere>() * count;
+        let free_current = free_current.get::<ampere>() * count;
+        let free_speed = free_speed.get::<radian_per_second>();
+
+        let resistance = nominal_voltage / stall_current;
+
+        Self {
+            nominal_voltage,
+            stall_torque,
+            stall_current,
+            free_current,
+            free_speed,
+
+            resistance,
+            kv: free_speed / (nominal_voltage - resistance * free_current),
+            kt: stall_torque / stall_current,
+        }
+    }
+
+    fn from_curve(
+        stall_torque: f64,
+        stall_current: f64,
+        free_current: f64,
+        free_speed_rpm: f64,
+        motor_count: usize,
+    ) -> Self {
+        Self::new(
+            ElectricPotential::new::<volt>(12.),
+            Torque::new::<newton_meter>(stall_torque),
+            ElectricCurrent::new::<ampere>(stall_current),
+            ElectricCurrent::new::<ampere>(free_current),
+            AngularVelocity::new::<revolution_per_minute>(free_speed_rpm),
+            motor_count,
+        )
+    }
+
+    pub fn kraken_x60(motor_count: usize) -> Self {
+        Self::from_curve(7.09, 366., 2., 6000., motor_count)
+    }
+
+    pub fn kraken_x60_foc(motor_count: usize) -> Self {
+        Self::from_curve(9.37, 483., 2., 5800., motor_count)
+    }
+
+    pub fn falcon_500(motor_count: usize) -> Self {
+        Self::from_curve(4.69, 257., 1.5, 6380., motor_count)
+    }
+
+    pub fn neo(motor_count: usize) -> Self {
+        Self::from_curve(2.6, 105., 1.8, 5676., motor_count)
+    }
+
+    pub fn neo_vortex(motor_count: usize) -> Self {
+        Self::from_curve(3.6, 211., 3.6, 6784., motor_count)
+    }
+
+    pub fn neo_550(motor_count: usize) -> Self {
+        Self::from_curve(0.97, 100., 1.4, 11000., motor_count)
+    }
+
+    pub fn nominal_voltage(&self) -> ElectricPotential {
+        ElectricPotential::new::<volt>(self.nominal_voltage)
+    }
+
+    pub fn stall_torque(&self) -> Torque {
+        Torque::new::<newton_meter>(self.stall_torque)
+    }
+
+    pub fn stall_current(&self) -> ElectricCurrent {
+        ElectricCurrent::new::<ampere>(self.stall_current)
+    }
+
+    pub fn free_current(&self) -> ElectricCurrent {
+        ElectricCurrent::new::<ampere>(self.free_current)
+    }
+
+    pub fn free_speed(&self) -> AngularVelocity {
+        AngularVelocity::new::<radian_per_second>(self.free_speed)
+    }
+
+    /// Current drawn when spinning at `speed` with `voltage` applied
+    pub fn current(&self, speed: AngularVelocity, voltage: ElectricPotential) -> ElectricCurrent {
+        ElectricCurrent::new::<ampere>(
+            self.current_si(speed.get::<radian_per_second>(), voltage.get::<volt>()),
+        )
+    }
+
+    /// Torque produced when drawing `current`
+    pub fn torque(&self, current: ElectricCurrent) -> Torque {
+        Torque::new::<newton_meter>(self.kt * current.get::<ampere>())
+    }
+
+    /// Voltage needed to produce `torque` while spinning at `speed`
+    pub fn voltage(&self, torque: Torque, speed: AngularVelocity) -> ElectricPotential {
+        ElectricPotential::new::<volt>(
+            speed.get::<radian_per_second>() / self.kv
+                + torque.get::<newton_meter>() / self.kt * self.resistance,
+        )
+    }
+
+    pub(crate) fn current_si(&self, speed: f64, voltage: f64) -> f64 {
+        (voltage - speed / self.kv) / self.resistance
+    }
+}
+
+/// A motor behind a reduction.
+///
+/// `reduction` is the number of motor rotations per output rotation, so a 5:1 gearbox is `5.`
+#[derive(Clone, Copy, Debug)]
+pub struct Gearbox {
+    pub motor: DCMotor,
+    pub reduction: f64,
+}
+
+impl Gearbox {
+    pub fn new(motor: DCMotor, reduction: f64) -> Self {
+        Self { motor, reduction }
+    }
+
+    /// Output torque is `voltage * a - output_speed * b`, this returns `(a, b)` in SI units
+    pub(crate) fn torque_coefficients(&self) -> (f64, f64) {
+        let motor = &self.motor;
+        let a = self.reduction * motor.kt / motor.resistance;
+        let b = self.reduction * self.reduction * motor.kt / (motor.resistance * motor.kv);
+        (a, b)
+    }
+
+    /// Current drawn in amps, given the output speed in rad/s
+    pub(crate) fn current(&self, output_speed: f64, voltage: f64) -> f64 {
+        self.motor
+            .current_si(output_speed * self.reduction, voltage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_speed_draws_free_current() {
+        let motor = DCMotor::kraken_x60(1);
+
+        let current = motor.current(motor.free_speed(), motor.nominal_voltage());
+
+        assert!((current.get::<ampere>() - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stall_torque_at_nominal_voltage() {
+        let motor = DCMotor::neo(2);
+
+        let torque = motor.torque(motor.current(
+            AngularVelocity::new::<radian_per_second>(0.),
+            motor.nominal_voltage(),
+        ));
+
+        assert!((torque.get::<newton_meter>() - 5.2).abs() < 1e-9);
+    }
+}
@@ -3,6 +3,9 @@ use jni::signature::Primitive::Void;
 use jni::signature::{Primitive, ReturnType};
 use jni::sys::jboolean;
 
+use crate::error::FrcError;
+use crate::hardware::PneumaticOutput;
+
 pub struct Solenoid {
     instance: GlobalRef,
 }
@@ -14,37 +17,41 @@ pub enum ModuleType {
 
 impl Solenoid {
     pub fn new(module_type: ModuleType, channel: i32) -> Self {
+        Self::try_new(module_type, channel).unwrap()
+    }
+
+    /// Like [`Solenoid::new`], but returns an error instead of panicking
+    pub fn try_new(module_type: ModuleType, channel: i32) -> Result<Self, FrcError> {
         let module_type_java = match module_type {
-            ModuleType::Rev => call_static!(
+            ModuleType::Rev => try_call_static!(
                 "frc/robot/Wrapper",
                 "revPH",
                 "()Ledu/wpi/first/wpilibj/PneumaticsModuleType;",
                 &Vec::new(),
                 ReturnType::Object
-            )
-            .l()
-            .unwrap(),
-            ModuleType::CTRE => call_static!(
+            )?
+            .l()?,
+            ModuleType::CTRE => try_call_static!(
                 "frc/robot/Wrapper",
                 "ctrePCM",
                 "()Ledu/wpi/first/wpilibj/PneumaticsModuleType;",
                 &Vec::new(),
                 ReturnType::Object
-            )
-            .l()
-            .unwrap(),
+            )?
+            .l()?,
         };
 
-        let instance = create!(
+        let instance = try_create!(
             "edu/wpi/first/wpilibj/Solenoid",
             "(Ledu/wpi/first/wpilibj/PneumaticsModuleType;I)V",
             &[
                 JValue::Object(&JObject::from(module_type_java)).as_jni(),
                 JValue::Int(channel).as_jni()
             ]
-        );
+        )
+        .map_err(FrcError::device("Solenoid", channel))?;
 
-        Self { instance }
+        Ok(Self { instance })
     }
 
     pub fn set(&self, engaged: bool) {
@@ -82,3 +89,13 @@ impl Solenoid {
         .unwrap()
     }
 }
+
+impl PneumaticOutput for Solenoid {
+    fn set(&mut self, engaged: bool) {
+        Solenoid::set(self, engaged);
+    }
+
+    fn get(&self) -> bool {
+        Solenoid::get(self)
+    }
+}
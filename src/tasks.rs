@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use tokio::task::{spawn_local, JoinHandle};
+use tokio::time::{interval, MissedTickBehavior};
+
+/// Names a task in a [`TaskManager`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TaskId(Key);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    Name(String),
+    /// Ids made for trigger bindings, which can't collide with named tasks or each other
+    Binding(usize),
+}
+
+impl TaskId {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(Key::Name(name.into()))
+    }
+
+    /// A fresh id, unique for the life of the process
+    pub(crate) fn binding() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        Self(Key::Binding(NEXT.fetch_add(1, Ordering::Relaxed)))
+    }
+
+    /// Name a task after a value, so tasks started with different arguments stay apart
+    ///
+    /// ```rust
+    /// use frcrs::TaskId;
+    ///
+    /// #[derive(Debug)]
+    /// enum Setpoint {
+    ///     Stow,
+    ///     Score(u8),
+    /// }
+    ///
+    /// assert_ne!(TaskId::of(&Setpoint::Score(1)), TaskId::of(&Setpoint::Score(2)));
+    /// ```
+    pub fn of(key: &impl Debug) -> Self {
+        Self::new(format!("{:?}", key))
+    }
+}
+
+impl From<&str> for TaskId {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for TaskId {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    Finished,
+    Cancelled,
+    Panicked,
+}
+
+struct Task {
+    handle: JoinHandle<()>,
+    status: TaskStatus,
+}
+
+impl Task {
+    fn status(&mut self) -> TaskStatus {
+        if self.status == TaskStatus::Running && self.handle.is_finished() {
+            // already finished, so this doesn't need a real waker
+            let mut context = Context::from_waker(Waker::noop());
+
+            if let Poll::Ready(result) = Pin::new(&mut self.handle).poll(&mut context) {
+                self.status = match result {
+                    Ok(()) => TaskStatus::Finished,
+                    Err(e) if e.is_panic() => TaskStatus::Panicked,
+                    Err(_) => TaskStatus::Cancelled,
+                };
+            }
+        }
+
+        self.status
+    }
+}
+
+/// Runs named tasks on the robot's `LocalSet`, starting each at most once at a time.
+///
+/// Tasks are spawned with [`tokio::task::spawn_local`], so the manager must be used from inside
+/// the robot loop. Return the manager from [`crate::Robot::tasks`] to have every task cancelled
+/// when the robot changes mode.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::{TaskManager, TaskStatus};
+///
+/// let mut tasks = TaskManager::new();
+///
+/// tasks.run_once("home", async { /* find the limit switch */ });
+/// tasks.run_task("hold", || async { /* apply feedforward */ });
+///
+/// if tasks.status("home") == Some(TaskStatus::Finished) {
+///     tasks.abort_task("hold");
+/// }
+/// ```
+pub struct TaskManager {
+    tasks: HashMap<TaskId, Task>,
+    period: Duration,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskManager {
+    /// A task manager that repeats tasks every 4ms
+    pub fn new() -> Self {
+        Self::with_period(Duration::from_millis(4))
+    }
+
+    /// A task manager that repeats tasks every `period`
+    pub fn with_period(period: Duration) -> Self {
+        Self {
+            tasks: HashMap::new(),
+            period,
+        }
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Change the period for tasks started from now on
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+    }
+
+    /// Call `task_fn` every period until the task is aborted.
+    ///
+    /// Does nothing if a task with this id is already running.
+    pub fn run_task<F, Fut>(&mut self, id: impl Into<TaskId>, mut task_fn: F)
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let period = self.period;
+
+        self.spawn(id.into(), async move {
+            let mut interval = interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                task_fn().await;
+            }
+        });
+    }
+
+    /// Run `task` to completion once.
+    ///
+    /// Does nothing if a task with this id is already running.
+    pub fn run_once(&mut self, id: impl Into<TaskId>, task: impl Future<Output = ()> + 'static) {
+        self.spawn(id.into(), task);
+    }
+
+    /// Cancel a task, does nothing if it isn't running
+    pub fn abort_task(&mut self, id: impl Into<TaskId>) {
+        if let Some(task) = self.tasks.get_mut(&id.into()) {
+            if task.status() == TaskStatus::Running {
+                task.handle.abort();
+                task.status = TaskStatus::Cancelled;
+            }
+        }
+    }
+
+    pub fn cancel_all(&mut self) {
+        for task in self.tasks.values_mut() {
+            if task.status() == TaskStatus::Running {
+                task.handle.abort();
+                task.status = TaskStatus::Cancelled;
+            }
+        }
+    }
+
+    /// How the most recent task with this id is doing, or `None` if it was never started
+    pub fn status(&mut self, id: impl Into<TaskId>) -> Option<TaskStatus> {
+        self.tasks.get_mut(&id.into()).map(Task::status)
+    }
+
+    pub fn is_running(&mut self, id: impl Into<TaskId>) -> bool {
+        self.status(id) == Some(TaskStatus::Running)
+    }
+
+    fn spawn(&mut self, id: TaskId, future: impl Future<Output = ()> + 'static) {
+        if self.is_running(id.clone()) {
+            return;
+        }
+
+        let handle = spawn_local(future);
+        self.tasks.insert(
+            id,
+            Task {
+                handle,
+                status: TaskStatus::Running,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tokio::task::LocalSet;
+    use tokio::time::sleep;
+
+    fn run_local(test: impl Future<Output = ()>) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        LocalSet::new().block_on(&runtime, test);
+    }
+
+    #[test]
+    fn reports_status() {
+        run_local(async {
+            let mut tasks = TaskManager::new();
+
+            tasks.run_once("done", async {});
+            tasks.run_once("panics", async { panic!("expected") });
+            tasks.run_once("slow", sleep(Duration::from_secs(10)));
+            sleep(Duration::from_millis(10)).await;
+
+            assert_eq!(tasks.status("done"), Some(TaskStatus::Finished));
+            assert_eq!(tasks.status("panics"), Some(TaskStatus::Panicked));
+            assert_eq!(tasks.status("slow"), Some(TaskStatus::Running));
+            assert_eq!(tasks.status("unknown"), None);
+
+            tasks.abort_task("slow");
+            assert_eq!(tasks.status("slow"), Some(TaskStatus::Cancelled));
+        });
+    }
+
+    #[test]
+    fn ids_keep_tasks_apart() {
+        run_local(async {
+            let mut tasks = TaskManager::new();
+            let count = Rc::new(Cell::new(0));
+
+            for key in [1, 2, 2] {
+                let count = count.clone();
+                tasks.run_once(TaskId::of(&key), async move {
+                    count.set(count.get() + 1);
+                    sleep(Duration::from_secs(10)).await;
+                });
+            }
+            sleep(Duration::from_millis(10)).await;
+
+            assert_eq!(count.get(), 2);
+        });
+    }
+
+    #[test]
+    fn repeats_every_period() {
+        run_local(async {
+            let mut tasks = TaskManager::with_period(Duration::from_millis(10));
+            let count = Rc::new(Cell::new(0));
+
+            let counted = count.clone();
+            tasks.run_task("count", move || {
+                counted.set(counted.get() + 1);
+                async {}
+            });
+            sleep(Duration::from_millis(55)).await;
+            tasks.cancel_all();
+
+            let runs = count.get();
+            assert!((4..=7).contains(&runs), "ran {} times", runs);
+
+            sleep(Duration::from_millis(30)).await;
+            assert_eq!(count.get(), runs);
+            assert_eq!(tasks.status("count"), Some(TaskStatus::Cancelled));
+        });
+    }
+
+    struct TaskRobot {
+        tasks: TaskManager,
+        status_in_teleop: Option<TaskStatus>,
+    }
+
+    impl crate::Robot for TaskRobot {
+        async fn robot_init(&mut self) {}
+
+        async fn autonomous_init(&mut self) {
+            self.tasks.run_once("auto", sleep(Duration::from_secs(10)));
+        }
+
+        async fn teleop_periodic(&mut self) {
+            self.status_in_teleop = self.tasks.status("auto");
+        }
+
+        fn tasks(&mut self) -> Option<&mut TaskManager> {
+            Some(&mut self.tasks)
+        }
+    }
+
+    #[test]
+    fn cancelled_on_mode_change() {
+        let ds = crate::driver_station::SimDriverStation::new();
+        ds.schedule_match(
+            Duration::from_millis(20),
+            Duration::ZERO,
+            Duration::from_millis(20),
+        );
+        crate::driver_station::set_source(ds);
+
+        let mut robot = TaskRobot {
+            tasks: TaskManager::new(),
+            status_in_teleop: None,
+        };
+        crate::Robot::start_competition(
+            &mut robot,
+            tokio::runtime::Runtime::new().unwrap(),
+            LocalSet::new(),
+        );
+
+        assert_eq!(robot.status_in_teleop, Some(TaskStatus::Cancelled));
+    }
+}
@@ -142,6 +142,11 @@ impl Telemetry {
         }
     }
 
+    /// Publish any serializable value as JSON, such as a [`crate::geometry::Pose2d`]
+    pub async fn put_json<T: Serialize>(key: &str, value: &T) {
+        Self::put_string(key, serde_json::to_string(value).unwrap()).await;
+    }
+
     pub async fn put_selector(key: &str, options: Vec<String>) {
         let selector_data = SelectorData {
             options: options.clone(),
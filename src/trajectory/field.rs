@@ -0,0 +1,252 @@
+use uom::si::f64::Length;
+use uom::si::length::meter;
+
+use super::{Feedforward, Path, Pose, PoseSample};
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::AllianceStation;
+
+/// How the red half of the field relates to the blue half
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Turned half way around the center of the field, like 2022 and 2025
+    Rotational,
+
+    /// Mirrored across the center line, like 2023 and 2024
+    Mirrored,
+}
+
+/// The size and symmetry of a field, for flipping paths drawn on the blue side.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use frcrs::alliance_station;
+/// use frcrs::trajectory::{Field, Path};
+///
+/// # fn example(path: Path) {
+/// let field = Field::for_year(2025).unwrap();
+/// let path = path.flipped_for(alliance_station(), &field);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+    pub length: Length,
+    pub width: Length,
+    pub symmetry: Symmetry,
+}
+
+impl Field {
+    pub fn new(length: Length, width: Length, symmetry: Symmetry) -> Self {
+        Self {
+            length,
+            width,
+            symmetry,
+        }
+    }
+
+    /// The field for a game year, from 2022 on
+    pub fn for_year(year: u16) -> Option<Self> {
+        let (length, width, symmetry) = match year {
+            2022 => (16.459, 8.229, Symmetry::Rotational),
+            2023 => (16.541, 8.014, Symmetry::Mirrored),
+            2024 => (16.541, 8.211, Symmetry::Mirrored),
+            2025 => (17.548, 8.052, Symmetry::Rotational),
+            _ => return None,
+        };
+
+        Some(Self::new(
+            Length::new::<meter>(length),
+            Length::new::<meter>(width),
+            symmetry,
+        ))
+    }
+
+    /// Move a point to the same place on the other alliance's side
+    pub fn flip_translation(&self, translation: Translation2d) -> Translation2d {
+        match self.symmetry {
+            Symmetry::Rotational => {
+                Translation2d::new(self.length - translation.x, self.width - translation.y)
+            }
+            Symmetry::Mirrored => Translation2d::new(self.length - translation.x, translation.y),
+        }
+    }
+
+    /// Turn a heading or direction to face the same way relative to the other alliance
+    pub fn flip_rotation(&self, rotation: Rotation2d) -> Rotation2d {
+        match self.symmetry {
+            Symmetry::Rotational => rotation + Rotation2d::from_degrees(180.),
+            Symmetry::Mirrored => Rotation2d::from_components(-rotation.cos(), rotation.sin()),
+        }
+    }
+
+    pub fn flip_pose(&self, pose: Pose2d) -> Pose2d {
+        Pose2d::new(
+            self.flip_translation(pose.translation),
+            self.flip_rotation(pose.rotation),
+        )
+    }
+}
+
+impl Pose {
+    /// The same sample on the other alliance's side, velocities included
+    pub fn flipped(&self, field: &Field) -> Pose {
+        let translation = field.flip_translation(Translation2d::new(self.x, self.y));
+        let heading = field.flip_rotation(Rotation2d::new(self.heading));
+
+        let (velocity_x, velocity_y, angular_velocity) = match field.symmetry {
+            Symmetry::Rotational => (-self.velocity_x, -self.velocity_y, self.angular_velocity),
+            Symmetry::Mirrored => (-self.velocity_x, self.velocity_y, -self.angular_velocity),
+        };
+
+        Pose {
+            x: translation.x,
+            y: translation.y,
+            heading: heading.angle(),
+            angular_velocity,
+            velocity_x,
+            velocity_y,
+        }
+    }
+}
+
+impl Feedforward {
+    fn flipped(&self, symmetry: Symmetry) -> Feedforward {
+        match (self, symmetry) {
+            (Feedforward::Swerve { fx, fy }, Symmetry::Rotational) => Feedforward::Swerve {
+                fx: fx.iter().map(|f| -f).collect(),
+                fy: fy.iter().map(|f| -f).collect(),
+            },
+            // the left and right modules trade places, front left becomes front right
+            (Feedforward::Swerve { fx, fy }, Symmetry::Mirrored) => {
+                let swap = |forces: &[f64], sign: f64| -> Vec<f64> {
+                    if forces.len() != 4 {
+                        return forces.iter().map(|f| f * sign).collect();
+                    }
+                    [1, 0, 3, 2].iter().map(|&i| forces[i] * sign).collect()
+                };
+
+                Feedforward::Swerve {
+                    fx: swap(fx, -1.),
+                    fy: swap(fy, 1.),
+                }
+            }
+            (Feedforward::Differential { .. }, Symmetry::Rotational) => self.clone(),
+            (Feedforward::Differential { vl, vr }, Symmetry::Mirrored) => {
+                Feedforward::Differential { vl: *vr, vr: *vl }
+            }
+        }
+    }
+}
+
+impl Path {
+    /// Flip the whole path onto the other side of the field.
+    ///
+    /// Times, events and splits stay the same.
+    pub fn flipped(&self, field: &Field) -> Path {
+        Path {
+            samples: self
+                .samples
+                .iter()
+                .map(|s| PoseSample {
+                    time: s.time,
+                    pose: s.pose.flipped(field),
+                    feedforward: s.feedforward.flipped(field.symmetry),
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Paths are drawn from the blue side, so this flips the path for the red alliance and
+    /// leaves it alone for blue
+    pub fn flipped_for(&self, alliance: AllianceStation, field: &Field) -> Path {
+        if alliance.red() {
+            self.flipped(field)
+        } else {
+            self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::angle::degree;
+    use uom::si::angular_velocity::radian_per_second;
+    use uom::si::f64::{Angle, AngularVelocity, Velocity};
+    use uom::si::velocity::meter_per_second;
+
+    fn field(symmetry: Symmetry) -> Field {
+        Field::new(
+            Length::new::<meter>(16.),
+            Length::new::<meter>(8.),
+            symmetry,
+        )
+    }
+
+    fn sample() -> Pose {
+        Pose {
+            x: Length::new::<meter>(2.),
+            y: Length::new::<meter>(1.),
+            heading: Angle::new::<degree>(30.),
+            angular_velocity: AngularVelocity::new::<radian_per_second>(1.),
+            velocity_x: Velocity::new::<meter_per_second>(1.),
+            velocity_y: Velocity::new::<meter_per_second>(2.),
+        }
+    }
+
+    #[test]
+    fn rotational_flip() {
+        let flipped = sample().flipped(&field(Symmetry::Rotational));
+
+        assert_eq!(flipped.x.get::<meter>(), 14.);
+        assert_eq!(flipped.y.get::<meter>(), 7.);
+        assert!((flipped.heading.get::<degree>() - -150.).abs() < 1e-9);
+        assert_eq!(flipped.velocity_x.get::<meter_per_second>(), -1.);
+        assert_eq!(flipped.velocity_y.get::<meter_per_second>(), -2.);
+        assert_eq!(flipped.angular_velocity.get::<radian_per_second>(), 1.);
+    }
+
+    #[test]
+    fn mirrored_flip() {
+        let flipped = sample().flipped(&field(Symmetry::Mirrored));
+
+        assert_eq!(flipped.x.get::<meter>(), 14.);
+        assert_eq!(flipped.y.get::<meter>(), 1.);
+        assert!((flipped.heading.get::<degree>() - 150.).abs() < 1e-9);
+        assert_eq!(flipped.velocity_x.get::<meter_per_second>(), -1.);
+        assert_eq!(flipped.velocity_y.get::<meter_per_second>(), 2.);
+        assert_eq!(flipped.angular_velocity.get::<radian_per_second>(), -1.);
+    }
+
+    #[test]
+    fn flips_only_for_red() {
+        let path = Path {
+            samples: vec![PoseSample {
+                time: 0.,
+                pose: sample(),
+                feedforward: Feedforward::Swerve {
+                    fx: vec![1., 2., 3., 4.],
+                    fy: vec![1., 2., 3., 4.],
+                },
+            }],
+            waypoints: Vec::new(),
+            events: Vec::new(),
+            splits: Vec::new(),
+        };
+        let field = Field::for_year(2024).unwrap();
+
+        let blue = path.flipped_for(AllianceStation::BLUE_2, &field);
+        let red = path.flipped_for(AllianceStation::RED_1, &field);
+
+        assert_eq!(blue.samples[0].pose.x.get::<meter>(), 2.);
+        assert!((red.samples[0].pose.x.get::<meter>() - 14.541).abs() < 1e-9);
+        match &red.samples[0].feedforward {
+            Feedforward::Swerve { fx, fy } => {
+                assert_eq!(fx, &[-2., -1., -4., -3.]);
+                assert_eq!(fy, &[2., 1., 4., 3.]);
+            }
+            _ => panic!("expected swerve"),
+        }
+    }
+}
@@ -0,0 +1,553 @@
+use thiserror::Error;
+use uom::si::acceleration::meter_per_second_squared;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{Acceleration, AngularVelocity, Length, Velocity};
+use uom::si::length::meter;
+use uom::si::velocity::meter_per_second;
+
+use super::{Feedforward, Path, Pose, PoseSample};
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GenerationError {
+    #[error("a trajectory needs at least 2 waypoints, got {0}")]
+    TooFewWaypoints(usize),
+
+    #[error("waypoints {0} and {1} are in the same place")]
+    DuplicateWaypoint(usize, usize),
+
+    #[error("max velocity and acceleration must be positive")]
+    InvalidConstraints,
+}
+
+/// How the path between waypoints is shaped
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplineType {
+    /// Passes through every waypoint in the direction of its tangent
+    #[default]
+    Quintic,
+
+    /// Only the first and last tangents are used, the tangents at the waypoints in between are
+    /// picked to keep curvature continuous
+    ClampedCubic,
+}
+
+/// Limits a generated trajectory has to stay within
+#[derive(Clone, Copy, Debug)]
+pub struct TrajectoryConfig {
+    pub max_velocity: Velocity,
+    pub max_acceleration: Acceleration,
+
+    /// Slows the robot down in tight turns, unlimited by default
+    pub max_centripetal_acceleration: Acceleration,
+
+    pub start_velocity: Velocity,
+    pub end_velocity: Velocity,
+    pub spline: SplineType,
+}
+
+impl TrajectoryConfig {
+    /// Start and end at rest, using quintic splines
+    pub fn new(max_velocity: Velocity, max_acceleration: Acceleration) -> Self {
+        Self {
+            max_velocity,
+            max_acceleration,
+            max_centripetal_acceleration: Acceleration::new::<meter_per_second_squared>(
+                f64::INFINITY,
+            ),
+            start_velocity: Velocity::default(),
+            end_velocity: Velocity::default(),
+            spline: SplineType::default(),
+        }
+    }
+}
+
+/// A point the path passes through.
+///
+/// `tangent` is the direction of travel there, and `heading` where the robot faces. The
+/// heading turns evenly with distance between waypoints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Waypoint {
+    pub translation: Translation2d,
+    pub tangent: Rotation2d,
+    pub heading: Rotation2d,
+}
+
+impl Waypoint {
+    pub fn new(translation: Translation2d, tangent: Rotation2d, heading: Rotation2d) -> Self {
+        Self {
+            translation,
+            tangent,
+            heading,
+        }
+    }
+}
+
+/// Face the direction of travel
+impl From<Pose2d> for Waypoint {
+    fn from(pose: Pose2d) -> Self {
+        Self::new(pose.translation, pose.rotation, pose.rotation)
+    }
+}
+
+/// A polynomial in each axis over `t` from 0 to 1, lowest order first
+#[derive(Clone, Copy, Debug)]
+struct Spline {
+    x: [f64; 6],
+    y: [f64; 6],
+}
+
+impl Spline {
+    /// Position, tangent and curvature at `t`
+    fn sample(&self, t: f64) -> (f64, f64, f64, f64, f64) {
+        let eval = |c: &[f64; 6]| {
+            let mut value = 0.;
+            let mut d1 = 0.;
+            let mut d2 = 0.;
+            for i in (0..6).rev() {
+                value = value * t + c[i];
+                if i >= 1 {
+                    d1 = d1 * t + c[i] * i as f64;
+                }
+                if i >= 2 {
+                    d2 = d2 * t + c[i] * (i * (i - 1)) as f64;
+                }
+            }
+            (value, d1, d2)
+        };
+
+        let (x, dx, ddx) = eval(&self.x);
+        let (y, dy, ddy) = eval(&self.y);
+        let curvature = (dx * ddy - dy * ddx) / (dx * dx + dy * dy).powf(1.5);
+
+        (x, y, dx, dy, curvature)
+    }
+
+    fn quintic(p0: [f64; 2], m0: [f64; 2], p1: [f64; 2], m1: [f64; 2]) -> Self {
+        // zero second derivative at both ends
+        let axis = |i: usize| {
+            [
+                p0[i],
+                m0[i],
+                0.,
+                -10. * p0[i] - 6. * m0[i] - 4. * m1[i] + 10. * p1[i],
+                15. * p0[i] + 8. * m0[i] + 7. * m1[i] - 15. * p1[i],
+                -6. * p0[i] - 3. * m0[i] - 3. * m1[i] + 6. * p1[i],
+            ]
+        };
+
+        Self {
+            x: axis(0),
+            y: axis(1),
+        }
+    }
+
+    fn cubic(p0: [f64; 2], m0: [f64; 2], p1: [f64; 2], m1: [f64; 2]) -> Self {
+        let axis = |i: usize| {
+            [
+                p0[i],
+                m0[i],
+                -3. * p0[i] - 2. * m0[i] - m1[i] + 3. * p1[i],
+                2. * p0[i] + m0[i] + m1[i] - 2. * p1[i],
+                0.,
+                0.,
+            ]
+        };
+
+        Self {
+            x: axis(0),
+            y: axis(1),
+        }
+    }
+}
+
+fn point(translation: Translation2d) -> [f64; 2] {
+    [translation.x.get::<meter>(), translation.y.get::<meter>()]
+}
+
+/// Tangent vector scaled to the distance to the neighbouring waypoint
+fn scaled_tangent(tangent: Rotation2d, scale: f64) -> [f64; 2] {
+    [tangent.cos() * scale * 1.2, tangent.sin() * scale * 1.2]
+}
+
+fn splines(waypoints: &[Waypoint], spline: SplineType) -> Vec<Spline> {
+    let points = waypoints
+        .iter()
+        .map(|w| point(w.translation))
+        .collect::<Vec<_>>();
+    let gap = |i: usize| {
+        waypoints[i]
+            .translation
+            .distance(waypoints[i + 1].translation)
+            .get::<meter>()
+    };
+
+    match spline {
+        SplineType::Quintic => (0..points.len() - 1)
+            .map(|i| {
+                let scale = gap(i);
+                Spline::quintic(
+                    points[i],
+                    scaled_tangent(waypoints[i].tangent, scale),
+                    points[i + 1],
+                    scaled_tangent(waypoints[i + 1].tangent, scale),
+                )
+            })
+            .collect(),
+        SplineType::ClampedCubic => {
+            let n = points.len();
+            let mut tangents = vec![[0.; 2]; n];
+            tangents[0] = scaled_tangent(waypoints[0].tangent, gap(0));
+            tangents[n - 1] = scaled_tangent(waypoints[n - 1].tangent, gap(n - 2));
+
+            // continuous second derivative at each interior waypoint gives
+            // m[i - 1] + 4 m[i] + m[i + 1] = 3 (p[i + 1] - p[i - 1]), solved with the Thomas algorithm
+            for axis in 0..2 {
+                let interior = n - 2;
+                let mut upper = vec![0.; interior];
+                let mut rhs = vec![0.; interior];
+
+                for k in 0..interior {
+                    let i = k + 1;
+                    let mut r = 3. * (points[i + 1][axis] - points[i - 1][axis]);
+                    if k == 0 {
+                        r -= tangents[0][axis];
+                    }
+                    if k == interior - 1 {
+                        r -= tangents[n - 1][axis];
+                    }
+
+                    let (previous_upper, previous_rhs) = if k == 0 {
+                        (0., 0.)
+                    } else {
+                        (upper[k - 1], rhs[k - 1])
+                    };
+                    let pivot = 4. - previous_upper;
+                    upper[k] = 1. / pivot;
+                    rhs[k] = (r - previous_rhs) / pivot;
+                }
+
+                for k in (0..interior).rev() {
+                    let next = if k + 1 < interior {
+                        tangents[k + 2][axis]
+                    } else {
+                        0.
+                    };
+                    tangents[k + 1][axis] = rhs[k] - upper[k] * next;
+                }
+            }
+
+            (0..n - 1)
+                .map(|i| Spline::cubic(points[i], tangents[i], points[i + 1], tangents[i + 1]))
+                .collect()
+        }
+    }
+}
+
+struct PathPoint {
+    x: f64,
+    y: f64,
+    tangent: Rotation2d,
+    curvature: f64,
+    distance: f64,
+    /// Which spline the point is on
+    segment: usize,
+}
+
+const MAX_STEP: f64 = 0.05;
+const MAX_TURN: f64 = 0.05;
+
+/// Split `spline` until each step is short and nearly straight
+fn subdivide(spline: &Spline, segment: usize, t0: f64, t1: f64, points: &mut Vec<PathPoint>) {
+    let (x0, y0, dx0, dy0, _) = spline.sample(t0);
+    let (x1, y1, dx1, dy1, curvature) = spline.sample(t1);
+    let tangent0 = Rotation2d::from_components(dx0, dy0);
+    let tangent1 = Rotation2d::from_components(dx1, dy1);
+    let step = (x1 - x0).hypot(y1 - y0);
+
+    if (step > MAX_STEP || (tangent1 - tangent0).radians().abs() > MAX_TURN) && t1 - t0 > 1e-4 {
+        let mid = (t0 + t1) / 2.;
+        subdivide(spline, segment, t0, mid, points);
+        subdivide(spline, segment, mid, t1, points);
+        return;
+    }
+
+    let distance = points.last().map_or(0., |p| p.distance) + step;
+    points.push(PathPoint {
+        x: x1,
+        y: y1,
+        tangent: tangent1,
+        curvature,
+        distance,
+        segment,
+    });
+}
+
+impl Path {
+    /// Fit splines through `waypoints` and time them to stay within `config`.
+    ///
+    /// The result samples like a Choreo path, without module forces.
+    ///
+    /// ```rust
+    /// use frcrs::geometry::{Pose2d, Rotation2d, Translation2d};
+    /// use frcrs::trajectory::{Path, TrajectoryConfig, Waypoint};
+    /// use uom::si::acceleration::meter_per_second_squared;
+    /// use uom::si::f64::{Acceleration, Velocity};
+    /// use uom::si::velocity::meter_per_second;
+    ///
+    /// let config = TrajectoryConfig::new(
+    ///     Velocity::new::<meter_per_second>(3.),
+    ///     Acceleration::new::<meter_per_second_squared>(2.),
+    /// );
+    ///
+    /// let path = Path::generate(
+    ///     &[
+    ///         Pose2d::default().into(),
+    ///         Waypoint::new(
+    ///             Translation2d::from_meters(2., 1.),
+    ///             Rotation2d::from_degrees(0.),
+    ///             Rotation2d::from_degrees(90.),
+    ///         ),
+    ///     ],
+    ///     &config,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(path.length().value > 0.);
+    /// ```
+    pub fn generate(
+        waypoints: &[Waypoint],
+        config: &TrajectoryConfig,
+    ) -> Result<Path, GenerationError> {
+        if waypoints.len() < 2 {
+            return Err(GenerationError::TooFewWaypoints(waypoints.len()));
+        }
+        for i in 0..waypoints.len() - 1 {
+            if waypoints[i]
+                .translation
+                .distance(waypoints[i + 1].translation)
+                .get::<meter>()
+                < 1e-6
+            {
+                return Err(GenerationError::DuplicateWaypoint(i, i + 1));
+            }
+        }
+
+        let max_velocity = config.max_velocity.get::<meter_per_second>();
+        let max_acceleration = config.max_acceleration.get::<meter_per_second_squared>();
+        let max_centripetal = config
+            .max_centripetal_acceleration
+            .get::<meter_per_second_squared>();
+        if max_velocity <= 0. || max_acceleration <= 0. || max_centripetal <= 0. {
+            return Err(GenerationError::InvalidConstraints);
+        }
+
+        let splines = splines(waypoints, config.spline);
+        let (x, y, dx, dy, curvature) = splines[0].sample(0.);
+        let mut points = vec![PathPoint {
+            x,
+            y,
+            tangent: Rotation2d::from_components(dx, dy),
+            curvature,
+            distance: 0.,
+            segment: 0,
+        }];
+        // distance along the path where each spline starts, and where the last one ends
+        let mut starts = Vec::with_capacity(splines.len() + 1);
+        for (segment, spline) in splines.iter().enumerate() {
+            starts.push(points.last().map_or(0., |p| p.distance));
+            subdivide(spline, segment, 0., 1., &mut points);
+        }
+        starts.push(points.last().map_or(0., |p| p.distance));
+
+        // fastest speed at each point, limited by curvature and accelerating from the start
+        let mut speeds = points
+            .iter()
+            .map(|p| max_velocity.min((max_centripetal / p.curvature.abs()).sqrt()))
+            .collect::<Vec<_>>();
+        speeds[0] = speeds[0].min(config.start_velocity.get::<meter_per_second>());
+        let last = speeds.len() - 1;
+        speeds[last] = speeds[last].min(config.end_velocity.get::<meter_per_second>());
+
+        for i in 1..speeds.len() {
+            let ds = points[i].distance - points[i - 1].distance;
+            speeds[i] = speeds[i].min((speeds[i - 1].powi(2) + 2. * max_acceleration * ds).sqrt());
+        }
+        for i in (0..last).rev() {
+            let ds = points[i + 1].distance - points[i].distance;
+            speeds[i] = speeds[i].min((speeds[i + 1].powi(2) + 2. * max_acceleration * ds).sqrt());
+        }
+
+        // the heading turns evenly with distance along each spline
+        let heading_rate = |segment: usize| {
+            let turn = (waypoints[segment + 1].heading - waypoints[segment].heading).radians();
+            (turn, turn / (starts[segment + 1] - starts[segment]))
+        };
+
+        let mut time = 0.;
+        let mut samples = Vec::with_capacity(points.len());
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                let ds = p.distance - points[i - 1].distance;
+                let average = (speeds[i] + speeds[i - 1]) / 2.;
+                if average > 0. {
+                    time += ds / average;
+                }
+            }
+
+            let (turn, rate) = heading_rate(p.segment);
+            let progress = ((p.distance - starts[p.segment])
+                / (starts[p.segment + 1] - starts[p.segment]))
+                .clamp(0., 1.);
+            let heading = waypoints[p.segment].heading + Rotation2d::from_radians(turn * progress);
+
+            samples.push(PoseSample {
+                time,
+                pose: Pose {
+                    x: Length::new::<meter>(p.x),
+                    y: Length::new::<meter>(p.y),
+                    heading: heading.angle(),
+                    angular_velocity: AngularVelocity::new::<radian_per_second>(rate * speeds[i]),
+                    velocity_x: Velocity::new::<meter_per_second>(speeds[i] * p.tangent.cos()),
+                    velocity_y: Velocity::new::<meter_per_second>(speeds[i] * p.tangent.sin()),
+                },
+                feedforward: Feedforward::Swerve {
+                    fx: Vec::new(),
+                    fy: Vec::new(),
+                },
+            });
+        }
+
+        Ok(Path {
+            waypoints: vec![time],
+            samples,
+            events: Vec::new(),
+            splits: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::time::second;
+
+    fn config(max_velocity: f64, max_acceleration: f64) -> TrajectoryConfig {
+        TrajectoryConfig::new(
+            Velocity::new::<meter_per_second>(max_velocity),
+            Acceleration::new::<meter_per_second_squared>(max_acceleration),
+        )
+    }
+
+    fn pose(x: f64, y: f64, degrees: f64) -> Waypoint {
+        Pose2d::new(
+            Translation2d::from_meters(x, y),
+            Rotation2d::from_degrees(degrees),
+        )
+        .into()
+    }
+
+    #[test]
+    fn straight_line_is_a_trapezoid() {
+        let path = Path::generate(&[pose(0., 0., 0.), pose(4., 0., 0.)], &config(3., 2.)).unwrap();
+
+        // too short to reach full speed, peaks at sqrt(2 * 2)
+        let peak = 8f64.sqrt();
+        assert!((path.length().get::<second>() - 2. * peak / 2.).abs() < 0.05);
+
+        let end = path.get(path.length());
+        assert!((end.x.get::<meter>() - 4.).abs() < 1e-9);
+        assert_eq!(end.velocity_x.get::<meter_per_second>(), 0.);
+
+        let middle = path.get(path.length() / 2.);
+        assert!((middle.velocity_x.get::<meter_per_second>() - peak).abs() < 0.05);
+        assert!(middle.velocity_y.get::<meter_per_second>().abs() < 1e-9);
+    }
+
+    #[test]
+    fn heading_turns_with_distance() {
+        let path = Path::generate(
+            &[
+                pose(0., 0., 0.),
+                Waypoint::new(
+                    Translation2d::from_meters(2., 0.),
+                    Rotation2d::default(),
+                    Rotation2d::from_degrees(90.),
+                ),
+            ],
+            &config(1., 1.),
+        )
+        .unwrap();
+
+        let end = path.get(path.length());
+        assert!((end.heading.get::<uom::si::angle::degree>() - 90.).abs() < 1e-6);
+
+        let middle = path.get(path.length() / 2.);
+        assert!((middle.heading.get::<uom::si::angle::degree>() - 45.).abs() < 0.5);
+        assert!(middle.angular_velocity.get::<radian_per_second>() > 0.);
+    }
+
+    #[test]
+    fn centripetal_limit_slows_turns() {
+        let waypoints = [pose(0., 0., 0.), pose(2., 2., 90.)];
+        let fast = Path::generate(&waypoints, &config(4., 4.)).unwrap();
+
+        let mut limited = config(4., 4.);
+        limited.max_centripetal_acceleration = Acceleration::new::<meter_per_second_squared>(0.5);
+        let slow = Path::generate(&waypoints, &limited).unwrap();
+
+        assert!(slow.length() > fast.length());
+        for i in 1..slow.samples.len() - 1 {
+            let (x, y) = (
+                slow.samples[i].pose.velocity_x.get::<meter_per_second>(),
+                slow.samples[i].pose.velocity_y.get::<meter_per_second>(),
+            );
+            assert!(x.hypot(y) <= 4. + 1e-9);
+        }
+    }
+
+    #[test]
+    fn clamped_cubic_passes_through_waypoints() {
+        let mut config = config(2., 2.);
+        config.spline = SplineType::ClampedCubic;
+
+        let path = Path::generate(
+            &[
+                pose(0., 0., 0.),
+                pose(1., 1., 0.),
+                pose(2., 0., 0.),
+                pose(3., 0., 0.),
+            ],
+            &config,
+        )
+        .unwrap();
+
+        let closest = |x: f64, y: f64| {
+            path.samples
+                .iter()
+                .map(|s| (s.pose.x.get::<meter>() - x).hypot(s.pose.y.get::<meter>() - y))
+                .fold(f64::INFINITY, f64::min)
+        };
+        assert!(closest(1., 1.) < 1e-9);
+        assert!(closest(2., 0.) < 1e-9);
+
+        let end = path.get(path.length());
+        assert!((end.x.get::<meter>() - 3.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(
+            Path::generate(&[pose(0., 0., 0.)], &config(1., 1.)).unwrap_err(),
+            GenerationError::TooFewWaypoints(1)
+        );
+        assert_eq!(
+            Path::generate(&[pose(0., 0., 0.), pose(0., 0., 90.)], &config(1., 1.)).unwrap_err(),
+            GenerationError::DuplicateWaypoint(0, 1)
+        );
+        assert_eq!(
+            Path::generate(&[pose(0., 0., 0.), pose(1., 0., 0.)], &config(0., 1.)).unwrap_err(),
+            GenerationError::InvalidConstraints
+        );
+    }
+}
@@ -0,0 +1,976 @@
+#![allow(dead_code)]
+
+use crate::drive::DifferentialDriveWheelSpeeds;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::ops::{Add, Mul, Sub};
+use uom::si::{
+    angle::radian,
+    angular_velocity::radian_per_second,
+    f64::{Angle, AngularVelocity, Force, Length, Time, Velocity},
+    force::newton,
+    length::meter,
+    time::second,
+    velocity::meter_per_second,
+};
+
+mod field;
+mod generator;
+
+pub use field::*;
+pub use generator::*;
+
+/// The newest `.traj` schema version [`ChoreoTrajectory::from_json`] understands
+pub const TRAJ_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+pub struct ChoreoTrajectory {
+    pub name: String,
+    pub version: u32,
+    #[serde(default)]
+    pub snapshot: Snapshot,
+    pub trajectory: Trajectory,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+impl ChoreoTrajectory {
+    /// Parse a `.traj` file, migrating older schemas to the current one.
+    ///
+    /// Files from before Choreo versioned its schema (a top level `samples` list) are migrated,
+    /// files newer than [`TRAJ_VERSION`] are rejected.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let value = serde_json::from_str::<Value>(json)?;
+
+        serde_json::from_value(migrate(value)?)
+    }
+}
+
+fn migrate(value: Value) -> Result<Value, serde_json::Error> {
+    match value.get("version") {
+        None => Ok(migrate_unversioned(value)),
+        // every supported version gets an arm, so bumping TRAJ_VERSION forces a decision about older files
+        Some(Value::Number(version)) => match version.as_u64() {
+            // 0 through 2 only differ from 3 in fields this parser ignores or defaults
+            Some(0) => Ok(value),
+            Some(1) => Ok(value),
+            Some(2) => Ok(value),
+            Some(3) => Ok(value),
+            _ => Err(serde_json::Error::custom(format!(
+                "unsupported trajectory version {}, newest supported is {}",
+                version, TRAJ_VERSION
+            ))),
+        },
+        Some(version) => Err(serde_json::Error::custom(format!(
+            "unsupported trajectory version {}",
+            version
+        ))),
+    }
+}
+
+/// Convert the unversioned 2024 layout, with camel case sample fields and `eventMarkers`
+fn migrate_unversioned(value: Value) -> Value {
+    const RENAMES: [(&str, &str); 6] = [
+        ("timestamp", "t"),
+        ("velocityX", "vx"),
+        ("velocityY", "vy"),
+        ("angularVelocity", "omega"),
+        ("moduleForcesX", "fx"),
+        ("moduleForcesY", "fy"),
+    ];
+
+    let samples = value
+        .get("samples")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|sample| {
+            let mut sample = sample.as_object().cloned().unwrap_or_default();
+            for (old, new) in RENAMES {
+                if let Some(field) = sample.remove(old) {
+                    sample.insert(new.to_string(), field);
+                }
+            }
+            Value::Object(sample)
+        })
+        .collect::<Vec<_>>();
+
+    let events = value
+        .get("eventMarkers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|marker| {
+            let name = marker
+                .pointer("/command/data/name")
+                .and_then(Value::as_str)
+                .unwrap_or("marker");
+
+            json!({
+                "name": name,
+                "from": { "targetTimestamp": marker.get("timestamp") },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "name": "",
+        "version": TRAJ_VERSION,
+        "trajectory": {
+            "waypoints": [],
+            "splits": [],
+            "samples": samples,
+        },
+        "events": events,
+        "snapshot": {},
+    })
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Snapshot {
+    #[serde(default)]
+    pub waypoints: Vec<SnapshotWaypoint>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotWaypoint {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+    pub split: bool,
+}
+
+/// An event marker, placed relative to a waypoint
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Event {
+    pub name: String,
+    pub from: EventTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTime {
+    /// Index of the waypoint the marker is attached to
+    #[serde(default)]
+    pub target: Option<usize>,
+
+    /// Time of the target waypoint in seconds, as of the last time the path was generated
+    #[serde(default)]
+    pub target_timestamp: Option<f64>,
+
+    #[serde(default)]
+    pub offset: Expression,
+}
+
+/// A value Choreo lets the user write as an expression, like `"0.5 s"`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Expression {
+    pub exp: String,
+    pub val: f64,
+}
+
+impl Event {
+    /// Seconds from the start of the path, or `None` if the marker isn't attached to a
+    /// generated waypoint
+    pub fn timestamp(&self) -> Option<f64> {
+        self.from
+            .target_timestamp
+            .map(|target| target + self.from.offset.val)
+    }
+}
+
+/// A named point in time along a [`Path`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventMarker {
+    pub name: String,
+    pub timestamp: Time,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "RawTrajectory")]
+pub struct Trajectory {
+    #[serde(rename = "sampleType", skip_serializing_if = "Option::is_none")]
+    pub sample_type: Option<SampleType>,
+    pub waypoints: Vec<f64>,
+    pub splits: Vec<usize>,
+    pub samples: Samples,
+}
+
+/// [`Trajectory`] before its samples are parsed, so `sampleType` can pick how to parse them
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTrajectory {
+    #[serde(default)]
+    sample_type: Option<SampleType>,
+    #[serde(default)]
+    waypoints: Vec<f64>,
+    #[serde(default)]
+    splits: Vec<usize>,
+    samples: Value,
+}
+
+impl TryFrom<RawTrajectory> for Trajectory {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawTrajectory) -> Result<Self, Self::Error> {
+        let invalid = |kind: SampleType, error: serde_json::Error| {
+            serde_json::Error::custom(format!("invalid {:?} samples: {}", kind, error))
+        };
+
+        let samples = match raw.sample_type {
+            Some(kind @ SampleType::Swerve) => {
+                Samples::Swerve(serde_json::from_value(raw.samples).map_err(|e| invalid(kind, e))?)
+            }
+            Some(kind @ SampleType::Differential) => Samples::Differential(
+                serde_json::from_value(raw.samples).map_err(|e| invalid(kind, e))?,
+            ),
+            // files without a sample type are told apart by their fields
+            None => serde_json::from_value(raw.samples)?,
+        };
+
+        Ok(Self {
+            sample_type: raw.sample_type,
+            waypoints: raw.waypoints,
+            splits: raw.splits,
+            samples,
+        })
+    }
+}
+
+/// The drivetrain a trajectory's samples were generated for
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SampleType {
+    Swerve,
+    Differential,
+}
+
+/// Samples for a swerve or differential drive
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Samples {
+    Swerve(Vec<Sample>),
+    Differential(Vec<DifferentialSample>),
+}
+
+/// A swerve sample, forces are field relative, in newtons, one per module
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Sample {
+    pub t: f64,
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+
+    #[serde(rename = "vx")]
+    pub velocity_x: f64,
+
+    #[serde(rename = "vy")]
+    pub velocity_y: f64,
+
+    #[serde(rename = "omega")]
+    pub angular_velocity: f64,
+
+    #[serde(default)]
+    pub ax: f64,
+    #[serde(default)]
+    pub ay: f64,
+    #[serde(default)]
+    pub alpha: f64,
+    #[serde(default)]
+    pub fx: Vec<f64>,
+    #[serde(default)]
+    pub fy: Vec<f64>,
+}
+
+/// A differential sample, forces are in newtons along each side
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DifferentialSample {
+    pub t: f64,
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+    pub vl: f64,
+    pub vr: f64,
+
+    #[serde(rename = "omega")]
+    pub angular_velocity: f64,
+
+    #[serde(default)]
+    pub al: f64,
+    #[serde(default)]
+    pub ar: f64,
+    #[serde(default)]
+    pub alpha: f64,
+    #[serde(default)]
+    pub fl: f64,
+    #[serde(default)]
+    pub fr: f64,
+}
+
+/// The force one swerve module should apply, field relative
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModuleForce {
+    pub x: Force,
+    pub y: Force,
+}
+
+#[derive(Clone, Debug)]
+pub struct Path {
+    samples: Vec<PoseSample>,
+    waypoints: Vec<f64>,
+    events: Vec<EventMarker>,
+    splits: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct PoseSample {
+    time: f64,
+    pose: Pose,
+    feedforward: Feedforward,
+}
+
+#[derive(Clone, Debug)]
+enum Feedforward {
+    Swerve { fx: Vec<f64>, fy: Vec<f64> },
+    Differential { vl: f64, vr: f64 },
+}
+
+impl From<Sample> for PoseSample {
+    fn from(mut s: Sample) -> Self {
+        Self {
+            time: s.t,
+            feedforward: Feedforward::Swerve {
+                fx: std::mem::take(&mut s.fx),
+                fy: std::mem::take(&mut s.fy),
+            },
+            pose: s.into(),
+        }
+    }
+}
+
+impl From<DifferentialSample> for PoseSample {
+    fn from(s: DifferentialSample) -> Self {
+        let speed = (s.vl + s.vr) / 2.;
+
+        Self {
+            time: s.t,
+            pose: Pose {
+                x: Length::new::<meter>(s.x),
+                y: Length::new::<meter>(s.y),
+                heading: Angle::new::<radian>(s.heading),
+                angular_velocity: AngularVelocity::new::<radian_per_second>(s.angular_velocity),
+                velocity_x: Velocity::new::<meter_per_second>(speed * s.heading.cos()),
+                velocity_y: Velocity::new::<meter_per_second>(speed * s.heading.sin()),
+            },
+            feedforward: Feedforward::Differential { vl: s.vl, vr: s.vr },
+        }
+    }
+}
+
+impl Path {
+    pub fn from_trajectory(json: &str) -> Result<Self, serde_json::Error> {
+        let choreo = ChoreoTrajectory::from_json(json)?;
+
+        let mut samples: Vec<PoseSample> = match choreo.trajectory.samples {
+            Samples::Swerve(samples) => samples.into_iter().map(PoseSample::from).collect(),
+            Samples::Differential(samples) => samples.into_iter().map(PoseSample::from).collect(),
+        };
+
+        if samples.is_empty() {
+            return Err(serde_json::Error::custom("trajectory has no samples"));
+        }
+
+        samples.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+        let mut events = choreo
+            .events
+            .into_iter()
+            .filter_map(|event| {
+                Some(EventMarker {
+                    timestamp: Time::new::<second>(event.timestamp()?),
+                    name: event.name,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        events.sort_by(|a, b| a.timestamp.partial_cmp(&b.timestamp).unwrap());
+
+        let mut splits = choreo
+            .trajectory
+            .splits
+            .into_iter()
+            .filter(|&i| i < samples.len())
+            .collect::<Vec<_>>();
+
+        splits.sort_unstable();
+        splits.dedup();
+
+        // splits are sample indices, so the stops between segments are at those samples' times
+        let mut waypoints = splits.iter().map(|&i| samples[i].time).collect::<Vec<_>>();
+
+        let end = samples.last().unwrap().time;
+        if waypoints.last() != Some(&end) {
+            waypoints.push(end);
+        }
+
+        Ok(Self {
+            samples,
+            waypoints,
+            events,
+            splits,
+        })
+    }
+
+    pub fn get(&self, time: Time) -> Pose {
+        let (a, b, progress) = self.bracket(time);
+
+        a.pose.lerp(&b.pose, progress)
+    }
+
+    /// Whether the path was generated for a differential drive
+    pub fn is_differential(&self) -> bool {
+        matches!(
+            self.samples[0].feedforward,
+            Feedforward::Differential { .. }
+        )
+    }
+
+    /// Wheel speeds at `time`, or `None` for a swerve path
+    pub fn wheel_speeds(&self, time: Time) -> Option<DifferentialDriveWheelSpeeds> {
+        let (a, b, progress) = self.bracket(time);
+
+        match (&a.feedforward, &b.feedforward) {
+            (
+                Feedforward::Differential { vl, vr },
+                Feedforward::Differential { vl: vl_b, vr: vr_b },
+            ) => Some(DifferentialDriveWheelSpeeds::new(
+                Velocity::new::<meter_per_second>(lerp(*vl, *vl_b, progress)),
+                Velocity::new::<meter_per_second>(lerp(*vr, *vr_b, progress)),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Forces for each swerve module at `time`, in the order of the modules in the Choreo
+    /// config. Empty for a differential path, or one generated without forces.
+    pub fn module_forces(&self, time: Time) -> Vec<ModuleForce> {
+        let (a, b, progress) = self.bracket(time);
+
+        match (&a.feedforward, &b.feedforward) {
+            (Feedforward::Swerve { fx, fy }, Feedforward::Swerve { fx: fx_b, fy: fy_b }) => fx
+                .iter()
+                .zip(fy)
+                .zip(fx_b.iter().zip(fy_b))
+                .map(|((&x, &y), (&x_b, &y_b))| ModuleForce {
+                    x: Force::new::<newton>(lerp(x, x_b, progress)),
+                    y: Force::new::<newton>(lerp(y, y_b, progress)),
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The samples either side of `time`, and how far between them it is
+    fn bracket(&self, time: Time) -> (&PoseSample, &PoseSample, f64) {
+        let t = time.get::<second>();
+
+        match self
+            .samples
+            .binary_search_by(|s| s.time.partial_cmp(&t).unwrap())
+        {
+            Ok(i) => (&self.samples[i], &self.samples[i], 0.),
+            Err(0) => (&self.samples[0], &self.samples[0], 0.),
+            Err(i) if i >= self.samples.len() => {
+                let last = self.samples.last().unwrap();
+                (last, last, 0.)
+            }
+            Err(i) => {
+                let a = &self.samples[i - 1];
+                let b = &self.samples[i];
+
+                (a, b, (t - a.time) / (b.time - a.time))
+            }
+        }
+    }
+
+    pub fn length(&self) -> Time {
+        Time::new::<second>(self.samples.last().unwrap().time)
+    }
+
+    /// Times of the start of each split and the end of the path, in seconds
+    pub fn waypoints(&self) -> &[f64] {
+        &self.waypoints
+    }
+
+    /// Every event marker on the path, in order
+    pub fn events(&self) -> &[EventMarker] {
+        &self.events
+    }
+
+    /// Events at or after `from` and before `to`.
+    ///
+    /// Passing the previous and current time each loop reports every event exactly once. Events
+    /// at the very end of the path fire once `to` is past [`Path::length`].
+    pub fn events_between(&self, from: Time, to: Time) -> impl Iterator<Item = &EventMarker> {
+        self.events
+            .iter()
+            .filter(move |event| event.timestamp >= from && event.timestamp < to)
+    }
+
+    /// Sample indices the path is split at
+    pub fn splits(&self) -> &[usize] {
+        &self.splits
+    }
+
+    /// Break the path into one path per split, so the robot can stop between them.
+    ///
+    /// Each segment starts at time zero and keeps the waypoints and events that fall within it.
+    /// A path without splits is returned whole.
+    pub fn segments(&self) -> Vec<Path> {
+        let mut bounds = self.splits.clone();
+        if bounds.first() != Some(&0) {
+            bounds.insert(0, 0);
+        }
+        bounds.push(self.samples.len().saturating_sub(1));
+
+        bounds
+            .windows(2)
+            .filter(|bound| bound[0] < bound[1])
+            .map(|bound| self.segment(bound[0], bound[1]))
+            .collect()
+    }
+
+    fn segment(&self, first: usize, last: usize) -> Path {
+        let start = self.samples[first].time;
+        let end = self.samples[last].time;
+        let is_last = last == self.samples.len() - 1;
+        let contains = |t: f64| t >= start && (t < end || (is_last && t <= end));
+
+        Path {
+            samples: self.samples[first..=last]
+                .iter()
+                .map(|s| PoseSample {
+                    time: s.time - start,
+                    ..s.clone()
+                })
+                .collect(),
+            waypoints: self
+                .waypoints
+                .iter()
+                .filter(|&&t| t >= start && t <= end)
+                .map(|t| t - start)
+                .collect(),
+            events: self
+                .events
+                .iter()
+                .filter(|e| contains(e.timestamp.get::<second>()))
+                .map(|e| EventMarker {
+                    name: e.name.clone(),
+                    timestamp: e.timestamp - Time::new::<second>(start),
+                })
+                .collect(),
+            splits: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pose {
+    pub x: Length,
+    pub y: Length,
+    pub heading: Angle,
+    pub angular_velocity: AngularVelocity,
+    pub velocity_x: Velocity,
+    pub velocity_y: Velocity,
+}
+
+impl Pose {
+    fn lerp(&self, other: &Pose, t: f64) -> Pose {
+        Pose {
+            x: lerp(self.x, other.x, t),
+            y: lerp(self.y, other.y, t),
+
+            heading: Angle::new::<radian>(lerp_angle(
+                self.heading.get::<radian>(),
+                other.heading.get::<radian>(),
+                t,
+            )),
+
+            angular_velocity: lerp(self.angular_velocity, other.angular_velocity, t),
+            velocity_x: lerp(self.velocity_x, other.velocity_x, t),
+            velocity_y: lerp(self.velocity_y, other.velocity_y, t),
+        }
+    }
+
+    /// The position and heading, without the velocities
+    pub fn to_pose2d(&self) -> Pose2d {
+        Pose2d::new(
+            Translation2d::new(self.x, self.y),
+            Rotation2d::new(self.heading),
+        )
+    }
+
+    #[deprecated(note = "use `Pose::flipped` with a `Field`, which also handles mirrored fields")]
+    pub fn mirror(&self, half_field_x: Length, half_field_y: Length) -> Pose {
+        Pose {
+            x: half_field_x * 2.0 - self.x,
+            y: half_field_y * 2.0 - self.y,
+
+            heading: Angle::new::<radian>(self.heading.get::<radian>() + std::f64::consts::PI),
+
+            angular_velocity: -self.angular_velocity,
+
+            velocity_x: -self.velocity_x,
+            velocity_y: -self.velocity_y,
+        }
+    }
+}
+
+fn lerp<T>(a: T, b: T, t: f64) -> T
+where
+    T: Sub<T, Output = T> + Add<T, Output = T> + Mul<f64, Output = T> + Clone,
+{
+    a.clone() + (b - a) * t
+}
+
+fn lerp_angle(a: f64, b: f64, t: f64) -> f64 {
+    let mut diff = b - a;
+
+    while diff > std::f64::consts::PI {
+        diff -= 2.0 * std::f64::consts::PI;
+    }
+
+    while diff < -std::f64::consts::PI {
+        diff += 2.0 * std::f64::consts::PI;
+    }
+
+    a + diff * t
+}
+
+impl From<Sample> for Pose {
+    fn from(s: Sample) -> Self {
+        Self {
+            x: Length::new::<meter>(s.x),
+            y: Length::new::<meter>(s.y),
+            heading: Angle::new::<radian>(s.heading),
+            angular_velocity: AngularVelocity::new::<radian_per_second>(s.angular_velocity),
+            velocity_x: Velocity::new::<meter_per_second>(s.velocity_x),
+            velocity_y: Velocity::new::<meter_per_second>(s.velocity_y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::{length::meter, time::second};
+
+    fn fake_path() -> &'static str {
+        r#"
+        {
+            "name": "test_path",
+            "version": 3,
+            "snapshot": {
+                "waypoints": []
+            },
+            "events": [],
+            "trajectory": {
+                "waypoints": [0.0, 1.0],
+                "splits": [0],
+                "samples": [
+                    {
+                        "t": 0.0,
+                        "x": 0.0,
+                        "y": 0.0,
+                        "heading": 0.0,
+                        "vx": 0.0,
+                        "vy": 0.0,
+                        "omega": 0.0
+                    },
+                    {
+                        "t": 1.0,
+                        "x": 1.0,
+                        "y": 2.0,
+                        "heading": 1.0,
+                        "vx": 1.0,
+                        "vy": 2.0,
+                        "omega": 0.5
+                    }
+                ]
+            }
+        }
+        "#
+    }
+
+    fn split_path() -> &'static str {
+        r#"
+        {
+            "name": "split_path",
+            "version": 3,
+            "snapshot": {
+                "waypoints": []
+            },
+            "events": [
+                {
+                    "name": "intake",
+                    "from": {
+                        "target": 1,
+                        "targetTimestamp": 1.0,
+                        "offset": { "exp": "-0.5 s", "val": -0.5 }
+                    },
+                    "event": null
+                },
+                {
+                    "name": "score",
+                    "from": {
+                        "target": 2,
+                        "targetTimestamp": 2.0,
+                        "offset": { "exp": "0 s", "val": 0.0 }
+                    },
+                    "event": null
+                },
+                {
+                    "name": "unplaced",
+                    "from": { "target": null, "targetTimestamp": null }
+                }
+            ],
+            "trajectory": {
+                "waypoints": [0.0, 1.0, 2.0],
+                "splits": [0, 1],
+                "samples": [
+                    { "t": 0.0, "x": 0.0, "y": 0.0, "heading": 0.0, "vx": 0.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 1.0, "x": 1.0, "y": 0.0, "heading": 0.0, "vx": 1.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 2.0, "x": 2.0, "y": 0.0, "heading": 0.0, "vx": 0.0, "vy": 0.0, "omega": 0.0 }
+                ]
+            }
+        }
+        "#
+    }
+
+    /// More samples than waypoints, so split indices only make sense as sample indices
+    fn dense_path() -> &'static str {
+        r#"
+        {
+            "name": "dense_path",
+            "version": 3,
+            "snapshot": {
+                "waypoints": []
+            },
+            "events": [],
+            "trajectory": {
+                "waypoints": [0.0, 1.0, 2.0],
+                "splits": [0, 2],
+                "samples": [
+                    { "t": 0.0, "x": 0.0, "y": 0.0, "heading": 0.0, "vx": 0.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 0.5, "x": 0.5, "y": 0.0, "heading": 0.0, "vx": 1.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 1.0, "x": 1.0, "y": 0.0, "heading": 0.0, "vx": 0.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 1.5, "x": 1.5, "y": 0.0, "heading": 0.0, "vx": 1.0, "vy": 0.0, "omega": 0.0 },
+                    { "t": 2.0, "x": 2.0, "y": 0.0, "heading": 0.0, "vx": 0.0, "vy": 0.0, "omega": 0.0 }
+                ]
+            }
+        }
+        "#
+    }
+
+    #[test]
+    fn parse_json() {
+        let path = Path::from_trajectory(fake_path()).unwrap();
+
+        assert_eq!(path.samples.len(), 2);
+    }
+
+    #[test]
+    fn waypoints_parse_correctly() {
+        let path = Path::from_trajectory(fake_path()).unwrap();
+
+        let waypoints = path.waypoints();
+
+        assert_eq!(waypoints.len(), 2);
+        assert_eq!(waypoints[0], 0.0);
+    }
+
+    #[test]
+    fn splits_are_sample_indices() {
+        let path = Path::from_trajectory(dense_path()).unwrap();
+
+        assert_eq!(path.splits(), &[0, 2]);
+        assert_eq!(path.waypoints(), &[0.0, 1.0, 2.0]);
+
+        let segments = path.segments();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].length().get::<second>(), 1.0);
+        assert_eq!(segments[0].waypoints(), &[0.0, 1.0]);
+        assert_eq!(segments[1].samples.len(), 3);
+        assert_eq!(segments[1].waypoints(), &[0.0, 1.0]);
+    }
+
+    #[test]
+    fn duration_is_correct() {
+        let path = Path::from_trajectory(fake_path()).unwrap();
+
+        let duration = path.length();
+
+        assert_eq!(duration.get::<second>(), 1.0);
+    }
+
+    #[test]
+    fn exact_sample_retrieval() {
+        let path = Path::from_trajectory(fake_path()).unwrap();
+
+        let pose = path.get(Time::new::<second>(1.0));
+
+        assert_eq!(pose.x.get::<meter>(), 1.0);
+        assert_eq!(pose.y.get::<meter>(), 2.0);
+    }
+
+    #[test]
+    fn interpolation_midpoint() {
+        let path = Path::from_trajectory(fake_path()).unwrap();
+
+        let pose = path.get(Time::new::<second>(0.5));
+
+        assert!((pose.x.get::<meter>() - 0.5) < 1e-6);
+        assert!((pose.y.get::<meter>() - 1.0) < 1e-6);
+    }
+
+    #[test]
+    fn events_fire_once() {
+        let path = Path::from_trajectory(split_path()).unwrap();
+        let names = |from: f64, to: f64| {
+            path.events_between(Time::new::<second>(from), Time::new::<second>(to))
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(path.events().len(), 2);
+        assert_eq!(path.events()[0].timestamp.get::<second>(), 0.5);
+        assert_eq!(names(0.0, 0.5), Vec::<&str>::new());
+        assert_eq!(names(0.5, 1.0), vec!["intake"]);
+        assert_eq!(names(1.0, 2.0), Vec::<&str>::new());
+        assert_eq!(names(2.0, 2.02), vec!["score"]);
+    }
+
+    #[test]
+    fn segments_restart_at_zero() {
+        let path = Path::from_trajectory(split_path()).unwrap();
+
+        let segments = path.segments();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].length().get::<second>(), 1.0);
+        assert_eq!(segments[0].events()[0].name, "intake");
+        assert_eq!(segments[1].length().get::<second>(), 1.0);
+        assert_eq!(
+            segments[1].get(Time::new::<second>(0.0)).x.get::<meter>(),
+            1.0
+        );
+        assert_eq!(segments[1].events()[0].name, "score");
+        assert_eq!(segments[1].events()[0].timestamp.get::<second>(), 1.0);
+
+        let whole = Path::from_trajectory(fake_path()).unwrap().segments();
+        assert_eq!(whole.len(), 1);
+        assert_eq!(whole[0].samples.len(), 2);
+    }
+
+    #[test]
+    fn differential_samples() {
+        let json = r#"
+        {
+            "name": "differential",
+            "version": 1,
+            "params": { "waypoints": [] },
+            "trajectory": {
+                "sampleType": "Differential",
+                "waypoints": [0.0, 1.0],
+                "splits": [0],
+                "samples": [
+                    { "t": 0.0, "x": 0.0, "y": 0.0, "heading": 1.5707963267948966, "vl": 1.0, "vr": 1.0, "omega": 0.0, "al": 0.0, "ar": 0.0, "fl": 0.0, "fr": 0.0 },
+                    { "t": 1.0, "x": 0.0, "y": 1.0, "heading": 1.5707963267948966, "vl": 1.0, "vr": 3.0, "omega": 1.0, "al": 2.0, "ar": 2.0, "fl": 10.0, "fr": 10.0 }
+                ]
+            },
+            "events": []
+        }
+        "#;
+
+        let path = Path::from_trajectory(json).unwrap();
+        let pose = path.get(Time::new::<second>(1.0));
+        let speeds = path.wheel_speeds(Time::new::<second>(0.5)).unwrap();
+
+        assert!(path.is_differential());
+        assert!(pose.velocity_x.get::<meter_per_second>().abs() < 1e-9);
+        assert!((pose.velocity_y.get::<meter_per_second>() - 2.0).abs() < 1e-9);
+        assert_eq!(speeds.right.get::<meter_per_second>(), 2.0);
+        assert!(path.module_forces(Time::new::<second>(0.5)).is_empty());
+    }
+
+    #[test]
+    fn sample_type_picks_parser() {
+        let json = fake_path().replace(
+            r#""waypoints": [0.0, 1.0],"#,
+            r#""sampleType": "Differential", "waypoints": [0.0, 1.0],"#,
+        );
+
+        let error = Path::from_trajectory(&json).unwrap_err().to_string();
+
+        assert!(error.contains("invalid Differential samples"), "{}", error);
+        assert!(error.contains("missing field `vl`"), "{}", error);
+
+        let json = fake_path().replace(
+            r#""waypoints": [0.0, 1.0],"#,
+            r#""sampleType": "Swerve", "waypoints": [0.0, 1.0],"#,
+        );
+
+        assert!(!Path::from_trajectory(&json).unwrap().is_differential());
+    }
+
+    #[test]
+    fn swerve_module_forces() {
+        let json = fake_path()
+            .replace(r#""omega": 0.0"#, r#""omega": 0.0, "fx": [0, 0, 0, 0], "fy": [0, 0, 0, 0]"#)
+            .replace(r#""omega": 0.5"#, r#""omega": 0.5, "ax": 1.0, "ay": 2.0, "alpha": 0.5, "fx": [4, 4, 4, 4], "fy": [2, 2, 2, 2]"#);
+
+        let path = Path::from_trajectory(&json).unwrap();
+        let forces = path.module_forces(Time::new::<second>(0.5));
+
+        assert!(!path.is_differential());
+        assert!(path.wheel_speeds(Time::new::<second>(0.5)).is_none());
+        assert_eq!(forces.len(), 4);
+        assert_eq!(forces[0].x.get::<newton>(), 2.0);
+        assert_eq!(forces[3].y.get::<newton>(), 1.0);
+    }
+
+    #[test]
+    fn migrates_unversioned() {
+        let json = r#"
+        {
+            "samples": [
+                { "timestamp": 0.0, "x": 0.0, "y": 0.0, "heading": 0.0, "velocityX": 0.0, "velocityY": 0.0, "angularVelocity": 0.0 },
+                { "timestamp": 2.0, "x": 2.0, "y": 0.0, "heading": 0.0, "velocityX": 1.0, "velocityY": 0.0, "angularVelocity": 0.0 }
+            ],
+            "eventMarkers": [
+                { "timestamp": 1.0, "command": { "type": "named", "data": { "name": "intake" } } }
+            ]
+        }
+        "#;
+
+        let path = Path::from_trajectory(json).unwrap();
+
+        assert_eq!(path.length().get::<second>(), 2.0);
+        assert_eq!(path.get(Time::new::<second>(1.0)).x.get::<meter>(), 1.0);
+        assert_eq!(path.events()[0].name, "intake");
+        assert_eq!(path.events()[0].timestamp.get::<second>(), 1.0);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = fake_path().replace(r#""version": 3"#, r#""version": 99"#);
+
+        let error = Path::from_trajectory(&json).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("unsupported trajectory version 99"));
+    }
+}
@@ -1,105 +1,156 @@
+use crate::motion_profile::{MotionProfile, ProfileState};
+
 /// A stateful trapezoidal motion profile that accumulates time internally.
 /// Instead of requiring the caller to provide the absolute time,
 /// you update the profile with the elapsed time (dt) each loop.
+///
+/// The profile can start and end moving, so it can be replanned from wherever the mechanism is
+/// when the goal changes mid-move.
 pub struct TrapezoidalProfile {
-    start: f64,
-    goal: f64,
+    current: ProfileState, // Where the plan starts, in the direction of travel
+    goal: ProfileState,    // Where the plan ends, in the direction of travel
     max_vel: f64,
     max_acc: f64,
-    t_accel: f64, // Time to accelerate (or reach peak velocity in a triangular profile)
-    t_flat: f64,  // Time at constant velocity (zero for a triangular profile)
-    t_total: f64, // Total time for the motion profile
-    sign: f64,    // +1 or -1 depending on the direction
-    elapsed: f64, // Accumulated time since the profile started (in seconds)
+    end_accel: f64,      // Time the acceleration phase ends
+    end_full_speed: f64, // Time the constant velocity phase ends
+    t_total: f64,        // Total time for the motion profile
+    sign: f64,           // +1 or -1 depending on the direction
+    elapsed: f64,        // Accumulated time since the profile started (in seconds)
 }
 
 impl TrapezoidalProfile {
-    /// Create a new trapezoidal profile.
+    /// Create a new trapezoidal profile, starting and ending at rest.
     /// The profile’s timer is set to zero when constructed.
     pub fn new(start: f64, goal: f64, max_vel: f64, max_acc: f64) -> Self {
-        let sign = if goal >= start { 1.0 } else { -1.0 };
-        let distance = (goal - start).abs();
-        // Candidate time to reach max velocity
-        let t_accel_candidate = max_vel / max_acc;
-        // Distance covered during acceleration (or deceleration)
-        let d_accel = 0.5 * max_acc * t_accel_candidate * t_accel_candidate;
-        let (t_accel, t_flat, t_total) = if distance < 2.0 * d_accel {
-            // Triangular profile: never reach max_vel
-            let t_accel = (distance / max_acc).sqrt();
-            let t_total = 2.0 * t_accel;
-            (t_accel, 0.0, t_total)
-        } else {
-            // Trapezoidal profile: accelerate, cruise, then decelerate
-            let t_flat = (distance - 2.0 * d_accel) / max_vel;
-            let t_total = 2.0 * t_accel_candidate + t_flat;
-            (t_accel_candidate, t_flat, t_total)
-        };
+        Self::from_states(
+            ProfileState::at_rest(start),
+            ProfileState::at_rest(goal),
+            max_vel,
+            max_acc,
+        )
+    }
 
-        Self {
-            start,
+    /// Plan from `current` to arrive at `goal` with its velocity.
+    /// A starting velocity over `max_vel` is clamped.
+    pub fn from_states(
+        current: ProfileState,
+        goal: ProfileState,
+        max_vel: f64,
+        max_acc: f64,
+    ) -> Self {
+        let mut profile = Self {
+            current,
             goal,
             max_vel,
             max_acc,
-            t_accel,
-            t_flat,
-            t_total,
-            sign,
-            elapsed: 0.0,
-        }
+            end_accel: 0.,
+            end_full_speed: 0.,
+            t_total: 0.,
+            sign: 1.,
+            elapsed: 0.,
+        };
+        profile.replan(current, goal);
+        profile
     }
 
     /// Update the profile by the elapsed time `dt` (in seconds).
-    /// This increments the internal timer and returns the current setpoint (position, velocity).
-    pub fn update(&mut self, dt: f64) -> (f64, f64) {
+    /// This increments the internal timer and returns the current setpoint.
+    pub fn update(&mut self, dt: f64) -> ProfileState {
         self.elapsed += dt;
-        let pos = self.position_at(self.elapsed);
-        let vel = self.velocity_at(self.elapsed);
-        (pos, vel)
+        self.state_at(self.elapsed)
     }
 
-    /// Compute the desired position at time `t` (in seconds).
-    pub fn position_at(&self, t: f64) -> f64 {
-        if t <= 0.0 {
-            return self.start;
-        } else if t >= self.t_total {
-            return self.goal;
-        }
-        if t < self.t_accel {
-            // Acceleration phase: x = 0.5 * a * t^2
-            let pos = 0.5 * self.max_acc * t * t;
-            self.start + self.sign * pos
-        } else if t < self.t_accel + self.t_flat {
-            // Constant velocity phase
-            let pos_accel = 0.5 * self.max_acc * self.t_accel * self.t_accel;
-            let pos_const = self.max_vel * (t - self.t_accel);
-            self.start + self.sign * (pos_accel + pos_const)
+    /// Seconds since the profile was planned
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+}
+
+impl MotionProfile for TrapezoidalProfile {
+    fn replan(&mut self, current: ProfileState, goal: ProfileState) {
+        let sign = if goal.position >= current.position {
+            1.0
         } else {
-            // Deceleration phase
-            let t_dec = t - self.t_accel - self.t_flat;
-            let pos_accel = 0.5 * self.max_acc * self.t_accel * self.t_accel;
-            let pos_const = self.max_vel * self.t_flat;
-            let pos_decel = self.max_vel * t_dec - 0.5 * self.max_acc * t_dec * t_dec;
-            self.start + self.sign * (pos_accel + pos_const + pos_decel)
+            -1.0
+        };
+        let direct =
+            |state: ProfileState| ProfileState::new(state.position * sign, state.velocity * sign);
+        let mut current = direct(current);
+        let goal = direct(goal);
+        current.velocity = current.velocity.min(self.max_vel);
+
+        // Extend the trapezoid back to where it would have started at rest, and forward to
+        // where it would end at rest
+        let cutoff_begin = current.velocity / self.max_acc;
+        let cutoff_dist_begin = cutoff_begin * cutoff_begin * self.max_acc / 2.0;
+        let cutoff_end = goal.velocity / self.max_acc;
+        let cutoff_dist_end = cutoff_end * cutoff_end * self.max_acc / 2.0;
+        let full_trapezoid_dist =
+            cutoff_dist_begin + (goal.position - current.position) + cutoff_dist_end;
+
+        let mut accel_time = self.max_vel / self.max_acc;
+        let mut full_speed_dist = full_trapezoid_dist - accel_time * accel_time * self.max_acc;
+        if full_speed_dist < 0.0 {
+            // Triangular profile: never reach max_vel
+            accel_time = (full_trapezoid_dist / self.max_acc).sqrt();
+            full_speed_dist = 0.0;
         }
+
+        self.end_accel = accel_time - cutoff_begin;
+        self.end_full_speed = self.end_accel + full_speed_dist / self.max_vel;
+        self.t_total = self.end_full_speed + accel_time - cutoff_end;
+        self.current = current;
+        self.goal = goal;
+        self.sign = sign;
+        self.elapsed = 0.0;
     }
 
-    /// Compute the desired velocity at time `t` (in seconds).
-    pub fn velocity_at(&self, t: f64) -> f64 {
-        if t <= 0.0 || t >= self.t_total {
-            return 0.0;
-        }
-        if t < self.t_accel {
-            self.max_acc * t * self.sign
-        } else if t < self.t_accel + self.t_flat {
-            self.max_vel * self.sign
+    fn state_at(&self, t: f64) -> ProfileState {
+        let (current, goal, acc) = (self.current, self.goal, self.max_acc);
+
+        let state = if t <= 0.0 {
+            current
+        } else if t < self.end_accel {
+            // Acceleration phase
+            ProfileState {
+                position: current.position + (current.velocity + t * acc / 2.0) * t,
+                velocity: current.velocity + t * acc,
+                acceleration: acc,
+            }
+        } else if t < self.end_full_speed {
+            // Constant velocity phase
+            let accel_dist = (current.velocity + self.end_accel * acc / 2.0) * self.end_accel;
+            ProfileState {
+                position: current.position + accel_dist + self.max_vel * (t - self.end_accel),
+                velocity: self.max_vel,
+                acceleration: 0.0,
+            }
+        } else if t < self.t_total {
+            // Deceleration phase, measured back from the end
+            let t_left = self.t_total - t;
+            ProfileState {
+                position: goal.position - (goal.velocity + t_left * acc / 2.0) * t_left,
+                velocity: goal.velocity + t_left * acc,
+                acceleration: -acc,
+            }
         } else {
-            let t_dec = t - self.t_accel - self.t_flat;
-            (self.max_vel - self.max_acc * t_dec) * self.sign
+            goal
+        };
+
+        ProfileState {
+            position: state.position * self.sign,
+            velocity: state.velocity * self.sign,
+            acceleration: state.acceleration * self.sign,
         }
     }
+
+    fn total_time(&self) -> f64 {
+        self.t_total
+    }
 }
 
 /// A simple PID controller for demonstration purposes.
+/// Use [`crate::pid::PIDController`] on a real mechanism.
 pub struct PID {
     k_p: f64,
     k_i: f64,
@@ -164,3 +215,41 @@ fn trapezoid() {
         t += dt;
     }
 }
+
+#[test]
+fn trapezoid_replans_mid_move() {
+    let mut profile = TrapezoidalProfile::new(0., 10., 2., 1.);
+    let mut state = ProfileState::default();
+    for _ in 0..150 {
+        state = profile.update(0.02);
+    }
+    assert_eq!(state.velocity, 2.);
+
+    // turn back while moving forward at full speed
+    profile.replan(state, ProfileState::at_rest(0.));
+    let next = profile.update(0.02);
+    assert!((next.velocity - (2. - 0.02)).abs() < 1e-9);
+    assert!((next.position - state.position).abs() < 0.05);
+    assert_eq!(next.acceleration, -1.);
+
+    let end = profile.state_at(profile.total_time());
+    assert_eq!(end, ProfileState::at_rest(0.));
+    assert!(!profile.is_finished(0.));
+    assert!(profile.is_finished(profile.total_time()));
+}
+
+#[test]
+fn trapezoid_ends_moving() {
+    let profile = TrapezoidalProfile::from_states(
+        ProfileState::new(0., 1.),
+        ProfileState::new(5., 1.),
+        2.,
+        1.,
+    );
+
+    // 1 to 2 takes 1.5m, cruise for 2m, then back to 1 in 1.5m
+    assert!((profile.total_time() - 3.).abs() < 1e-9);
+    assert!((profile.position_at(1.) - 1.5).abs() < 1e-9);
+    assert!((profile.velocity_at(2.5) - 1.5).abs() < 1e-9);
+    assert_eq!(profile.velocity_at(profile.total_time()), 1.);
+}
@@ -0,0 +1,182 @@
+use std::time::{Duration, Instant};
+
+use crate::telemetry::Telemetry;
+
+/// Minimum time between overrun messages, so a loop that overruns every cycle doesn't flood stderr
+const MIN_PRINT_PERIOD: Duration = Duration::from_secs(1);
+
+/// Times the phases of a loop and reports when the loop runs over its budget.
+///
+/// Like WPILib's `Watchdog` and `Tracer` combined. Call [`Watchdog::reset`] at the start of each
+/// loop, [`Watchdog::add_epoch`] after each phase, and [`Watchdog::report`] at the end.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use frcrs::watchdog::Watchdog;
+///
+/// let mut watchdog = Watchdog::new(Duration::from_millis(20));
+///
+/// watchdog.reset();
+/// // read sensors
+/// watchdog.add_epoch("sensors");
+/// // run control loops
+/// watchdog.add_epoch("control");
+///
+/// assert_eq!(watchdog.epochs().len(), 2);
+/// assert!(!watchdog.is_expired());
+/// ```
+pub struct Watchdog {
+    timeout: Duration,
+    start: Instant,
+    last_epoch: Instant,
+    epochs: Vec<(&'static str, Duration)>,
+    overruns: u64,
+    last_print: Option<Instant>,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        let now = Instant::now();
+
+        Self {
+            timeout,
+            start: now,
+            last_epoch: now,
+            epochs: Vec::new(),
+            overruns: 0,
+            last_print: None,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Start timing a new loop
+    pub fn reset(&mut self) {
+        self.start = Instant::now();
+        self.last_epoch = self.start;
+        self.epochs.clear();
+    }
+
+    /// Record the time since the previous epoch, or since the reset for the first one
+    pub fn add_epoch(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.epochs.push((name, now - self.last_epoch));
+        self.last_epoch = now;
+    }
+
+    /// Epochs recorded since the last reset, in order
+    pub fn epochs(&self) -> &[(&'static str, Duration)] {
+        &self.epochs
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.elapsed() > self.timeout
+    }
+
+    /// Number of loops that have overrun since creation
+    pub fn overruns(&self) -> u64 {
+        self.overruns
+    }
+
+    /// If the loop overran, publish the loop time and the time of every epoch, and print them at
+    /// most once per second.
+    ///
+    /// Publishing is timed as a final `telemetry` epoch, so an overrun caused by it shows up in
+    /// the breakdown.
+    ///
+    /// Returns whether the loop overran.
+    pub async fn report(&mut self) -> bool {
+        let elapsed = self.elapsed();
+        if elapsed <= self.timeout {
+            return false;
+        }
+
+        self.overruns += 1;
+        Telemetry::put_number("loop/overruns", self.overruns as f64).await;
+        Telemetry::put_number("loop/time_ms", millis(elapsed)).await;
+
+        for (name, duration) in &self.epochs {
+            Telemetry::put_number(&format!("loop/{}_ms", name), millis(*duration)).await;
+        }
+
+        self.add_epoch("telemetry");
+        let (_, publish) = self.epochs[self.epochs.len() - 1];
+        Telemetry::put_number("loop/telemetry_ms", millis(publish)).await;
+
+        let now = Instant::now();
+        if self
+            .last_print
+            .is_some_and(|last| now - last < MIN_PRINT_PERIOD)
+        {
+            return true;
+        }
+        self.last_print = Some(now);
+
+        let mut breakdown = String::new();
+        for (name, duration) in &self.epochs {
+            breakdown.push_str(&format!("\n\t{}: {:.3}ms", name, millis(*duration)));
+        }
+
+        eprintln!(
+            "Loop time of {:.3}ms overran {:.3}ms{}",
+            millis(elapsed),
+            millis(self.timeout),
+            breakdown
+        );
+
+        true
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_overrun() {
+        let mut watchdog = Watchdog::new(Duration::from_millis(5));
+
+        watchdog.reset();
+        watchdog.add_epoch("fast");
+        std::thread::sleep(Duration::from_millis(10));
+        watchdog.add_epoch("slow");
+
+        assert!(watchdog.is_expired());
+        assert!(watchdog.epochs()[1].1 >= Duration::from_millis(10));
+        assert!(watchdog.report().await);
+        assert_eq!(watchdog.overruns(), 1);
+        assert!(Telemetry::get("loop/slow_ms").await.is_some());
+        assert!(Telemetry::get("loop/time_ms").await.is_some());
+        assert!(Telemetry::get("loop/telemetry_ms").await.is_some());
+        assert_eq!(watchdog.epochs().last().unwrap().0, "telemetry");
+        let printed = watchdog.last_print;
+        assert!(printed.is_some());
+
+        // a second overrun right away still counts, but isn't printed again
+        watchdog.reset();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(watchdog.report().await);
+        assert_eq!(watchdog.overruns(), 2);
+        assert_eq!(watchdog.last_print, printed);
+
+        watchdog.reset();
+        assert!(!watchdog.report().await);
+        assert_eq!(watchdog.overruns(), 2);
+    }
+}